@@ -0,0 +1,55 @@
+// Not every bench uses every auto.
+#![allow(dead_code)]
+
+use clairvoyance::geometry::{Pose2d, Rotation2d, Translation2d};
+
+pub fn pose(x: f64, y: f64, degrees: f64) -> Pose2d {
+    Pose2d::new(Translation2d::new(x, y), Rotation2d::from_degrees(degrees))
+}
+
+/** Drives a couple of meters straight out of the community. */
+pub fn short_auto() -> Vec<Pose2d> {
+    vec![pose(1.8, 4.4, 0.), pose(4.0, 4.4, 0.)]
+}
+
+/** Drives the length of the field and swerves around the charge station. */
+pub fn long_auto() -> Vec<Pose2d> {
+    vec![pose(1.8, 0.5, 0.), pose(8.3, 0.9, 0.), pose(14.7, 4.4, 90.)]
+}
+
+/** Turns around in little more than a robot length. */
+pub fn tight_turn_auto() -> Vec<Pose2d> {
+    vec![pose(2.0, 2.0, 0.), pose(3.0, 2.5, 90.), pose(2.0, 3.0, 180.)]
+}
+
+/** A two piece auto on the 2023 field: score, grab a piece, come back and score. */
+pub fn two_piece_auto() -> Vec<Pose2d> {
+    vec![
+        pose(1.8, 0.5, 0.),
+        pose(6.0, 0.9, 0.),
+        pose(6.5, 2.5, 90.),
+        pose(2.0, 2.5, 180.),
+        pose(1.8, 4.4, 90.),
+        pose(6.0, 4.6, 0.)
+    ]
+}
+
+/** Slaloms down the field through twenty waypoints. */
+pub fn many_waypoint_auto() -> Vec<Pose2d> {
+    (0..20)
+        .map(|i| {
+            let side = if i % 2 == 0 { 1. } else { -1. };
+            pose(1.5 + 0.7 * i as f64, 4.0 + 0.8 * side, -30. * side)
+        })
+        .collect()
+}
+
+pub fn representative_autos() -> Vec<(&'static str, Vec<Pose2d>)> {
+    vec![
+        ("short", short_auto()),
+        ("long", long_auto()),
+        ("tight turn", tight_turn_auto()),
+        ("two piece", two_piece_auto()),
+        ("many waypoints", many_waypoint_auto())
+    ]
+}
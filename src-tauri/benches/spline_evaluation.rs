@@ -0,0 +1,18 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+use clairvoyance::trajectory::spline_helper::quintic_splines_from_waypoints;
+
+mod common;
+
+fn spline_evaluation(c: &mut Criterion) {
+    let splines = quintic_splines_from_waypoints(common::two_piece_auto());
+
+    c.bench_function("get_point", |b| b.iter(|| {
+        for i in 0..=100 {
+            black_box(splines[0].get_point(black_box(i as f64 / 100.)));
+        }
+    }));
+}
+
+criterion_group!(benches, spline_evaluation);
+criterion_main!(benches);
@@ -0,0 +1,55 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+
+use clairvoyance::trajectory::cancellation::CancellationToken;
+use clairvoyance::trajectory::spline_helper::quintic_splines_from_waypoints;
+use clairvoyance::trajectory::spline_parameterizer::parameterize;
+use clairvoyance::trajectory::trajectory_generator::spline_points_from_splines;
+use clairvoyance::trajectory::trajectory_parameterizer::time_parameterize_trajectory;
+
+mod common;
+
+const MAX_VELOCITY: f64 = 4.5;
+const MAX_ACCELERATION: f64 = 3.5;
+
+fn splines(c: &mut Criterion) {
+    let mut group = c.benchmark_group("quintic_splines_from_waypoints");
+    for (name, waypoints) in common::representative_autos() {
+        group.bench_with_input(BenchmarkId::from_parameter(name), &waypoints, |b, waypoints| {
+            b.iter(|| quintic_splines_from_waypoints(black_box(waypoints.clone())))
+        });
+    }
+    group.finish();
+}
+
+fn spline_parameterization(c: &mut Criterion) {
+    let mut group = c.benchmark_group("spline_parameterizer::parameterize");
+    for (name, waypoints) in common::representative_autos() {
+        let splines = quintic_splines_from_waypoints(waypoints);
+        group.bench_with_input(BenchmarkId::from_parameter(name), &splines, |b, splines| {
+            b.iter(|| {
+                for spline in splines {
+                    black_box(parameterize(spline, None, None).unwrap());
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+fn time_parameterization(c: &mut Criterion) {
+    let mut group = c.benchmark_group("time_parameterize_trajectory");
+    for (name, waypoints) in common::representative_autos() {
+        let splines = quintic_splines_from_waypoints(waypoints);
+        let points = spline_points_from_splines(&splines, &CancellationToken::new()).unwrap();
+        group.bench_with_input(BenchmarkId::from_parameter(name), &points, |b, points| {
+            b.iter(|| {
+                time_parameterize_trajectory(
+                    points, Vec::new(), 0., 0., MAX_VELOCITY, MAX_ACCELERATION, false).unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, splines, spline_parameterization, time_parameterization);
+criterion_main!(benches);
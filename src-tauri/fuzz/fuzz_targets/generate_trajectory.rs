@@ -0,0 +1,55 @@
+#![no_main]
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+
+use clairvoyance::geometry::{Pose2d, Rotation2d, Translation2d};
+use clairvoyance::trajectory::TrajectoryConfig;
+use clairvoyance::trajectory::cancellation::CancellationToken;
+use clairvoyance::trajectory::trajectory_generator::try_generate_trajectory;
+
+#[derive(Arbitrary, Debug)]
+struct Input {
+    waypoints: Vec<(f64, f64, f64)>,
+    max_velocity: f64,
+    max_acceleration: f64,
+    start_velocity: f64,
+    end_velocity: f64,
+    reversed: bool
+}
+
+/** Limits inputs to values a user could plausibly type into the editor. */
+fn plausible(value: f64, min: f64, max: f64) -> bool {
+    value.is_finite() && value >= min && value <= max
+}
+
+fuzz_target!(|input: Input| {
+    if input.waypoints.len() > 32
+        || !input.waypoints.iter().all(|&(x, y, degrees)|
+            plausible(x, -100., 100.) && plausible(y, -100., 100.) && plausible(degrees, -720., 720.))
+        || !plausible(input.max_velocity, 1E-3, 100.)
+        || !plausible(input.max_acceleration, 1E-3, 100.)
+        || !plausible(input.start_velocity, 0., input.max_velocity)
+        || !plausible(input.end_velocity, 0., input.max_velocity) {
+        return;
+    }
+
+    let waypoints = input.waypoints.iter()
+        .map(|&(x, y, degrees)| Pose2d::new(Translation2d::new(x, y), Rotation2d::from_degrees(degrees)))
+        .collect();
+    let mut config = TrajectoryConfig::new(input.max_velocity, input.max_acceleration);
+    config.start_velocity = input.start_velocity;
+    config.end_velocity = input.end_velocity;
+    config.reversed = input.reversed;
+
+    // Generation is allowed to fail, but not to panic or produce garbage.
+    if let Ok(trajectory) = try_generate_trajectory(waypoints, config, &CancellationToken::new()) {
+        assert!(trajectory.total_time().is_finite());
+        for state in trajectory.states() {
+            assert!(state.t.is_finite() && state.velocity.is_finite() && state.acceleration.is_finite());
+        }
+        for pair in trajectory.states().windows(2) {
+            assert!(pair[1].t >= pair[0].t);
+        }
+    }
+});
@@ -0,0 +1,317 @@
+use rayon::prelude::*;
+use serde::{Serialize, Deserialize};
+
+use crate::collision::{check_collisions, Collision};
+use crate::field::Field;
+use crate::geometry::{Pose2d, Translation2d, Rotation2d};
+use crate::kinematics::WheelStates;
+use crate::robot::RobotConfig;
+use crate::simulation::controllers::angle_modulus;
+use crate::trajectory::{Trajectory, TrajectoryConfig};
+use crate::trajectory::cancellation::CancellationToken;
+use crate::trajectory::report::TrajectoryReport;
+use crate::trajectory::trajectory_generator::{try_generate_trajectory, GenerationError};
+use crate::vision::{tag_visibility, TagVisibility};
+
+/**
+ * A single waypoint of a path as stored by the editor. Headings and
+ * orientations are in degrees.
+ */
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct Waypoint {
+    pub x: f64,
+    pub y: f64,
+    /** Heading of the path at this waypoint in degrees */
+    pub th: Option<f64>,
+    /** Orientation of the robot at this waypoint in degrees */
+    pub psi: Option<f64>,
+    #[serde(default)]
+    pub stop: bool,
+    #[serde(default)]
+    pub hidden: bool
+}
+impl Waypoint {
+    pub fn to_pose(&self) -> Pose2d {
+        Pose2d::new(
+            Translation2d::new(self.x, self.y),
+            Rotation2d::from_degrees(self.th.unwrap_or(0.))
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoConfig {
+    pub max_velocity: f64,
+    pub max_acceleration: f64,
+    pub reversed: bool
+}
+
+/**
+ * Per path overrides of the auto config. Any field left unset falls back to
+ * the value in the auto config.
+ */
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PathConfig {
+    pub start_velocity: Option<f64>,
+    pub end_velocity: Option<f64>,
+    pub max_velocity: Option<f64>,
+    pub max_acceleration: Option<f64>,
+    pub reversed: Option<bool>,
+    /**
+     * Id of an AprilTag a swerve drive keeps its camera facing along the
+     * path. Only the tag visibility analysis turns the robot towards it, the
+     * exported, simulated, replayed and deployed trajectories still follow
+     * the waypoints' orientations.
+     */
+    pub face_tag: Option<u32>
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AutoPath {
+    pub waypoints: Vec<Waypoint>,
+    #[serde(default)]
+    pub config: PathConfig,
+    #[serde(default)]
+    pub hidden: bool
+}
+
+/**
+ * An autonomous routine made up of one or more paths that are driven one
+ * after another.
+ */
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Auto {
+    pub title: String,
+    pub paths: Vec<AutoPath>,
+    pub config: AutoConfig,
+    /** The robot driving the auto, which may constrain its paths further */
+    #[serde(default)]
+    pub robot: RobotConfig
+}
+impl Auto {
+    /**
+     * Merges the auto config with the config of the path at the given index,
+     * adding the constraints of the robot's drivetrain.
+     */
+    pub fn path_config(&self, index: usize) -> TrajectoryConfig {
+        let path_config = &self.paths[index].config;
+        let mut config = TrajectoryConfig::new(
+            path_config.max_velocity.unwrap_or(self.config.max_velocity),
+            path_config.max_acceleration.unwrap_or(self.config.max_acceleration)
+        );
+        config.start_velocity = path_config.start_velocity.unwrap_or(0.);
+        config.end_velocity = path_config.end_velocity.unwrap_or(0.);
+        config.reversed = path_config.reversed.unwrap_or(self.config.reversed);
+        config.constraints = self.robot.constraints();
+        config
+    }
+}
+
+/**
+ * Which way a swerve drive faces along the trajectory of a path. The robot
+ * turns from the orientation of each waypoint to the next at a steady rate
+ * while it drives between them, the shorter way around. Waypoints without an
+ * orientation keep the one before them, and the first one falls back to the
+ * heading the path starts with.
+ */
+#[derive(Clone, Debug)]
+pub struct Headings {
+    /**
+     * When the trajectory passes each waypoint and the orientation there in
+     * radians, unwrapped so that every turn is the shorter way around.
+     */
+    keyframes: Vec<(f64, f64)>
+}
+impl Headings {
+    pub fn new(path: &AutoPath, trajectory: &Trajectory) -> Self {
+        let states = trajectory.states();
+        let first = match states.first() {
+            Some(first) => first,
+            None => return Self::holding(Rotation2d::default())
+        };
+        let mut facing = *first.pose.rotation().radians();
+        let mut from = 0;
+        let keyframes = path.waypoints.iter().enumerate().map(|(i, waypoint)| {
+            if let Some(psi) = waypoint.psi {
+                let psi = psi.to_radians();
+                facing = if i == 0 { psi } else { facing + angle_modulus(psi - facing) };
+            }
+            // Waypoints are where the splines join, so the trajectory passes
+            // right through them. The closest state is only a fallback.
+            let target = Translation2d::new(waypoint.x, waypoint.y);
+            let distance = |j: &usize| states[*j].pose.translation().distance_to(&target);
+            from = (from..states.len()).find(|j| distance(j) < 1E-6)
+                .or_else(|| (from..states.len()).min_by(|a, b| distance(a).total_cmp(&distance(b))))
+                .unwrap_or(from);
+            (states[from].t, facing)
+        }).collect();
+        Self { keyframes }
+    }
+
+    /**
+     * Holds the same heading the whole way.
+     */
+    pub fn holding(heading: Rotation2d) -> Self {
+        Self { keyframes: vec![(0., *heading.radians())] }
+    }
+
+    /**
+     * Which way the robot faces at a time since the start of the trajectory.
+     */
+    pub fn at(&self, t: f64) -> Rotation2d {
+        let next = self.keyframes.partition_point(|&(time, _)| time <= t);
+        let radians = match (next.checked_sub(1).map(|i| self.keyframes[i]), self.keyframes.get(next)) {
+            (Some((start_time, start)), Some(&(end_time, end))) =>
+                start + (end - start) * (t - start_time) / (end_time - start_time),
+            (Some((_, heading)), None) | (None, Some(&(_, heading))) => heading,
+            (None, None) => 0.
+        };
+        Rotation2d::from_radians(radians)
+    }
+
+    /**
+     * How fast the robot turns at a time since the start of the trajectory,
+     * in radians per second counterclockwise.
+     */
+    pub fn angular_velocity(&self, t: f64) -> f64 {
+        let next = self.keyframes.partition_point(|&(time, _)| time <= t);
+        match (next.checked_sub(1).map(|i| self.keyframes[i]), self.keyframes.get(next)) {
+            (Some((start_time, start)), Some(&(end_time, end))) => (end - start) / (end_time - start_time),
+            _ => 0.
+        }
+    }
+}
+
+/**
+ * When a path starts and ends relative to the start of the auto.
+ */
+#[derive(Clone, Copy, Debug, Serialize)]
+pub struct PathTiming {
+    pub start_time: f64,
+    pub end_time: f64
+}
+
+#[derive(Serialize, Debug)]
+pub struct AutoTrajectory {
+    paths: Vec<Trajectory>,
+    /** What the wheels do at every state of every path */
+    wheel_states: Vec<Vec<WheelStates>>,
+    /** Where the robot first hits a field element on every path, if it does */
+    collisions: Vec<Option<Collision>>,
+    /** Which AprilTags the robot's camera sees at every state of every path */
+    tag_visibility: Vec<Vec<TagVisibility>>,
+    /** Which way a swerve drive faces along every path */
+    #[serde(skip)]
+    headings: Vec<Headings>,
+    /** Statistics about every path and what limits it */
+    reports: Vec<TrajectoryReport>,
+    timeline: Vec<PathTiming>,
+    total_time: f64
+}
+impl AutoTrajectory {
+    pub fn new(paths: Vec<Trajectory>, auto: &Auto, field: &Field) -> Self {
+        let robot = &auto.robot;
+        let mut timeline = Vec::<PathTiming>::with_capacity(paths.len());
+        let mut t = 0.;
+        for path in &paths {
+            timeline.push(PathTiming { start_time: t, end_time: t + path.total_time() });
+            t += path.total_time();
+        }
+        let headings: Vec<Headings> = paths.iter().zip(&auto.paths)
+            .map(|(path, auto_path)| Headings::new(auto_path, path))
+            .collect();
+        let wheel_states = paths.iter().zip(&headings).map(|(path, headings)| robot.wheel_states(path, headings)).collect();
+        let collisions = paths.iter().zip(&headings)
+            .map(|(path, headings)| check_collisions(path, robot, headings, field))
+            .collect();
+        let tag_visibility = paths.iter().zip(&auto.paths).zip(&headings)
+            .map(|((path, auto_path), headings)| tag_visibility(path, robot, &field.april_tags, auto_path.config.face_tag, headings))
+            .collect();
+        let reports = paths.iter().enumerate()
+            .map(|(i, path)| TrajectoryReport::new(path, &auto.path_config(i)))
+            .collect();
+        Self { paths, wheel_states, collisions, tag_visibility, headings, reports, timeline, total_time: t }
+    }
+    pub const fn paths(&self) -> &Vec<Trajectory> {
+        &self.paths
+    }
+    pub const fn wheel_states(&self) -> &Vec<Vec<WheelStates>> {
+        &self.wheel_states
+    }
+    pub const fn collisions(&self) -> &Vec<Option<Collision>> {
+        &self.collisions
+    }
+    pub const fn tag_visibility(&self) -> &Vec<Vec<TagVisibility>> {
+        &self.tag_visibility
+    }
+    pub const fn headings(&self) -> &Vec<Headings> {
+        &self.headings
+    }
+    pub const fn reports(&self) -> &Vec<TrajectoryReport> {
+        &self.reports
+    }
+    pub const fn timeline(&self) -> &Vec<PathTiming> {
+        &self.timeline
+    }
+    pub const fn total_time(&self) -> &f64 {
+        &self.total_time
+    }
+}
+
+/**
+ * Generates the trajectories for every path of an auto. Paths are generated
+ * in parallel, but are returned in the same order as they appear in the auto.
+ * Paths with fewer than two waypoints result in a do nothing trajectory.
+ *
+ * @param auto The auto to generate.
+ * @return The trajectory of every path along with the overall timeline.
+ */
+pub fn generate_auto(auto: &Auto) -> AutoTrajectory {
+    try_generate_auto(auto, &CancellationToken::new())
+        .expect("generation can only be cancelled through its token")
+}
+
+/**
+ * Generates the trajectories for every path of an auto, stopping early if the
+ * given token is cancelled. Paths that fail to generate for any other reason
+ * result in a do nothing trajectory so the rest of the auto can still be shown.
+ *
+ * @param auto   The auto to generate.
+ * @param cancel Token checked between the generation steps of every path.
+ * @return The trajectory of every path along with the overall timeline.
+ */
+pub fn try_generate_auto(auto: &Auto, cancel: &CancellationToken) -> Result<AutoTrajectory, GenerationError> {
+    try_generate_auto_on(auto, Field::current(), cancel)
+}
+
+/**
+ * Generates the trajectories for every path of an auto like
+ * try_generate_auto, checking them against the given field instead of the
+ * current season's, like one with a different AprilTag layout.
+ */
+pub fn try_generate_auto_on(auto: &Auto, field: &Field, cancel: &CancellationToken) -> Result<AutoTrajectory, GenerationError> {
+    let paths = (0..auto.paths.len())
+        .into_par_iter()
+        .map(|i| {
+            let waypoints = &auto.paths[i].waypoints;
+            if waypoints.len() < 2 {
+                return Ok(Trajectory::do_nothing());
+            }
+            let poses = waypoints.iter().map(Waypoint::to_pose).collect();
+            match try_generate_trajectory(poses, auto.path_config(i), cancel) {
+                Err(GenerationError::Cancelled(err)) => Err(GenerationError::Cancelled(err)),
+                Err(err) => {
+                    println!("{}", err);
+                    println!("Returning do nothing trajectory for path {}.", i);
+                    Ok(Trajectory::do_nothing())
+                },
+                ok => ok
+            }
+        })
+        .collect::<Result<Vec<Trajectory>, GenerationError>>()?;
+    Ok(AutoTrajectory::new(paths, auto, field))
+}
+
+pub mod schema;
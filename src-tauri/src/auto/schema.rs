@@ -0,0 +1,270 @@
+use std::fmt;
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+
+use super::{Auto, AutoConfig, AutoPath, PathConfig, Waypoint};
+use crate::robot::{DrivetrainType, RobotConfig};
+
+/**
+ * The newest version of the auto file layout. Bump it and add a migration
+ * whenever the layout changes, so older files are upgraded as they're read
+ * and older builds refuse files they would misread.
+ */
+pub const AUTO_SCHEMA_VERSION: u32 = 1;
+
+/**
+ * Upgrades an auto from the version at its index to the next one, so files
+ * of any older version can be read by running every migration after it.
+ */
+const MIGRATIONS: [fn(&mut Map<String, Value>); AUTO_SCHEMA_VERSION as usize] = [
+    from_unversioned
+];
+
+/**
+ * Something wrong with an auto, and where in the file it is.
+ */
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ValidationError {
+    /** Where the problem is, like paths[0].waypoints[2].x */
+    pub location: String,
+    pub message: String
+}
+impl ValidationError {
+    fn new(location: impl Into<String>, message: impl Into<String>) -> Self {
+        Self { location: location.into(), message: message.into() }
+    }
+}
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.location, self.message)
+    }
+}
+
+#[derive(Debug)]
+pub enum SchemaError {
+    /** The file isn't JSON at all */
+    Parse(serde_json::Error),
+    UnsupportedVersion(u32),
+    /** The file is JSON but isn't a valid auto, with every problem found */
+    Invalid(Vec<ValidationError>)
+}
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SchemaError::Parse(err) => write!(f, "The auto isn't valid JSON: {}", err),
+            SchemaError::UnsupportedVersion(version) => write!(f, "The auto is version {}, but only versions up to {} are supported. Try updating the app.", version, AUTO_SCHEMA_VERSION),
+            SchemaError::Invalid(errors) => {
+                write!(f, "The auto is invalid:")?;
+                for error in errors {
+                    write!(f, "\n{}", error)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+impl From<serde_json::Error> for SchemaError {
+    fn from(err: serde_json::Error) -> Self {
+        SchemaError::Parse(err)
+    }
+}
+
+/**
+ * Reads an auto from JSON of any version, upgrading it to the current layout
+ * and checking that it can be generated.
+ */
+pub fn parse_auto(json: &str) -> Result<Auto, SchemaError> {
+    let mut value: Value = serde_json::from_str(json)?;
+    let object = value.as_object_mut()
+        .ok_or_else(|| SchemaError::Invalid(vec![ValidationError::new("auto", "has to be an object")]))?;
+    let version = match object.remove("version") {
+        // Autos exported before files were versioned
+        None => 0,
+        Some(version) => version.as_u64().and_then(|version| u32::try_from(version).ok())
+            .ok_or_else(|| SchemaError::Invalid(vec![ValidationError::new("version", "has to be a whole number")]))?
+    };
+    if version > AUTO_SCHEMA_VERSION {
+        return Err(SchemaError::UnsupportedVersion(version));
+    }
+    for migrate in &MIGRATIONS[version as usize..] {
+        migrate(object);
+    }
+
+    let mut errors = Vec::new();
+    let auto = read_auto(object, &mut errors);
+    if let Some(auto) = &auto {
+        errors.extend(validate(auto));
+    }
+    match auto {
+        Some(auto) if errors.is_empty() => Ok(auto),
+        _ => Err(SchemaError::Invalid(errors))
+    }
+}
+
+/**
+ * Writes an auto as JSON in the current layout.
+ */
+pub fn to_json(auto: &Auto) -> Result<String, serde_json::Error> {
+    #[derive(Serialize)]
+    struct Versioned<'a> {
+        version: u32,
+        #[serde(flatten)]
+        auto: &'a Auto
+    }
+    serde_json::to_string_pretty(&Versioned { version: AUTO_SCHEMA_VERSION, auto })
+}
+
+/**
+ * Version 0 is what the web app exported before files were versioned. Every
+ * path carries what was generated for it, which is regenerated on load
+ * anyways.
+ */
+fn from_unversioned(auto: &mut Map<String, Value>) {
+    if let Some(Value::Array(paths)) = auto.get_mut("paths") {
+        for path in paths.iter_mut().filter_map(Value::as_object_mut) {
+            for generated in ["path", "wheelStates", "collision", "simulated", "tagVisibility"] {
+                path.remove(generated);
+            }
+        }
+    }
+}
+
+/**
+ * Reads the auto a piece at a time, so every malformed piece is reported with
+ * where it is instead of only the first.
+ */
+fn read_auto(object: &mut Map<String, Value>, errors: &mut Vec<ValidationError>) -> Option<Auto> {
+    let title = read_required::<String>(object, "title", "title", errors);
+    let config = read_required::<AutoConfig>(object, "config", "config", errors);
+    let robot = read_optional::<RobotConfig>(object, "robot", "robot", errors);
+    let paths = match object.remove("paths") {
+        Some(Value::Array(paths)) => Some(paths.into_iter().enumerate()
+            .filter_map(|(i, path)| read_path(path, &format!("paths[{}]", i), errors))
+            .collect::<Vec<_>>()),
+        Some(_) => {
+            errors.push(ValidationError::new("paths", "has to be a list"));
+            None
+        },
+        None => {
+            errors.push(ValidationError::new("paths", "is missing"));
+            None
+        }
+    };
+    if !errors.is_empty() {
+        return None;
+    }
+    Some(Auto { title: title?, paths: paths?, config: config?, robot: robot? })
+}
+
+fn read_path(path: Value, location: &str, errors: &mut Vec<ValidationError>) -> Option<AutoPath> {
+    let mut object = match path {
+        Value::Object(object) => object,
+        _ => {
+            errors.push(ValidationError::new(location, "has to be an object"));
+            return None;
+        }
+    };
+    let config = read_optional::<PathConfig>(&mut object, "config", &format!("{}.config", location), errors);
+    let hidden = read_optional::<bool>(&mut object, "hidden", &format!("{}.hidden", location), errors);
+    let waypoints = match object.remove("waypoints") {
+        Some(Value::Array(waypoints)) => Some(waypoints.into_iter().enumerate()
+            .filter_map(|(i, waypoint)| read(waypoint, &format!("{}.waypoints[{}]", location, i), errors))
+            .collect::<Vec<Waypoint>>()),
+        Some(_) => {
+            errors.push(ValidationError::new(format!("{}.waypoints", location), "has to be a list"));
+            None
+        },
+        None => {
+            errors.push(ValidationError::new(format!("{}.waypoints", location), "is missing"));
+            None
+        }
+    };
+    Some(AutoPath { waypoints: waypoints?, config: config?, hidden: hidden? })
+}
+
+fn read<T: DeserializeOwned>(value: Value, location: &str, errors: &mut Vec<ValidationError>) -> Option<T> {
+    serde_json::from_value(value)
+        .map_err(|err| errors.push(ValidationError::new(location, err.to_string())))
+        .ok()
+}
+
+fn read_required<T: DeserializeOwned>(object: &mut Map<String, Value>, key: &str, location: &str, errors: &mut Vec<ValidationError>) -> Option<T> {
+    match object.remove(key) {
+        Some(value) => read(value, location, errors),
+        None => {
+            errors.push(ValidationError::new(location, "is missing"));
+            None
+        }
+    }
+}
+
+/**
+ * Reads a field that falls back to its default when it's missing or null.
+ */
+fn read_optional<T: DeserializeOwned + Default>(object: &mut Map<String, Value>, key: &str, location: &str, errors: &mut Vec<ValidationError>) -> Option<T> {
+    match object.remove(key) {
+        None | Some(Value::Null) => Some(T::default()),
+        Some(value) => read(value, location, errors)
+    }
+}
+
+/**
+ * Checks that an auto makes sense beyond being well formed, returning every
+ * problem found.
+ */
+pub fn validate(auto: &Auto) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+    if auto.title.trim().is_empty() {
+        errors.push(ValidationError::new("title", "can't be empty"));
+    }
+    if auto.paths.is_empty() {
+        errors.push(ValidationError::new("paths", "has to have at least one path"));
+    }
+
+    let mut limits = vec![
+        ("config.maxVelocity".to_string(), Some(auto.config.max_velocity)),
+        ("config.maxAcceleration".to_string(), Some(auto.config.max_acceleration)),
+        ("robot.width".to_string(), Some(auto.robot.width)),
+        ("robot.length".to_string(), Some(auto.robot.length)),
+        ("robot.wheelbase".to_string(), Some(auto.robot.wheelbase)),
+        ("robot.trackWidth".to_string(), Some(auto.robot.track_width)),
+        ("robot.wheelDiameter".to_string(), Some(auto.robot.wheel_diameter)),
+        ("robot.gearRatio".to_string(), Some(auto.robot.gear_ratio))
+    ];
+    if let DrivetrainType::Differential { max_wheel_speed, feedforward, max_voltage } = auto.robot.drivetrain {
+        limits.push(("robot.drivetrain.maxWheelSpeed".to_string(), Some(max_wheel_speed)));
+        // The voltage constraint divides by kA, so without it the constraint would do nothing.
+        if let Some(feedforward) = feedforward {
+            limits.push(("robot.drivetrain.maxVoltage".to_string(), Some(max_voltage)));
+            limits.push(("robot.drivetrain.feedforward.ka".to_string(), Some(feedforward.ka)));
+        }
+    }
+    for (i, path) in auto.paths.iter().enumerate() {
+        limits.push((format!("paths[{}].config.maxVelocity", i), path.config.max_velocity));
+        limits.push((format!("paths[{}].config.maxAcceleration", i), path.config.max_acceleration));
+    }
+    for (location, limit) in limits {
+        if matches!(limit, Some(limit) if !(limit.is_finite() && limit > 0.)) {
+            errors.push(ValidationError::new(location, "has to be greater than 0"));
+        }
+    }
+
+    for (i, path) in auto.paths.iter().enumerate() {
+        for (name, velocity) in [("startVelocity", path.config.start_velocity), ("endVelocity", path.config.end_velocity)] {
+            if matches!(velocity, Some(velocity) if !(velocity.is_finite() && velocity >= 0.)) {
+                errors.push(ValidationError::new(format!("paths[{}].config.{}", i, name), "can't be negative"));
+            }
+        }
+        for (j, waypoint) in path.waypoints.iter().enumerate() {
+            let values = [("x", Some(waypoint.x)), ("y", Some(waypoint.y)), ("th", waypoint.th), ("psi", waypoint.psi)];
+            for (name, value) in values {
+                if matches!(value, Some(value) if !value.is_finite()) {
+                    errors.push(ValidationError::new(format!("paths[{}].waypoints[{}].{}", i, j, name), "has to be a number"));
+                }
+            }
+        }
+    }
+    errors
+}
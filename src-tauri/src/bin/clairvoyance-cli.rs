@@ -0,0 +1,154 @@
+//! Command line access to what the app can export, for scripts and analysis
+//! without opening a window.
+
+use std::path::{Path, PathBuf};
+use std::{env, fs, process};
+
+use clairvoyance::auto::Auto;
+use clairvoyance::auto::schema::parse_auto;
+use clairvoyance::export::csv::{auto_to_csv, write_csv, CsvOptions};
+use clairvoyance::export::motion_profile::{write_motion_profiles, MotionProfileFormat, MotionProfileOptions, PhoenixVersion};
+
+const USAGE: &str = "\
+Usage: clairvoyance-cli csv <auto.json> [options]
+       clairvoyance-cli motion-profile <auto.json> [options]
+
+csv writes the trajectory of an auto as CSV, to standard output unless --output is given.
+
+    -o, --output <file>        File to write the CSV to
+        --dt <seconds>         Resample the trajectory every so many seconds
+        --delimiter <char>     Column delimiter, or tab (default ,)
+        --distance-unit <unit> m, ft or in (default m)
+        --angle-unit <unit>    deg or rad (default deg)
+
+motion-profile writes a motion profile for every drive motor, using the wheel diameter and
+gear ratio of the auto's robot.
+
+    -o, --output <directory>   Directory to write the profiles to (default .)
+        --format <format>      ctre or rev (default ctre)
+        --period <ms>          Time between points in milliseconds (default 10)
+        --phoenix <version>    5 for encoder counts and counts per 100 ms, or 6 for rotations and
+                               rotations per second, for ctre profiles (default 5)
+        --counts-per-rev <n>   Encoder counts per motor rotation for Phoenix 5 (default 2048)
+";
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if let Err(message) = run(&args) {
+        eprintln!("{}", message);
+        process::exit(1);
+    }
+}
+
+fn run(args: &[String]) -> Result<(), String> {
+    match args.first().map(String::as_str) {
+        Some("csv") => csv(&args[1..]),
+        Some("motion-profile") => motion_profile(&args[1..]),
+        Some("-h" | "--help" | "help") => {
+            print!("{}", USAGE);
+            Ok(())
+        },
+        Some(command) => Err(format!("Unknown command \"{}\".\n\n{}", command, USAGE)),
+        None => Err(USAGE.to_string())
+    }
+}
+
+fn csv(args: &[String]) -> Result<(), String> {
+    let mut input = None;
+    let mut output = None;
+    let mut options = CsvOptions::default();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("{} needs a value.", arg));
+        match arg.as_str() {
+            "-o" | "--output" => output = Some(PathBuf::from(value()?)),
+            "--dt" => {
+                let period = value()?;
+                options.sample_period = Some(period.parse().map_err(|_| format!("\"{}\" isn't a number of seconds.", period))?);
+            },
+            "--delimiter" => {
+                let delimiter = value()?;
+                options.delimiter = match delimiter.as_str() {
+                    "tab" | "\\t" => '\t',
+                    _ => {
+                        let mut chars = delimiter.chars();
+                        match (chars.next(), chars.next()) {
+                            (Some(c), None) => c,
+                            _ => return Err(format!("\"{}\" isn't a single character.", delimiter))
+                        }
+                    }
+                };
+            },
+            "--distance-unit" => options.distance_unit = value()?.parse().map_err(|err| format!("{}", err))?,
+            "--angle-unit" => options.angle_unit = value()?.parse().map_err(|err| format!("{}", err))?,
+            _ if arg.starts_with('-') => return Err(format!("Unknown option \"{}\".\n\n{}", arg, USAGE)),
+            _ if input.is_none() => input = Some(PathBuf::from(arg)),
+            _ => return Err(format!("Only one auto can be exported at a time, but found \"{}\" too.", arg))
+        }
+    }
+
+    let input = input.ok_or_else(|| format!("No auto file given.\n\n{}", USAGE))?;
+    let auto = read_auto(&input)?;
+    match output {
+        Some(output) => write_csv(&output, &auto, &options).map_err(|err| format!("Couldn't write {}: {}", output.display(), err)),
+        None => {
+            print!("{}", auto_to_csv(&auto, &options).map_err(|err| err.to_string())?);
+            Ok(())
+        }
+    }
+}
+
+fn motion_profile(args: &[String]) -> Result<(), String> {
+    let mut input = None;
+    let mut output = PathBuf::from(".");
+    let mut options = MotionProfileOptions::default();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("{} needs a value.", arg));
+        match arg.as_str() {
+            "-o" | "--output" => output = PathBuf::from(value()?),
+            "--format" => {
+                options.format = match value()?.as_str() {
+                    "ctre" => MotionProfileFormat::Ctre,
+                    "rev" => MotionProfileFormat::Rev,
+                    format => return Err(format!("\"{}\" isn't a motion profile format, use ctre or rev.", format))
+                };
+            },
+            "--period" => {
+                let period = value()?;
+                options.period_ms = period.parse().map_err(|_| format!("\"{}\" isn't a whole number of milliseconds.", period))?;
+            },
+            "--phoenix" => {
+                options.phoenix_version = match value()?.as_str() {
+                    "5" => PhoenixVersion::Phoenix5,
+                    "6" => PhoenixVersion::Phoenix6,
+                    version => return Err(format!("\"{}\" isn't a Phoenix version, use 5 or 6.", version))
+                };
+            },
+            "--counts-per-rev" => {
+                let counts = value()?;
+                options.counts_per_rev = counts.parse().map_err(|_| format!("\"{}\" isn't a number of counts.", counts))?;
+            },
+            _ if arg.starts_with('-') => return Err(format!("Unknown option \"{}\".\n\n{}", arg, USAGE)),
+            _ if input.is_none() => input = Some(PathBuf::from(arg)),
+            _ => return Err(format!("Only one auto can be exported at a time, but found \"{}\" too.", arg))
+        }
+    }
+
+    let input = input.ok_or_else(|| format!("No auto file given.\n\n{}", USAGE))?;
+    let auto = read_auto(&input)?;
+    let stem = input.file_stem().and_then(|stem| stem.to_str()).unwrap_or("auto");
+    let written = write_motion_profiles(&output, stem, &auto, &options)
+        .map_err(|err| format!("Couldn't write to {}: {}", output.display(), err))?;
+    for name in written {
+        println!("{}", output.join(name).display());
+    }
+    Ok(())
+}
+
+fn read_auto(input: &Path) -> Result<Auto, String> {
+    let json = fs::read_to_string(input).map_err(|err| format!("Couldn't read {}: {}", input.display(), err))?;
+    parse_auto(&json).map_err(|err| format!("{}: {}", input.display(), err))
+}
@@ -0,0 +1,74 @@
+use super::{Case, Template};
+
+pub(super) const TEMPLATE: Template = Template {
+    extension: "h",
+    file_case: Case::UpperCamel,
+    constant_case: Case::UpperCamel,
+    function_case: Case::UpperCamel,
+    file: r#"// Generated by Clairvoyance from "${title}". Changes will be overwritten.
+#pragma once
+
+#include <vector>
+
+#include <frc/controller/SimpleMotorFeedforward.h>
+#include <frc/geometry/Pose2d.h>
+#include <frc/geometry/Rotation2d.h>
+#include <frc/kinematics/DifferentialDriveKinematics.h>
+#include <frc/trajectory/TrajectoryConfig.h>
+#include <frc/trajectory/constraint/DifferentialDriveKinematicsConstraint.h>
+#include <frc/trajectory/constraint/DifferentialDriveVoltageConstraint.h>
+#include <units/acceleration.h>
+#include <units/angle.h>
+#include <units/length.h>
+#include <units/velocity.h>
+#include <units/voltage.h>
+
+namespace ${class} {
+    inline constexpr units::meters_per_second_t kMaxVelocity{${max_velocity}};
+    inline constexpr units::meters_per_second_squared_t kMaxAcceleration{${max_acceleration}};
+    inline constexpr bool kReversed = ${reversed};
+${paths}
+}  // namespace ${class}
+"#,
+    package: "",
+    path: r#"
+    /** Waypoints of ${title}, with the heading of the path at each */
+    inline const std::vector<frc::Pose2d> k${constant}Waypoints{${waypoints}};
+    /** Which way the robot faces at every waypoint of ${title} */
+    inline const std::vector<frc::Rotation2d> k${constant}Rotations{${rotations}};
+    /** Indices of the waypoints of ${title} the robot stops at */
+    inline const std::vector<size_t> k${constant}Stops{${stops}};
+
+    inline frc::TrajectoryConfig ${function}Config() {
+        frc::TrajectoryConfig config{units::meters_per_second_t{${max_velocity}}, units::meters_per_second_squared_t{${max_acceleration}}};${setters}
+        return config;
+    }
+"#,
+    pose: "frc::Pose2d{units::meter_t{${x}}, units::meter_t{${y}}, frc::Rotation2d{units::degree_t{${th}}}}",
+    rotation: "frc::Rotation2d{units::degree_t{${degrees}}}",
+    start_velocity: "\n        config.SetStartVelocity(units::meters_per_second_t{${value}});",
+    end_velocity: "\n        config.SetEndVelocity(units::meters_per_second_t{${value}});",
+    reversed: "\n        config.SetReversed(${value});",
+    kinematics: "\n        config.SetKinematics(${value});",
+    constraint: "\n        config.AddConstraint(${value});",
+    differential_kinematics: "frc::DifferentialDriveKinematics{units::meter_t{${track_width}}}",
+    kinematics_constraint: "frc::DifferentialDriveKinematicsConstraint{${kinematics}, units::meters_per_second_t{${max_wheel_speed}}}",
+    voltage_constraint: "frc::DifferentialDriveVoltageConstraint{frc::SimpleMotorFeedforward<units::meters>{units::volt_t{${ks}}, \
+        units::unit_t<frc::SimpleMotorFeedforward<units::meters>::kv_unit>{${kv}}, \
+        units::unit_t<frc::SimpleMotorFeedforward<units::meters>::ka_unit>{${ka}}}, ${kinematics}, units::volt_t{${max_voltage}}}",
+    true_literal: "true",
+    false_literal: "false",
+    reserved: &[
+        "alignas", "alignof", "and", "and_eq", "asm", "auto", "bitand", "bitor", "bool", "break",
+        "case", "catch", "char", "char8_t", "char16_t", "char32_t", "class", "compl", "concept",
+        "const", "consteval", "constexpr", "constinit", "const_cast", "continue", "co_await",
+        "co_return", "co_yield", "decltype", "default", "delete", "do", "double", "dynamic_cast",
+        "else", "enum", "explicit", "export", "extern", "false", "float", "for", "friend", "goto",
+        "if", "inline", "int", "long", "mutable", "namespace", "new", "noexcept", "not", "not_eq",
+        "nullptr", "operator", "or", "or_eq", "private", "protected", "public", "register",
+        "reinterpret_cast", "requires", "return", "short", "signed", "sizeof", "static",
+        "static_assert", "static_cast", "struct", "switch", "template", "this", "thread_local",
+        "throw", "true", "try", "typedef", "typeid", "typename", "union", "unsigned", "using",
+        "virtual", "void", "volatile", "wchar_t", "while", "xor", "xor_eq"
+    ]
+};
@@ -0,0 +1,275 @@
+use super::tokens::{Token, TokenKind};
+
+/** Types a value can be cast to without changing it for our purposes */
+const NUMBER_TYPES: [&str; 6] = ["double", "float", "int", "long", "short", "byte"];
+
+/**
+ * A Java expression, limited to what's needed to work out paths and configs.
+ */
+#[derive(Clone, Debug)]
+pub(super) enum Expr {
+    Number(f64),
+    Boolean(bool),
+    Null,
+    /** A string or character, which is never part of a path */
+    Text,
+    /** A constant or local, possibly qualified like Constants.kMaxSpeed */
+    Name(String),
+    Unary(char, Box<Expr>),
+    Binary(char, Box<Expr>, Box<Expr>),
+    /** A method call, on a class or value if there's a target */
+    Call {
+        target: Option<Box<Expr>>,
+        name: String,
+        arguments: Vec<Expr>
+    },
+    /** A constructor call, by the class's simple name */
+    New {
+        class: String,
+        arguments: Vec<Expr>
+    },
+    /** An array initializer, with or without new T[] in front */
+    Array(Vec<Expr>)
+}
+impl Expr {
+    /**
+     * Whether the expression names any of the classes, like in a constructor
+     * or a static call.
+     */
+    pub fn mentions(&self, classes: &[&str]) -> bool {
+        match self {
+            Expr::Name(name) => classes.iter().any(|class| name.split('.').any(|part| part == *class)),
+            Expr::Unary(_, operand) => operand.mentions(classes),
+            Expr::Binary(_, left, right) => left.mentions(classes) || right.mentions(classes),
+            Expr::Call { target, arguments, .. } => {
+                matches!(target, Some(target) if target.mentions(classes)) || arguments.iter().any(|argument| argument.mentions(classes))
+            },
+            Expr::New { class, arguments } => classes.contains(&class.as_str()) || arguments.iter().any(|argument| argument.mentions(classes)),
+            Expr::Array(items) => items.iter().any(|item| item.mentions(classes)),
+            Expr::Number(_) | Expr::Boolean(_) | Expr::Null | Expr::Text => false
+        }
+    }
+}
+
+/**
+ * Parses expressions out of a token stream by recursive descent, leaving the
+ * tokens after the expression for the caller.
+ */
+pub(super) struct Parser<'a> {
+    tokens: &'a [Token],
+    position: usize
+}
+impl<'a> Parser<'a> {
+    pub fn new(tokens: &'a [Token], position: usize) -> Self {
+        Self { tokens, position }
+    }
+
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /**
+     * Parses an expression.
+     *
+     * @return The expression, or why it couldn't be parsed.
+     */
+    pub fn expression(&mut self) -> Result<Expr, String> {
+        self.additive()
+    }
+
+    fn peek(&self) -> Option<&'a Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn peek_is(&self, symbol: &str) -> bool {
+        matches!(self.peek(), Some(token) if token.is(symbol))
+    }
+
+    fn eat(&mut self, symbol: &str) -> bool {
+        let matched = self.peek_is(symbol);
+        if matched {
+            self.position += 1;
+        }
+        matched
+    }
+
+    fn expect(&mut self, symbol: &str) -> Result<(), String> {
+        if self.eat(symbol) {
+            Ok(())
+        } else {
+            Err(format!("expected \"{}\" {}", symbol, self.found()))
+        }
+    }
+
+    fn identifier(&mut self) -> Result<String, String> {
+        match self.peek().and_then(Token::identifier) {
+            Some(name) => {
+                self.position += 1;
+                Ok(name.to_string())
+            },
+            None => Err(format!("expected a name {}", self.found()))
+        }
+    }
+
+    /** Describes the next token for an error message */
+    fn found(&self) -> String {
+        match self.peek().map(|token| &token.kind) {
+            None => "at the end of the code".to_string(),
+            Some(TokenKind::Identifier(name)) => format!("but found \"{}\"", name),
+            Some(TokenKind::Number(value)) => format!("but found {}", value),
+            Some(TokenKind::Text(text)) => format!("but found \"{}\"", text),
+            Some(TokenKind::Symbol(symbol)) => format!("but found \"{}\"", symbol)
+        }
+    }
+
+    fn additive(&mut self) -> Result<Expr, String> {
+        let mut left = self.multiplicative()?;
+        while let Some(operator) = ['+', '-'].into_iter().find(|operator| self.peek_is(&operator.to_string())) {
+            self.position += 1;
+            left = Expr::Binary(operator, Box::new(left), Box::new(self.multiplicative()?));
+        }
+        Ok(left)
+    }
+
+    fn multiplicative(&mut self) -> Result<Expr, String> {
+        let mut left = self.unary()?;
+        while let Some(operator) = ['*', '/', '%'].into_iter().find(|operator| self.peek_is(&operator.to_string())) {
+            self.position += 1;
+            left = Expr::Binary(operator, Box::new(left), Box::new(self.unary()?));
+        }
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<Expr, String> {
+        if let Some(operator) = ['-', '+', '!'].into_iter().find(|operator| self.peek_is(&operator.to_string())) {
+            self.position += 1;
+            return Ok(Expr::Unary(operator, Box::new(self.unary()?)));
+        }
+        // A cast to a number type, like (double) kMaxSpeed
+        let cast = self.peek_is("(")
+            && matches!(self.tokens.get(self.position + 1).and_then(Token::identifier), Some(name) if NUMBER_TYPES.contains(&name))
+            && matches!(self.tokens.get(self.position + 2), Some(token) if token.is(")"));
+        if cast {
+            self.position += 3;
+            return self.unary();
+        }
+        self.postfix()
+    }
+
+    /** Member accesses and method calls following a primary expression */
+    fn postfix(&mut self) -> Result<Expr, String> {
+        let mut expr = self.primary()?;
+        while self.eat(".") {
+            let name = self.identifier()?;
+            if self.peek_is("(") {
+                let arguments = self.arguments()?;
+                expr = Expr::Call { target: Some(Box::new(expr)), name, arguments };
+            } else if let Expr::Name(qualifier) = expr {
+                expr = Expr::Name(format!("{}.{}", qualifier, name));
+            } else {
+                return Err(format!("fields of values like .{} aren't supported", name));
+            }
+        }
+        if self.peek_is("::") || self.peek_is("->") || self.peek_is("[") {
+            return Err(format!("{} isn't supported here", self.found().trim_start_matches("but found ")));
+        }
+        Ok(expr)
+    }
+
+    fn primary(&mut self) -> Result<Expr, String> {
+        let token = self.peek().ok_or_else(|| "expected a value at the end of the code".to_string())?;
+        match &token.kind {
+            TokenKind::Number(value) => {
+                self.position += 1;
+                Ok(Expr::Number(*value))
+            },
+            TokenKind::Text(_) => {
+                self.position += 1;
+                Ok(Expr::Text)
+            },
+            TokenKind::Symbol(symbol) if symbol == "(" => {
+                self.position += 1;
+                let expr = self.expression()?;
+                self.expect(")")?;
+                Ok(expr)
+            },
+            TokenKind::Symbol(symbol) if symbol == "{" => self.array(),
+            TokenKind::Identifier(name) => {
+                self.position += 1;
+                match name.as_str() {
+                    "true" => Ok(Expr::Boolean(true)),
+                    "false" => Ok(Expr::Boolean(false)),
+                    "null" => Ok(Expr::Null),
+                    "new" => self.constructor(),
+                    _ if self.peek_is("(") => Ok(Expr::Call { target: None, name: name.clone(), arguments: self.arguments()? }),
+                    _ => Ok(Expr::Name(name.clone()))
+                }
+            },
+            TokenKind::Symbol(_) => Err(format!("expected a value {}", self.found()))
+        }
+    }
+
+    /** What follows new, which is either a constructor call or an array */
+    fn constructor(&mut self) -> Result<Expr, String> {
+        let mut class = self.identifier()?;
+        while self.eat(".") {
+            class = self.identifier()?;
+        }
+        self.skip_type_arguments()?;
+
+        if self.eat("[") {
+            self.expect("]")?;
+            while self.eat("[") {
+                self.expect("]")?;
+            }
+            if !self.peek_is("{") {
+                return Err("arrays need their items listed".to_string());
+            }
+            return self.array();
+        }
+        let arguments = self.arguments()?;
+        if self.peek_is("{") {
+            return Err(format!("anonymous {} classes aren't supported", class));
+        }
+        Ok(Expr::New { class, arguments })
+    }
+
+    fn skip_type_arguments(&mut self) -> Result<(), String> {
+        if !self.eat("<") {
+            return Ok(());
+        }
+        let mut depth = 1;
+        while depth > 0 {
+            let token = self.peek().ok_or_else(|| "type arguments are never closed".to_string())?;
+            if token.is("<") {
+                depth += 1;
+            } else if token.is(">") {
+                depth -= 1;
+            }
+            self.position += 1;
+        }
+        Ok(())
+    }
+
+    fn arguments(&mut self) -> Result<Vec<Expr>, String> {
+        self.list("(", ")")
+    }
+
+    fn array(&mut self) -> Result<Expr, String> {
+        self.list("{", "}").map(Expr::Array)
+    }
+
+    /** Comma separated expressions between brackets, allowing a trailing comma */
+    fn list(&mut self, open: &str, close: &str) -> Result<Vec<Expr>, String> {
+        self.expect(open)?;
+        let mut items = Vec::new();
+        while !self.eat(close) {
+            items.push(self.expression()?);
+            if !self.eat(",") {
+                self.expect(close)?;
+                break;
+            }
+        }
+        Ok(items)
+    }
+}
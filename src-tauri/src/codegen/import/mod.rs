@@ -0,0 +1,811 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::auto::{Auto, AutoConfig, AutoPath, PathConfig, Waypoint};
+use crate::auto::schema::validate;
+use crate::field::Field;
+use crate::robot::{DrivetrainType, RobotConfig};
+
+use super::words;
+use self::expression::{Expr, Parser};
+use self::tokens::{tokenize, Token};
+use self::value::{evaluate, ImportedConfig, Value};
+
+/** Classes whose declarations are reported if they can't be worked out */
+const PATH_CLASSES: [&str; 6] = ["Pose2d", "Translation2d", "Rotation2d", "SwerveTrajectoryWaypoint", "TrajectoryConfig", "TrajectoryGenerator"];
+/** Words at the end of a name that say what it holds rather than which path */
+const PATH_SUFFIXES: [&str; 7] = ["waypoints", "points", "poses", "rotations", "stops", "config", "trajectory"];
+/** Limits of an auto when the code doesn't set any, the same as the editor's */
+const DEFAULT_MAX_VELOCITY: f64 = 4.5;
+const DEFAULT_MAX_ACCELERATION: f64 = 3.5;
+/** Limits of a differential drive the code only partly describes, the same as the editor's */
+const DEFAULT_MAX_WHEEL_SPEED: f64 = 4.;
+const DEFAULT_MAX_VOLTAGE: f64 = 10.;
+
+#[derive(Debug)]
+pub enum ImportError {
+    /** The code couldn't be split into tokens */
+    Syntax {
+        line: usize,
+        message: String
+    },
+    /** Nothing in the code could be read as a path, with why things were skipped */
+    NoPaths(Vec<ImportWarning>)
+}
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportError::Syntax { line, message } => write!(f, "Line {}: {}", line, message),
+            ImportError::NoPaths(warnings) => {
+                write!(f, "No paths were found in the code.")?;
+                for warning in warnings {
+                    write!(f, "\n{}", warning)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/**
+ * Something in the code that was skipped or may not have been read the way
+ * it was meant.
+ */
+#[derive(Clone, Debug, Serialize)]
+pub struct ImportWarning {
+    /** The line it's on, if it's about a particular line */
+    pub line: Option<usize>,
+    pub message: String
+}
+impl fmt::Display for ImportWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "Line {}: {}", line, self.message),
+            None => write!(f, "{}", self.message)
+        }
+    }
+}
+
+/**
+ * The autos read from Java source, one for every class with paths in it.
+ */
+#[derive(Clone, Debug, Serialize)]
+pub struct JavaImport {
+    pub autos: Vec<Auto>,
+    pub warnings: Vec<ImportWarning>
+}
+
+/**
+ * A value given a name in the code, or a trajectory generated without one.
+ */
+struct Declaration {
+    /** The class, followed by the method for locals, like TwoPiece.build */
+    scope: String,
+    /** The name, followed by () for methods without parameters */
+    name: Option<String>,
+    expr: Expr,
+    line: usize
+}
+
+/**
+ * Reads the paths out of Java source, like the code this app generates or
+ * hand written SwerveTrajectoryWaypoint arrays, TrajectoryConfigs, Pose2d
+ * lists and TrajectoryGenerator.generateTrajectory calls. Arithmetic on
+ * constants is worked out, including fieldWidth and fieldLength of the field.
+ *
+ * Every class with paths in it becomes an auto, with a path for every list of
+ * waypoints or generated trajectory. Configs, rotations and stops are matched
+ * to a path by name, so PATH_1_WAYPOINTS goes with path1Config() and
+ * PATH_1_STOPS.
+ */
+pub fn import_java(source: &str, field: &Field) -> Result<JavaImport, ImportError> {
+    let tokens = tokenize(source)?;
+    let mut scanner = Scanner { tokens: &tokens, position: 0, declarations: Vec::new(), warnings: Vec::new() };
+    while scanner.position < tokens.len() {
+        scanner.members("");
+        // A stray } closes nothing, so skip it and carry on.
+        scanner.position += 1;
+    }
+
+    let mut environment = Environment {
+        values: vec![None; scanner.declarations.len()],
+        declarations: scanner.declarations,
+        field_width: field.width,
+        field_length: field.length
+    };
+    let mut warnings = scanner.warnings;
+    let mut groups: Vec<String> = Vec::new();
+    for declaration in &environment.declarations {
+        let group = declaration.scope.split('.').next().unwrap_or_default().to_string();
+        if !groups.contains(&group) {
+            groups.push(group);
+        }
+    }
+
+    let mut autos = Vec::new();
+    for group in groups {
+        if let Some(auto) = environment.auto(&group, &mut warnings) {
+            for error in validate(&auto) {
+                warnings.push(ImportWarning { line: None, message: format!("{}: {}", auto.title, error) });
+            }
+            autos.push(auto);
+        }
+    }
+    if autos.is_empty() {
+        return Err(ImportError::NoPaths(warnings));
+    }
+    Ok(JavaImport { autos, warnings })
+}
+
+/**
+ * Finds the declarations in a class body or method body, skipping over
+ * anything else. It only needs to be right about well formed code, and never
+ * gets stuck on code it doesn't understand.
+ */
+struct Scanner<'a> {
+    tokens: &'a [Token],
+    position: usize,
+    declarations: Vec<Declaration>,
+    warnings: Vec<ImportWarning>
+}
+impl<'a> Scanner<'a> {
+    fn peek(&self) -> Option<&'a Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn peek_is(&self, symbol: &str) -> bool {
+        matches!(self.peek(), Some(token) if token.is(symbol))
+    }
+
+    fn line(&self) -> usize {
+        self.peek().or_else(|| self.tokens.last()).map_or(0, |token| token.line)
+    }
+
+    /** Skips from an opening bracket to just past the one that closes it */
+    fn skip_balanced(&mut self) {
+        let mut depth = 0;
+        while let Some(token) = self.peek() {
+            self.position += 1;
+            if token.is("(") || token.is("{") || token.is("[") {
+                depth += 1;
+            } else if token.is(")") || token.is("}") || token.is("]") {
+                depth -= 1;
+                if depth <= 0 {
+                    return;
+                }
+            }
+        }
+    }
+
+    /**
+     * Skips to the end of a statement, just past its ; or a block ending it,
+     * or up to the } that closes the block it's in.
+     */
+    fn skip_statement(&mut self) {
+        while let Some(token) = self.peek() {
+            if token.is(";") {
+                self.position += 1;
+                return;
+            } else if token.is("}") {
+                return;
+            } else if token.is("{") {
+                self.skip_balanced();
+                return;
+            } else if token.is("(") || token.is("[") {
+                self.skip_balanced();
+            } else {
+                self.position += 1;
+            }
+        }
+    }
+
+    /**
+     * Finds the first of the symbols outside of any brackets, without moving.
+     */
+    fn find(&self, symbols: &[&str]) -> usize {
+        let mut depth = 0;
+        let mut i = self.position;
+        while let Some(token) = self.tokens.get(i) {
+            if depth == 0 && symbols.iter().any(|symbol| token.is(symbol)) {
+                return i;
+            }
+            if token.is("(") || token.is("[") || token.is("{") {
+                depth += 1;
+            } else if token.is(")") || token.is("]") || token.is("}") {
+                if depth == 0 {
+                    return i;
+                }
+                depth -= 1;
+            }
+            i += 1;
+        }
+        i
+    }
+
+    /** Reads the members of a class up to the } that closes it */
+    fn members(&mut self, scope: &str) {
+        while let Some(token) = self.peek() {
+            if token.is("}") {
+                return;
+            } else if token.is(";") {
+                self.position += 1;
+            } else if token.is("@") {
+                // An annotation, possibly with arguments
+                self.position += 2;
+                while self.peek_is(".") {
+                    self.position += 2;
+                }
+                if self.peek_is("(") {
+                    self.skip_balanced();
+                }
+            } else if token.is_keyword("package") || token.is_keyword("import") {
+                self.skip_statement();
+            } else {
+                self.member(scope);
+            }
+        }
+    }
+
+    fn member(&mut self, scope: &str) {
+        let start = self.position;
+        let end = self.find(&["=", ";", "{", "(", "}"]);
+        let header = &self.tokens[start..end];
+
+        let kind = header.iter().position(|token| ["class", "interface", "enum", "record"].iter().any(|kind| token.is_keyword(kind)));
+        if let Some(kind) = kind {
+            let name = header.get(kind + 1).and_then(Token::identifier).unwrap_or_default().to_string();
+            self.position = end;
+            if header[kind].is_keyword("class") || header[kind].is_keyword("interface") {
+                if self.peek_is("{") {
+                    self.position += 1;
+                    let scope = if scope.is_empty() { name } else { format!("{}.{}", scope, name) };
+                    self.members(&scope);
+                    self.position += 1;
+                }
+            } else {
+                // Enum constants and record components aren't read.
+                while self.peek().is_some() && !self.peek_is("{") {
+                    self.position += 1;
+                }
+                self.skip_balanced();
+            }
+            return;
+        }
+
+        let name = header.last().and_then(Token::identifier);
+        let delimiter = self.tokens.get(end);
+        match (name, delimiter) {
+            (Some(name), Some(delimiter)) if delimiter.is("(") => {
+                self.position = end;
+                self.method(scope, name);
+            },
+            (Some(name), Some(delimiter)) if delimiter.is("=") => {
+                self.position = end;
+                self.declarators(scope, name);
+            },
+            (_, Some(delimiter)) if delimiter.is("{") => {
+                // An initializer block
+                self.position = end + 1;
+                self.statements(scope);
+                self.position += 1;
+            },
+            _ => {
+                self.position = end;
+                self.skip_statement();
+            }
+        }
+    }
+
+    /**
+     * Reads a method, from its parameters on. A method without parameters
+     * that just returns something is recorded like a constant.
+     */
+    fn method(&mut self, scope: &str, name: &str) {
+        let parameters = self.position + 1;
+        self.skip_balanced();
+        let has_parameters = self.position > parameters + 1;
+        // Skip a throws clause, up to the body or the ; of an abstract method
+        self.position = self.find(&["{", ";"]);
+        if !self.peek_is("{") {
+            self.skip_statement();
+            return;
+        }
+
+        let body = self.position;
+        if !has_parameters && matches!(self.tokens.get(body + 1), Some(token) if token.is_keyword("return")) {
+            let mut parser = Parser::new(self.tokens, body + 2);
+            if let Ok(expr) = parser.expression() {
+                let end = parser.position();
+                let closes = matches!(self.tokens.get(end), Some(token) if token.is(";"))
+                    && matches!(self.tokens.get(end + 1), Some(token) if token.is("}"));
+                if closes {
+                    self.declare(scope, Some(format!("{}()", name)), expr, self.tokens[body + 2].line);
+                    self.scan_trajectories(scope, body + 3, end);
+                    self.position = end + 2;
+                    return;
+                }
+            }
+        }
+        self.position = body + 1;
+        self.statements(&format!("{}.{}", scope, name));
+        self.position += 1;
+    }
+
+    /**
+     * Reads the declarations of one or more names, starting at the = after
+     * the first name, up to the ;.
+     */
+    fn declarators(&mut self, scope: &str, name: &str) {
+        let mut name = name.to_string();
+        loop {
+            let start = self.position + 1;
+            let line = self.line();
+            let mut parser = Parser::new(self.tokens, start);
+            match parser.expression() {
+                Ok(expr) => {
+                    self.position = parser.position();
+                    self.scan_trajectories(scope, start + 1, self.position);
+                    let expr = self.rename_previous(scope, &name, expr);
+                    self.declare(scope, Some(name), expr, line);
+                },
+                Err(message) => {
+                    self.position = start;
+                    let end = self.find(&[";"]);
+                    if self.tokens[start..end].iter().any(|token| PATH_CLASSES.iter().any(|class| token.is_keyword(class))) {
+                        self.warnings.push(ImportWarning { line: Some(line), message: format!("{} was skipped: {}", name, message) });
+                    }
+                    self.scan_trajectories(scope, start, end);
+                    self.skip_statement();
+                    return;
+                }
+            }
+
+            // Another name declared in the same statement, like a = 1, b = 2
+            let next = self.tokens.get(self.position + 1).and_then(Token::identifier);
+            let assigned = matches!(self.tokens.get(self.position + 2), Some(token) if token.is("="));
+            match next {
+                Some(next) if self.peek_is(",") && assigned => {
+                    name = next.to_string();
+                    self.position += 2;
+                },
+                _ => {
+                    self.skip_statement();
+                    return;
+                }
+            }
+        }
+    }
+
+    /** Reads the statements of a method body up to the } that closes it */
+    fn statements(&mut self, scope: &str) {
+        while let Some(token) = self.peek() {
+            if token.is("}") {
+                return;
+            } else if token.is(";") {
+                self.position += 1;
+            } else if token.is("{") {
+                self.position += 1;
+                self.statements(scope);
+                self.position += 1;
+            } else if ["if", "while", "for", "switch", "synchronized", "catch", "try"].iter().any(|keyword| token.is_keyword(keyword)) {
+                self.position += 1;
+                if self.peek_is("(") {
+                    let start = self.position;
+                    self.skip_balanced();
+                    self.scan_trajectories(scope, start, self.position);
+                }
+            } else if ["else", "do", "finally"].iter().any(|keyword| token.is_keyword(keyword)) {
+                self.position += 1;
+            } else {
+                self.statement(scope);
+            }
+        }
+    }
+
+    /**
+     * Reads a statement that's a declaration, an assignment or a call that
+     * changes a declared value, like config.setReversed(true).
+     */
+    fn statement(&mut self, scope: &str) {
+        let start = self.position;
+        let end = self.find(&["=", ";", "{", "}"]);
+        let line = self.line();
+        let name = end.checked_sub(1)
+            .filter(|before| *before >= start)
+            .and_then(|before| self.tokens[before].identifier());
+
+        if let (Some(name), Some(delimiter)) = (name, self.tokens.get(end)) {
+            if delimiter.is("=") && !self.tokens[start].is_keyword("return") {
+                self.position = end;
+                self.declarators(scope, name);
+                return;
+            }
+        }
+
+        let mut parser = Parser::new(self.tokens, start);
+        if let Ok(expr) = parser.expression() {
+            let ends = matches!(self.tokens.get(parser.position()), Some(token) if token.is(";"));
+            if let (true, Some(root)) = (ends, root_name(&expr)) {
+                if self.find_declaration(scope, &root).is_some() {
+                    let (scope, root) = self.declaration_scope(scope, &root);
+                    let expr = self.rename_previous(&scope, &root, expr);
+                    self.declare(&scope, Some(root), expr, line);
+                    self.position = parser.position() + 1;
+                    return;
+                }
+            }
+        }
+        self.skip_statement();
+        self.scan_trajectories(scope, start, self.position);
+    }
+
+    /**
+     * Records the trajectories generated between two tokens that aren't
+     * assigned to anything of their own, like one passed to a command.
+     */
+    fn scan_trajectories(&mut self, scope: &str, start: usize, end: usize) {
+        let mut i = start;
+        while i + 2 < end {
+            let generates = self.tokens[i].is_keyword("TrajectoryGenerator")
+                && self.tokens[i + 1].is(".")
+                && self.tokens[i + 2].is_keyword("generateTrajectory");
+            if !generates {
+                i += 1;
+                continue;
+            }
+            let line = self.tokens[i].line;
+            let mut parser = Parser::new(self.tokens, i);
+            match parser.expression() {
+                Ok(expr) => {
+                    self.declare(scope, None, expr, line);
+                    i = parser.position();
+                },
+                Err(message) => {
+                    self.warnings.push(ImportWarning { line: Some(line), message: format!("a trajectory was skipped: {}", message) });
+                    i += 3;
+                }
+            }
+        }
+    }
+
+    fn declare(&mut self, scope: &str, name: Option<String>, expr: Expr, line: usize) {
+        self.declarations.push(Declaration { scope: scope.to_string(), name, expr, line });
+    }
+
+    fn find_declaration(&self, scope: &str, name: &str) -> Option<usize> {
+        find_declaration(&self.declarations, scope, name, self.declarations.len())
+    }
+
+    /** The scope a name was declared in, as seen from the given scope */
+    fn declaration_scope(&self, scope: &str, name: &str) -> (String, String) {
+        let index = self.find_declaration(scope, name).unwrap_or_default();
+        let declaration = &self.declarations[index];
+        (declaration.scope.clone(), declaration.name.clone().unwrap_or_default())
+    }
+
+    /**
+     * Points the uses of a name in a new value for it at its previous value,
+     * so config = config.setReversed(true) doesn't refer to itself.
+     */
+    fn rename_previous(&self, scope: &str, name: &str, expr: Expr) -> Expr {
+        match self.find_declaration(scope, name) {
+            Some(previous) => rename(expr, name, &format!("#{}", previous)),
+            None => expr
+        }
+    }
+}
+
+/**
+ * The name a chain of calls starts from, like config in
+ * config.setReversed(true).addConstraint(constraint).
+ */
+fn root_name(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Call { target: Some(target), .. } => match target.as_ref() {
+            Expr::Name(name) => Some(name.clone()),
+            target => root_name(target)
+        },
+        _ => None
+    }
+}
+
+fn rename(expr: Expr, from: &str, to: &str) -> Expr {
+    let rename_all = |exprs: Vec<Expr>| exprs.into_iter().map(|expr| rename(expr, from, to)).collect();
+    match expr {
+        Expr::Name(name) if name == from => Expr::Name(to.to_string()),
+        Expr::Unary(operator, operand) => Expr::Unary(operator, Box::new(rename(*operand, from, to))),
+        Expr::Binary(operator, left, right) => Expr::Binary(operator, Box::new(rename(*left, from, to)), Box::new(rename(*right, from, to))),
+        Expr::Call { target, name, arguments } => Expr::Call {
+            target: target.map(|target| Box::new(rename(*target, from, to))),
+            name,
+            arguments: rename_all(arguments)
+        },
+        Expr::New { class, arguments } => Expr::New { class, arguments: rename_all(arguments) },
+        Expr::Array(items) => Expr::Array(rename_all(items)),
+        expr => expr
+    }
+}
+
+/**
+ * Finds what a name refers to from a scope, looking in the scope and then in
+ * the ones around it, or by the last part of a qualified name like
+ * Constants.kMaxSpeed. The last declaration before the one asking wins,
+ * falling back to a later one since fields can be used before they're
+ * declared. A name like #3 refers to the declaration with that index.
+ */
+fn find_declaration(declarations: &[Declaration], scope: &str, name: &str, before: usize) -> Option<usize> {
+    if let Some(index) = name.strip_prefix('#') {
+        return index.parse().ok();
+    }
+    let last_before = |matches: &dyn Fn(&Declaration) -> bool| {
+        let found: Vec<usize> = declarations.iter().enumerate()
+            .filter(|(_, declaration)| matches(declaration))
+            .map(|(i, _)| i)
+            .collect();
+        found.iter().rev().find(|i| **i < before).or_else(|| found.last()).copied()
+    };
+
+    let mut scope = scope;
+    loop {
+        let found = last_before(&|declaration: &Declaration| declaration.scope == scope && declaration.name.as_deref() == Some(name));
+        if found.is_some() {
+            return found;
+        }
+        match scope.rfind('.') {
+            Some(dot) => scope = &scope[..dot],
+            None => break
+        }
+    }
+    if let Some((qualifier, simple)) = name.rsplit_once('.') {
+        let class = qualifier.rsplit('.').next().unwrap_or(qualifier);
+        let found = last_before(&|declaration: &Declaration| {
+            declaration.scope.rsplit('.').next() == Some(class) && declaration.name.as_deref() == Some(simple)
+        });
+        if found.is_some() {
+            return found;
+        }
+        return last_before(&|declaration: &Declaration| declaration.name.as_deref() == Some(simple));
+    }
+    last_before(&|declaration: &Declaration| declaration.name.as_deref() == Some(name))
+}
+
+/**
+ * The declarations found in the code, worked out on demand so they can be
+ * used before they're declared.
+ */
+struct Environment {
+    declarations: Vec<Declaration>,
+    /** The value of every declaration once it's been worked out */
+    values: Vec<Option<Result<Value, String>>>,
+    field_width: f64,
+    field_length: f64
+}
+impl Environment {
+    fn value(&mut self, index: usize) -> Result<Value, String> {
+        if let Some(value) = &self.values[index] {
+            return value.clone();
+        }
+        let name = self.declarations[index].name.clone().unwrap_or_else(|| "the trajectory".to_string());
+        // Marks the declaration as being worked out, in case it refers to itself.
+        self.values[index] = Some(Err(format!("{} refers to itself", name)));
+        let expr = self.declarations[index].expr.clone();
+        let scope = self.declarations[index].scope.clone();
+        let value = evaluate(&expr, &mut |name| self.lookup(name, &scope, index));
+        self.values[index] = Some(value.clone());
+        value
+    }
+
+    fn lookup(&mut self, name: &str, scope: &str, before: usize) -> Result<Value, String> {
+        match find_declaration(&self.declarations, scope, name, before) {
+            Some(index) if index < self.declarations.len() => self.value(index),
+            _ => match name {
+                "fieldWidth" => Ok(Value::Number(self.field_width)),
+                "fieldLength" => Ok(Value::Number(self.field_length)),
+                _ => Err(format!("{} isn't defined in the code", name.trim_end_matches("()")))
+            }
+        }
+    }
+
+    /**
+     * Puts together an auto from the paths declared in a class, or None if it
+     * doesn't have any.
+     */
+    fn auto(&mut self, group: &str, warnings: &mut Vec<ImportWarning>) -> Option<Auto> {
+        // The key and name of every path, along with its waypoints
+        let mut paths: Vec<(Option<String>, Option<String>, Value)> = Vec::new();
+        let mut rotations: HashMap<String, Vec<f64>> = HashMap::new();
+        let mut stops: HashMap<String, Vec<usize>> = HashMap::new();
+        let mut configs: HashMap<String, ImportedConfig> = HashMap::new();
+        let mut constants: HashMap<String, Value> = HashMap::new();
+
+        for index in 0..self.declarations.len() {
+            let declaration = &self.declarations[index];
+            if declaration.scope.split('.').next() != Some(group) {
+                continue;
+            }
+            // Only the last value of a name counts, after all the changes to it.
+            let replaced = declaration.name.is_some() && self.declarations[index + 1..].iter()
+                .any(|later| later.scope == declaration.scope && later.name == declaration.name);
+            if replaced {
+                continue;
+            }
+            let (name, line) = (declaration.name.clone(), declaration.line);
+            let mentions_paths = declaration.expr.mentions(&PATH_CLASSES);
+            let value = match self.value(index) {
+                Ok(value) => value,
+                Err(message) => {
+                    if mentions_paths {
+                        let subject = name.as_deref().unwrap_or("a trajectory").trim_end_matches("()").to_string();
+                        warnings.push(ImportWarning { line: Some(line), message: format!("{} was skipped: {}", subject, message) });
+                    }
+                    continue;
+                }
+            };
+
+            let key = name.as_deref().map(path_key);
+            let holds = name.as_deref().and_then(|name| words(name).last().map(|word| word.to_ascii_lowercase()));
+            match (value, key) {
+                (value @ Value::Trajectory(..), key) => paths.push((key, name, value)),
+                (Value::List(items), key) if is_path(&items) => paths.push((key, name, Value::List(items))),
+                (Value::List(items), Some(key)) if !items.is_empty() && items.iter().all(|item| matches!(item, Value::Rotation(_))) => {
+                    rotations.insert(key, items.iter().map(|item| if let Value::Rotation(degrees) = item { *degrees } else { 0. }).collect());
+                },
+                (Value::List(items), Some(key)) if holds.as_deref() == Some("stops") => {
+                    stops.insert(key, items.iter().filter_map(|item| if let Value::Number(index) = item { Some(*index as usize) } else { None }).collect());
+                },
+                (Value::Config(config), Some(key)) => {
+                    configs.insert(key, config);
+                },
+                (value @ (Value::Number(_) | Value::Boolean(_)), Some(key)) => {
+                    constants.insert(key, value);
+                },
+                _ => {}
+            }
+        }
+        if paths.is_empty() {
+            return None;
+        }
+
+        let path_configs: Vec<Option<ImportedConfig>> = paths.iter().map(|(key, _, value)| match value {
+            Value::Trajectory(_, config) => Some(*config),
+            _ => key.as_ref().and_then(|key| configs.get(key)).copied()
+        }).collect();
+        let first_config = path_configs.iter().flatten().next();
+        let number = |key: &str| match constants.get(key) {
+            Some(Value::Number(value)) => Some(*value),
+            _ => None
+        };
+        let config = AutoConfig {
+            max_velocity: number("maxvelocity").or_else(|| first_config.map(|config| config.max_velocity)).unwrap_or(DEFAULT_MAX_VELOCITY),
+            max_acceleration: number("maxacceleration").or_else(|| first_config.map(|config| config.max_acceleration)).unwrap_or(DEFAULT_MAX_ACCELERATION),
+            reversed: match constants.get("reversed") {
+                Some(Value::Boolean(reversed)) => *reversed,
+                _ => matches!(first_config, Some(config) if config.reversed)
+            }
+        };
+
+        let auto_paths = paths.iter().zip(&path_configs).map(|((key, _, value), path_config)| {
+            let mut waypoints = match value {
+                Value::Trajectory(waypoints, _) => waypoints.clone(),
+                Value::List(items) => list_waypoints(items),
+                _ => Vec::new()
+            };
+            if let Some(key) = key {
+                if let Some(rotations) = rotations.get(key).filter(|rotations| rotations.len() == waypoints.len()) {
+                    for (waypoint, psi) in waypoints.iter_mut().zip(rotations) {
+                        waypoint.psi = Some(*psi);
+                    }
+                }
+                for stop in stops.get(key).into_iter().flatten() {
+                    if let Some(waypoint) = waypoints.get_mut(*stop) {
+                        waypoint.stop = true;
+                    }
+                }
+            }
+            AutoPath { waypoints, config: path_config.map(|path_config| relative_config(&path_config, &config)).unwrap_or_default(), hidden: false }
+        }).collect();
+
+        // Code outside of a class is named after its first path.
+        let title = if group.is_empty() {
+            paths.iter().find_map(|(_, name, _)| name.as_deref())
+                .map_or_else(|| "Imported Auto".to_string(), |name| title_case(&path_key_words(name)))
+        } else {
+            title_case(&words(group))
+        };
+        Some(Auto { title, paths: auto_paths, config, robot: robot(&path_configs) })
+    }
+}
+
+/** Whether a list holds the waypoints of a path */
+fn is_path(items: &[Value]) -> bool {
+    let poses = items.iter().all(|item| matches!(item, Value::Pose(..)));
+    let swerve = items.iter().all(|item| matches!(item, Value::SwerveWaypoint { .. } | Value::Null))
+        && items.iter().any(|item| matches!(item, Value::SwerveWaypoint { .. }));
+    !items.is_empty() && (poses || swerve)
+}
+
+/**
+ * The waypoints of a list of poses or SwerveTrajectoryWaypoints, where a null
+ * makes the robot stop at the waypoint before it.
+ */
+fn list_waypoints(items: &[Value]) -> Vec<Waypoint> {
+    let mut waypoints: Vec<Waypoint> = Vec::new();
+    for item in items {
+        match *item {
+            Value::Pose(x, y, th) => waypoints.push(Waypoint { x, y, th: Some(th), psi: None, stop: false, hidden: false }),
+            Value::SwerveWaypoint { x, y, psi, th } => waypoints.push(Waypoint { x, y, th, psi: Some(psi), stop: false, hidden: false }),
+            _ => if let Some(last) = waypoints.last_mut() {
+                last.stop = true;
+            }
+        }
+    }
+    waypoints
+}
+
+/**
+ * A path's config as overrides of the auto's, leaving out what's the same.
+ */
+fn relative_config(path: &ImportedConfig, auto: &AutoConfig) -> PathConfig {
+    PathConfig {
+        start_velocity: Some(path.start_velocity).filter(|velocity| *velocity != 0.),
+        end_velocity: Some(path.end_velocity).filter(|velocity| *velocity != 0.),
+        max_velocity: Some(path.max_velocity).filter(|velocity| *velocity != auto.max_velocity),
+        max_acceleration: Some(path.max_acceleration).filter(|acceleration| *acceleration != auto.max_acceleration),
+        reversed: Some(path.reversed).filter(|reversed| *reversed != auto.reversed),
+        face_tag: None
+    }
+}
+
+/**
+ * The robot driving the paths, which is a differential drive if any of the
+ * configs use differential drive kinematics.
+ */
+fn robot(configs: &[Option<ImportedConfig>]) -> RobotConfig {
+    let mut robot = RobotConfig::default();
+    let configs: Vec<&ImportedConfig> = configs.iter().flatten().collect();
+    let track_width = configs.iter().find_map(|config| config.track_width);
+    let max_wheel_speed = configs.iter().find_map(|config| config.max_wheel_speed);
+    let voltage = configs.iter().find_map(|config| config.voltage);
+    if track_width.is_some() || max_wheel_speed.is_some() || voltage.is_some() {
+        robot.track_width = track_width.unwrap_or(robot.track_width);
+        robot.drivetrain = DrivetrainType::Differential {
+            max_wheel_speed: max_wheel_speed.unwrap_or(DEFAULT_MAX_WHEEL_SPEED),
+            feedforward: voltage.map(|(feedforward, _)| feedforward),
+            max_voltage: voltage.map_or(DEFAULT_MAX_VOLTAGE, |(_, max_voltage)| max_voltage)
+        };
+    }
+    robot
+}
+
+/**
+ * The words of a name that say which path it's for, leaving out a leading k
+ * and a trailing word like Waypoints or Config.
+ */
+fn path_key_words(name: &str) -> Vec<String> {
+    let mut words = words(name.trim_end_matches("()"));
+    if words.len() > 1 && words[0] == "k" {
+        words.remove(0);
+    }
+    if words.len() > 1 && PATH_SUFFIXES.contains(&words[words.len() - 1].to_ascii_lowercase().as_str()) {
+        words.pop();
+    }
+    words
+}
+
+/** What a name is matched to other names of the same path by */
+fn path_key(name: &str) -> String {
+    path_key_words(name).concat().to_ascii_lowercase()
+}
+
+fn title_case(words: &[String]) -> String {
+    words.iter().map(|word| {
+        let mut chars = word.chars();
+        chars.next().map_or_else(String::new, |first| first.to_ascii_uppercase().to_string() + chars.as_str())
+    }).collect::<Vec<_>>().join(" ")
+}
+
+mod expression;
+mod tokens;
+mod value;
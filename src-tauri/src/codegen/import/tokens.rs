@@ -0,0 +1,142 @@
+use super::ImportError;
+
+/** Operators longer than one character, longest first */
+const OPERATORS: [&str; 14] = ["->", "::", "==", "!=", "<=", ">=", "&&", "||", "++", "--", "+=", "-=", "*=", "/="];
+
+#[derive(Clone, Debug, PartialEq)]
+pub(super) enum TokenKind {
+    Identifier(String),
+    Number(f64),
+    /** A string or character literal */
+    Text(String),
+    /** Punctuation or an operator */
+    Symbol(String)
+}
+
+#[derive(Clone, Debug)]
+pub(super) struct Token {
+    pub kind: TokenKind,
+    pub line: usize
+}
+impl Token {
+    pub fn is(&self, symbol: &str) -> bool {
+        matches!(&self.kind, TokenKind::Symbol(s) if s == symbol)
+    }
+
+    pub fn identifier(&self) -> Option<&str> {
+        match &self.kind {
+            TokenKind::Identifier(name) => Some(name),
+            _ => None
+        }
+    }
+
+    pub fn is_keyword(&self, keyword: &str) -> bool {
+        self.identifier() == Some(keyword)
+    }
+}
+
+/**
+ * Splits Java source into tokens, dropping whitespace and comments. Generics
+ * are left as separate < and > symbols, so >> never closes two of them.
+ */
+pub(super) fn tokenize(source: &str) -> Result<Vec<Token>, ImportError> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut line = 1;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        if c == '\n' {
+            line += 1;
+            i += 1;
+        } else if c.is_whitespace() {
+            i += 1;
+        } else if c == '/' && next == Some('/') {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+        } else if c == '/' && next == Some('*') {
+            let start = line;
+            i += 2;
+            loop {
+                match chars.get(i) {
+                    None => return Err(ImportError::Syntax { line: start, message: "comment is never closed".to_string() }),
+                    Some('*') if chars.get(i + 1) == Some(&'/') => break,
+                    Some('\n') => line += 1,
+                    Some(_) => {}
+                }
+                i += 1;
+            }
+            i += 2;
+        } else if c == '"' || c == '\'' {
+            let start = line;
+            let text_block = c == '"' && chars.get(i + 1..i + 3) == Some(&['"', '"'][..]);
+            i += if text_block { 3 } else { 1 };
+            let mut text = String::new();
+            loop {
+                match chars.get(i) {
+                    None => return Err(ImportError::Syntax { line: start, message: "text is never closed".to_string() }),
+                    Some('\n') if !text_block => return Err(ImportError::Syntax { line: start, message: "text is never closed".to_string() }),
+                    Some('\\') => {
+                        text.extend(chars.get(i + 1));
+                        i += 2;
+                        continue;
+                    },
+                    Some(&quote) if quote == c && (!text_block || chars.get(i + 1..i + 3) == Some(&['"', '"'][..])) => break,
+                    Some(&other) => {
+                        if other == '\n' {
+                            line += 1;
+                        }
+                        text.push(other);
+                    }
+                }
+                i += 1;
+            }
+            i += if text_block { 3 } else { 1 };
+            tokens.push(Token { kind: TokenKind::Text(text), line: start });
+        } else if c.is_ascii_digit() || (c == '.' && matches!(next, Some(next) if next.is_ascii_digit())) {
+            let start = i;
+            while i < chars.len() {
+                let exponent_sign = (chars[i] == '+' || chars[i] == '-')
+                    && matches!(chars[i - 1], 'e' | 'E')
+                    && !chars[start..i].iter().any(|c| *c == 'x' || *c == 'X');
+                if chars[i].is_ascii_alphanumeric() || chars[i] == '_' || chars[i] == '.' || exponent_sign {
+                    i += 1;
+                } else {
+                    break;
+                }
+            }
+            let literal: String = chars[start..i].iter().collect();
+            let value = parse_number(&literal)
+                .ok_or_else(|| ImportError::Syntax { line, message: format!("\"{}\" isn't a number", literal) })?;
+            tokens.push(Token { kind: TokenKind::Number(value), line });
+        } else if c.is_alphabetic() || c == '_' || c == '$' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '$') {
+                i += 1;
+            }
+            tokens.push(Token { kind: TokenKind::Identifier(chars[start..i].iter().collect()), line });
+        } else {
+            let operator = OPERATORS.iter()
+                .find(|operator| operator.chars().eq(chars[i..].iter().take(2).copied()))
+                .map_or_else(|| c.to_string(), |operator| operator.to_string());
+            i += operator.chars().count();
+            tokens.push(Token { kind: TokenKind::Symbol(operator), line });
+        }
+    }
+    Ok(tokens)
+}
+
+/**
+ * Reads a Java number literal, like 4.5, 1e-3, 2.0f, 10L, 1_000 or 0x1F.
+ */
+fn parse_number(literal: &str) -> Option<f64> {
+    let literal = literal.replace('_', "");
+    if let Some(hex) = literal.strip_prefix("0x").or_else(|| literal.strip_prefix("0X")) {
+        let hex = hex.trim_end_matches(['l', 'L']);
+        return i64::from_str_radix(hex, 16).ok().map(|value| value as f64);
+    }
+    literal.trim_end_matches(['d', 'D', 'f', 'F', 'l', 'L']).parse().ok()
+}
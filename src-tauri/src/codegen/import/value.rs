@@ -0,0 +1,428 @@
+use std::f64::consts::{E, PI};
+
+use crate::auto::Waypoint;
+use crate::robot::SimpleMotorFeedforward;
+
+use super::expression::Expr;
+
+const METERS_PER_INCH: f64 = 0.0254;
+const METERS_PER_FOOT: f64 = 0.3048;
+
+/**
+ * A trajectory config as built up in the code, along with the differential
+ * drive constraints added to it.
+ */
+#[derive(Clone, Copy, Debug)]
+pub(super) struct ImportedConfig {
+    pub max_velocity: f64,
+    pub max_acceleration: f64,
+    pub start_velocity: f64,
+    pub end_velocity: f64,
+    pub reversed: bool,
+    /** Track width of the differential drive kinematics used by the config */
+    pub track_width: Option<f64>,
+    pub max_wheel_speed: Option<f64>,
+    /** Feedforward of the voltage constraint, with the voltage it's held to */
+    pub voltage: Option<(SimpleMotorFeedforward, f64)>
+}
+impl ImportedConfig {
+    fn new(max_velocity: f64, max_acceleration: f64) -> Self {
+        Self {
+            max_velocity,
+            max_acceleration,
+            start_velocity: 0.,
+            end_velocity: 0.,
+            reversed: false,
+            track_width: None,
+            max_wheel_speed: None,
+            voltage: None
+        }
+    }
+
+    fn add_constraint(&mut self, constraint: Constraint) {
+        match constraint {
+            Constraint::DifferentialKinematics { track_width, max_wheel_speed } => {
+                self.track_width = track_width.or(self.track_width);
+                self.max_wheel_speed = Some(max_wheel_speed);
+            },
+            Constraint::DifferentialVoltage { feedforward, track_width, max_voltage } => {
+                self.track_width = track_width.or(self.track_width);
+                self.voltage = Some((feedforward, max_voltage));
+            },
+            Constraint::Other => {}
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub(super) enum Constraint {
+    DifferentialKinematics {
+        track_width: Option<f64>,
+        max_wheel_speed: f64
+    },
+    DifferentialVoltage {
+        feedforward: SimpleMotorFeedforward,
+        track_width: Option<f64>,
+        max_voltage: f64
+    },
+    /** A constraint a path in the editor can't have */
+    Other
+}
+
+/**
+ * The value of an expression. Rotations are kept in degrees, since that's
+ * what the editor uses and what most code writes them in.
+ */
+#[derive(Clone, Debug)]
+pub(super) enum Value {
+    Number(f64),
+    Boolean(bool),
+    Null,
+    Text,
+    Translation(f64, f64),
+    Rotation(f64),
+    Pose(f64, f64, f64),
+    /** A waypoint with the robot's orientation psi and the path heading th */
+    SwerveWaypoint {
+        x: f64,
+        y: f64,
+        psi: f64,
+        th: Option<f64>
+    },
+    List(Vec<Value>),
+    Config(ImportedConfig),
+    Trajectory(Vec<Waypoint>, ImportedConfig),
+    /** Kinematics, with the track width if they're for a differential drive */
+    Kinematics(Option<f64>),
+    Feedforward(SimpleMotorFeedforward),
+    Constraint(Constraint),
+    /** An instance of any other class, by its name */
+    Object(String)
+}
+impl Value {
+    fn describe(&self) -> String {
+        match self {
+            Value::Number(_) => "a number".to_string(),
+            Value::Boolean(_) => "a boolean".to_string(),
+            Value::Null => "null".to_string(),
+            Value::Text => "text".to_string(),
+            Value::Translation(..) => "a Translation2d".to_string(),
+            Value::Rotation(_) => "a Rotation2d".to_string(),
+            Value::Pose(..) => "a Pose2d".to_string(),
+            Value::SwerveWaypoint { .. } => "a SwerveTrajectoryWaypoint".to_string(),
+            Value::List(_) => "a list".to_string(),
+            Value::Config(_) => "a TrajectoryConfig".to_string(),
+            Value::Trajectory(..) => "a Trajectory".to_string(),
+            Value::Kinematics(_) => "kinematics".to_string(),
+            Value::Feedforward(_) => "a feedforward".to_string(),
+            Value::Constraint(_) => "a constraint".to_string(),
+            Value::Object(class) => format!("a {}", class)
+        }
+    }
+}
+
+/**
+ * Works out the value of an expression.
+ *
+ * @param lookup Finds the value of a constant by name, or of a method without
+ * parameters by its name followed by ().
+ */
+pub(super) fn evaluate(expr: &Expr, lookup: &mut dyn FnMut(&str) -> Result<Value, String>) -> Result<Value, String> {
+    match expr {
+        Expr::Number(value) => Ok(Value::Number(*value)),
+        Expr::Boolean(value) => Ok(Value::Boolean(*value)),
+        Expr::Null => Ok(Value::Null),
+        Expr::Text => Ok(Value::Text),
+        Expr::Name(name) => match name.as_str() {
+            "Math.PI" | "PI" => Ok(Value::Number(PI)),
+            "Math.E" => Ok(Value::Number(E)),
+            _ => lookup(name)
+        },
+        Expr::Unary(operator, operand) => {
+            let operand = evaluate(operand, lookup)?;
+            match operator {
+                '!' => Ok(Value::Boolean(!boolean(&operand)?)),
+                '-' => Ok(Value::Number(-number(&operand)?)),
+                _ => Ok(Value::Number(number(&operand)?))
+            }
+        },
+        Expr::Binary(operator, left, right) => {
+            let left = number(&evaluate(left, lookup)?)?;
+            let right = number(&evaluate(right, lookup)?)?;
+            Ok(Value::Number(match operator {
+                '+' => left + right,
+                '-' => left - right,
+                '*' => left * right,
+                '/' => left / right,
+                _ => left % right
+            }))
+        },
+        Expr::Array(items) => Ok(Value::List(evaluate_all(items, lookup)?)),
+        Expr::New { class, arguments } => construct(class, arguments, lookup),
+        Expr::Call { target: None, name, arguments } if arguments.is_empty() => lookup(&format!("{}()", name)),
+        Expr::Call { target: None, name, .. } => Err(format!("calls to {} with arguments aren't supported", name)),
+        Expr::Call { target: Some(target), name, arguments } => {
+            if let Expr::Name(class) = target.as_ref() {
+                let class = class.rsplit('.').next().unwrap_or(class);
+                if let Some(value) = call_static(class, name, arguments, lookup) {
+                    return value;
+                }
+            }
+            let target = evaluate(target, lookup)?;
+            let arguments = match (&target, name.as_str(), arguments.as_slice()) {
+                // Kinematics and constraints are often defined in other files, and
+                // only the differential drive ones matter.
+                (Value::Config(_), "setKinematics", [kinematics]) => vec![evaluate(kinematics, lookup).unwrap_or(Value::Kinematics(None))],
+                (Value::Config(_), "addConstraint", [constraint]) => vec![evaluate(constraint, lookup).unwrap_or(Value::Constraint(Constraint::Other))],
+                _ => evaluate_all(arguments, lookup)?
+            };
+            call_method(target, name, &arguments)
+        }
+    }
+}
+
+fn evaluate_all(exprs: &[Expr], lookup: &mut dyn FnMut(&str) -> Result<Value, String>) -> Result<Vec<Value>, String> {
+    exprs.iter().map(|expr| evaluate(expr, lookup)).collect()
+}
+
+fn number(value: &Value) -> Result<f64, String> {
+    match value {
+        Value::Number(value) => Ok(*value),
+        other => Err(format!("expected a number but found {}", other.describe()))
+    }
+}
+
+fn boolean(value: &Value) -> Result<bool, String> {
+    match value {
+        Value::Boolean(value) => Ok(*value),
+        other => Err(format!("expected a boolean but found {}", other.describe()))
+    }
+}
+
+/** A rotation in degrees */
+fn rotation(value: &Value) -> Result<f64, String> {
+    match value {
+        Value::Rotation(degrees) => Ok(*degrees),
+        other => Err(format!("expected a Rotation2d but found {}", other.describe()))
+    }
+}
+
+fn translation(value: &Value) -> Result<(f64, f64), String> {
+    match value {
+        Value::Translation(x, y) => Ok((*x, *y)),
+        other => Err(format!("expected a Translation2d but found {}", other.describe()))
+    }
+}
+
+fn list(value: &Value) -> Result<&[Value], String> {
+    match value {
+        Value::List(items) => Ok(items),
+        other => Err(format!("expected a list but found {}", other.describe()))
+    }
+}
+
+/** A pose as a waypoint with its rotation as the path heading */
+fn waypoint(value: &Value) -> Result<Waypoint, String> {
+    match value {
+        Value::Pose(x, y, th) => Ok(Waypoint { x: *x, y: *y, th: Some(*th), psi: None, stop: false, hidden: false }),
+        other => Err(format!("expected a Pose2d but found {}", other.describe()))
+    }
+}
+
+fn arity_error(name: &str, count: usize) -> String {
+    format!("{} doesn't take {} arguments", name, count)
+}
+
+fn construct(class: &str, arguments: &[Expr], lookup: &mut dyn FnMut(&str) -> Result<Value, String>) -> Result<Value, String> {
+    let known = [
+        "Translation2d", "Rotation2d", "Pose2d", "SwerveTrajectoryWaypoint", "TrajectoryConfig",
+        "DifferentialDriveKinematics", "DifferentialDriveKinematicsConstraint", "DifferentialDriveVoltageConstraint",
+        "SimpleMotorFeedforward", "ArrayList", "LinkedList"
+    ];
+    if !known.contains(&class) {
+        return Ok(match class {
+            "SwerveDriveKinematics" | "MecanumDriveKinematics" => Value::Kinematics(None),
+            _ if class.ends_with("Constraint") => Value::Constraint(Constraint::Other),
+            _ => Value::Object(class.to_string())
+        });
+    }
+
+    let values = evaluate_all(arguments, lookup)?;
+    Ok(match (class, values.as_slice()) {
+        ("Translation2d", []) => Value::Translation(0., 0.),
+        ("Translation2d", [distance, Value::Rotation(degrees)]) => {
+            let distance = number(distance)?;
+            Value::Translation(distance * degrees.to_radians().cos(), distance * degrees.to_radians().sin())
+        },
+        ("Translation2d", [x, y]) => Value::Translation(number(x)?, number(y)?),
+        ("Rotation2d", []) => Value::Rotation(0.),
+        ("Rotation2d", [radians]) => Value::Rotation(number(radians)?.to_degrees()),
+        ("Rotation2d", [x, y]) => Value::Rotation(number(y)?.atan2(number(x)?).to_degrees()),
+        ("Pose2d", []) => Value::Pose(0., 0., 0.),
+        ("Pose2d", [position, heading]) => {
+            let (x, y) = translation(position)?;
+            Value::Pose(x, y, rotation(heading)?)
+        },
+        ("Pose2d", [x, y, heading]) => Value::Pose(number(x)?, number(y)?, rotation(heading)?),
+        ("SwerveTrajectoryWaypoint", [position, psi, rest @ ..]) if rest.len() <= 1 => {
+            let (x, y) = translation(position)?;
+            let th = rest.first().map(rotation).transpose()?;
+            Value::SwerveWaypoint { x, y, psi: rotation(psi)?, th }
+        },
+        ("TrajectoryConfig", [max_velocity, max_acceleration]) => Value::Config(ImportedConfig::new(number(max_velocity)?, number(max_acceleration)?)),
+        ("DifferentialDriveKinematics", [track_width]) => Value::Kinematics(Some(number(track_width)?)),
+        ("DifferentialDriveKinematicsConstraint", [kinematics, max_wheel_speed]) => Value::Constraint(Constraint::DifferentialKinematics {
+            track_width: kinematics_track_width(kinematics)?,
+            max_wheel_speed: number(max_wheel_speed)?
+        }),
+        ("DifferentialDriveVoltageConstraint", [Value::Feedforward(feedforward), kinematics, max_voltage]) => Value::Constraint(Constraint::DifferentialVoltage {
+            feedforward: *feedforward,
+            track_width: kinematics_track_width(kinematics)?,
+            max_voltage: number(max_voltage)?
+        }),
+        ("SimpleMotorFeedforward", [ks, kv]) => Value::Feedforward(SimpleMotorFeedforward::new(number(ks)?, number(kv)?, 0.)),
+        ("SimpleMotorFeedforward", [ks, kv, ka]) => Value::Feedforward(SimpleMotorFeedforward::new(number(ks)?, number(kv)?, number(ka)?)),
+        ("ArrayList" | "LinkedList", []) => Value::List(Vec::new()),
+        ("ArrayList" | "LinkedList", [items]) => Value::List(list(items)?.to_vec()),
+        (class, values) => return Err(arity_error(class, values.len()))
+    })
+}
+
+fn kinematics_track_width(value: &Value) -> Result<Option<f64>, String> {
+    match value {
+        Value::Kinematics(track_width) => Ok(*track_width),
+        other => Err(format!("expected kinematics but found {}", other.describe()))
+    }
+}
+
+/**
+ * Calls a static method of a class the importer knows.
+ *
+ * @return The result, or None if it isn't a class the importer knows, in
+ * which case the target may be a value instead.
+ */
+fn call_static(class: &str, name: &str, arguments: &[Expr], lookup: &mut dyn FnMut(&str) -> Result<Value, String>) -> Option<Result<Value, String>> {
+    if !matches!(class, "Rotation2d" | "Math" | "StrictMath" | "Units" | "List" | "Arrays" | "TrajectoryGenerator") {
+        return None;
+    }
+    Some(evaluate_all(arguments, lookup).and_then(|values| {
+        if matches!((class, name), ("List", "of") | ("Arrays", "asList")) {
+            return Ok(Value::List(values));
+        }
+        if (class, name) == ("TrajectoryGenerator", "generateTrajectory") {
+            return generate_trajectory(&values);
+        }
+        let values = values.iter().map(number).collect::<Result<Vec<_>, _>>()?;
+        let result = match (class, name, values.as_slice()) {
+            ("Rotation2d", "fromDegrees", [degrees]) => return Ok(Value::Rotation(*degrees)),
+            ("Rotation2d", "fromRadians", [radians]) => return Ok(Value::Rotation(radians.to_degrees())),
+            ("Rotation2d", "fromRotations", [rotations]) => return Ok(Value::Rotation(rotations * 360.)),
+            ("Units", "inchesToMeters", [inches]) => inches * METERS_PER_INCH,
+            ("Units", "metersToInches", [meters]) => meters / METERS_PER_INCH,
+            ("Units", "feetToMeters", [feet]) => feet * METERS_PER_FOOT,
+            ("Units", "metersToFeet", [meters]) => meters / METERS_PER_FOOT,
+            ("Units", "degreesToRadians", [degrees]) | ("Math" | "StrictMath", "toRadians", [degrees]) => degrees.to_radians(),
+            ("Units", "radiansToDegrees", [radians]) | ("Math" | "StrictMath", "toDegrees", [radians]) => radians.to_degrees(),
+            ("Math" | "StrictMath", function, values) => match (function, values) {
+                ("sin", [x]) => x.sin(),
+                ("cos", [x]) => x.cos(),
+                ("tan", [x]) => x.tan(),
+                ("asin", [x]) => x.asin(),
+                ("acos", [x]) => x.acos(),
+                ("atan", [x]) => x.atan(),
+                ("atan2", [y, x]) => y.atan2(*x),
+                ("sqrt", [x]) => x.sqrt(),
+                ("abs", [x]) => x.abs(),
+                ("pow", [x, y]) => x.powf(*y),
+                ("hypot", [x, y]) => x.hypot(*y),
+                ("min", [x, y]) => x.min(*y),
+                ("max", [x, y]) => x.max(*y),
+                _ => return Err(format!("Math.{} isn't supported", function))
+            },
+            _ => return Err(format!("{}.{} isn't supported", class, name))
+        };
+        Ok(Value::Number(result))
+    }))
+}
+
+/**
+ * A trajectory from either a list of poses or a start, interior points and
+ * an end, followed by the config.
+ */
+fn generate_trajectory(values: &[Value]) -> Result<Value, String> {
+    let (waypoints, config) = match values {
+        [poses, Value::Config(config)] => (list(poses)?.iter().map(waypoint).collect::<Result<Vec<_>, _>>()?, config),
+        [start, interior, end, Value::Config(config)] => {
+            let mut waypoints = vec![waypoint(start)?];
+            for point in list(interior)? {
+                let (x, y) = translation(point)?;
+                waypoints.push(Waypoint { x, y, th: None, psi: None, stop: false, hidden: false });
+            }
+            waypoints.push(waypoint(end)?);
+            (waypoints, config)
+        },
+        _ => return Err("generateTrajectory needs waypoints and a TrajectoryConfig".to_string())
+    };
+    Ok(Value::Trajectory(waypoints, *config))
+}
+
+fn call_method(target: Value, name: &str, arguments: &[Value]) -> Result<Value, String> {
+    Ok(match (target, name, arguments) {
+        (Value::Config(mut config), _, [argument]) if name.starts_with("set") || name.starts_with("add") => {
+            match (name, argument) {
+                ("setReversed", value) => config.reversed = boolean(value)?,
+                ("setStartVelocity", value) => config.start_velocity = number(value)?,
+                ("setEndVelocity", value) => config.end_velocity = number(value)?,
+                ("setKinematics", kinematics) => if let Value::Kinematics(Some(track_width)) = kinematics {
+                    config.track_width = Some(*track_width);
+                },
+                ("addConstraint", constraint) => if let Value::Constraint(constraint) = constraint {
+                    config.add_constraint(*constraint);
+                },
+                ("addConstraints", Value::List(constraints)) => {
+                    for constraint in constraints {
+                        if let Value::Constraint(constraint) = constraint {
+                            config.add_constraint(*constraint);
+                        }
+                    }
+                },
+                (_, argument) => return Err(format!("{} with {} isn't supported", name, argument.describe()))
+            }
+            Value::Config(config)
+        },
+        (Value::List(mut items), "add", [item]) => {
+            items.push(item.clone());
+            Value::List(items)
+        },
+        (Value::List(mut items), "addAll", [Value::List(more)]) => {
+            items.extend(more.iter().cloned());
+            Value::List(items)
+        },
+        (Value::Rotation(degrees), "getDegrees", []) => Value::Number(degrees),
+        (Value::Rotation(degrees), "getRadians", []) => Value::Number(degrees.to_radians()),
+        (Value::Rotation(degrees), "getCos", []) => Value::Number(degrees.to_radians().cos()),
+        (Value::Rotation(degrees), "getSin", []) => Value::Number(degrees.to_radians().sin()),
+        (Value::Rotation(degrees), "unaryMinus", []) => Value::Rotation(-degrees),
+        (Value::Rotation(degrees), "plus", [other]) => Value::Rotation(degrees + rotation(other)?),
+        (Value::Rotation(degrees), "minus", [other]) => Value::Rotation(degrees - rotation(other)?),
+        (Value::Rotation(degrees), "times", [scale]) => Value::Rotation(degrees * number(scale)?),
+        (Value::Translation(x, _), "getX", []) | (Value::Pose(x, _, _), "getX", []) => Value::Number(x),
+        (Value::Translation(_, y), "getY", []) | (Value::Pose(_, y, _), "getY", []) => Value::Number(y),
+        (Value::Translation(x, y), "getNorm", []) => Value::Number(x.hypot(y)),
+        (Value::Translation(x, y), "getAngle", []) => Value::Rotation(y.atan2(x).to_degrees()),
+        (Value::Translation(x, y), "unaryMinus", []) => Value::Translation(-x, -y),
+        (Value::Translation(x, y), "plus", [other]) => {
+            let (other_x, other_y) = translation(other)?;
+            Value::Translation(x + other_x, y + other_y)
+        },
+        (Value::Translation(x, y), "minus", [other]) => {
+            let (other_x, other_y) = translation(other)?;
+            Value::Translation(x - other_x, y - other_y)
+        },
+        (Value::Translation(x, y), "times", [scale]) => Value::Translation(x * number(scale)?, y * number(scale)?),
+        (Value::Translation(x, y), "div", [scale]) => Value::Translation(x / number(scale)?, y / number(scale)?),
+        (Value::Pose(x, y, _), "getTranslation", []) => Value::Translation(x, y),
+        (Value::Pose(_, _, degrees), "getRotation", []) => Value::Rotation(degrees),
+        (target, name, _) => return Err(format!("{} of {} isn't supported", name, target.describe()))
+    })
+}
@@ -0,0 +1,60 @@
+use super::{Case, Template};
+
+pub(super) const TEMPLATE: Template = Template {
+    extension: "java",
+    file_case: Case::UpperCamel,
+    constant_case: Case::ScreamingSnake,
+    function_case: Case::LowerCamel,
+    file: r#"// Generated by Clairvoyance from "${title}". Changes will be overwritten.
+${package}import edu.wpi.first.math.controller.SimpleMotorFeedforward;
+import edu.wpi.first.math.geometry.Pose2d;
+import edu.wpi.first.math.geometry.Rotation2d;
+import edu.wpi.first.math.kinematics.DifferentialDriveKinematics;
+import edu.wpi.first.math.trajectory.TrajectoryConfig;
+import edu.wpi.first.math.trajectory.constraint.DifferentialDriveKinematicsConstraint;
+import edu.wpi.first.math.trajectory.constraint.DifferentialDriveVoltageConstraint;
+import java.util.List;
+
+public final class ${class} {
+    public static final double MAX_VELOCITY = ${max_velocity};
+    public static final double MAX_ACCELERATION = ${max_acceleration};
+    public static final boolean REVERSED = ${reversed};
+${paths}
+    private ${class}() {}
+}
+"#,
+    package: "package ${package};\n\n",
+    path: r#"
+    /** Waypoints of ${title}, with the heading of the path at each */
+    public static final List<Pose2d> ${constant}_WAYPOINTS = List.of(${waypoints});
+    /** Which way the robot faces at every waypoint of ${title} */
+    public static final List<Rotation2d> ${constant}_ROTATIONS = List.of(${rotations});
+    /** Indices of the waypoints of ${title} the robot stops at */
+    public static final List<Integer> ${constant}_STOPS = List.of(${stops});
+
+    public static TrajectoryConfig ${function}Config() {
+        return new TrajectoryConfig(${max_velocity}, ${max_acceleration})${setters};
+    }
+"#,
+    pose: "new Pose2d(${x}, ${y}, Rotation2d.fromDegrees(${th}))",
+    rotation: "Rotation2d.fromDegrees(${degrees})",
+    start_velocity: "\n            .setStartVelocity(${value})",
+    end_velocity: "\n            .setEndVelocity(${value})",
+    reversed: "\n            .setReversed(${value})",
+    kinematics: "\n            .setKinematics(${value})",
+    constraint: "\n            .addConstraint(${value})",
+    differential_kinematics: "new DifferentialDriveKinematics(${track_width})",
+    kinematics_constraint: "new DifferentialDriveKinematicsConstraint(${kinematics}, ${max_wheel_speed})",
+    voltage_constraint: "new DifferentialDriveVoltageConstraint(new SimpleMotorFeedforward(${ks}, ${kv}, ${ka}), ${kinematics}, ${max_voltage})",
+    true_literal: "true",
+    false_literal: "false",
+    reserved: &[
+        "abstract", "assert", "boolean", "break", "byte", "case", "catch", "char", "class", "const",
+        "continue", "default", "do", "double", "else", "enum", "extends", "final", "finally",
+        "float", "for", "goto", "if", "implements", "import", "instanceof", "int", "interface",
+        "long", "native", "new", "package", "private", "protected", "public", "return", "short",
+        "static", "strictfp", "super", "switch", "synchronized", "this", "throw", "throws",
+        "transient", "try", "void", "volatile", "while", "true", "false", "null", "var", "record",
+        "yield"
+    ]
+};
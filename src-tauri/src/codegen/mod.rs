@@ -0,0 +1,352 @@
+use std::collections::HashSet;
+
+use serde::{Serialize, Deserialize};
+
+use crate::auto::Auto;
+use crate::robot::DrivetrainType;
+
+/**
+ * A language robot code can be generated in.
+ */
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Language {
+    #[default]
+    Java,
+    Cpp,
+    /** RobotPy */
+    Python
+}
+
+/**
+ * How to generate the code for an auto.
+ */
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CodegenOptions {
+    pub language: Language,
+    /** Name of the generated class, or None to name it after the auto */
+    #[serde(default)]
+    pub class_name: Option<String>,
+    /**
+     * Names of the paths to use for their constants, in order. Paths without
+     * a name are called Path 1, Path 2 and so on, and a name that comes out
+     * the same as an earlier one in code gets a number after it.
+     */
+    #[serde(default)]
+    pub path_names: Vec<String>,
+    /**
+     * Code for the kinematics set on the trajectory configs, like
+     * Constants.kSwerveKinematics. Differential drive kinematics are made from
+     * the track width when this isn't set.
+     */
+    #[serde(default)]
+    pub kinematics: Option<String>,
+    /** Package of the generated class, only used for Java */
+    #[serde(default)]
+    pub package: Option<String>
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct GeneratedCode {
+    pub file_name: String,
+    pub code: String
+}
+
+/**
+ * How a name is written in code.
+ */
+#[derive(Clone, Copy, Debug)]
+enum Case {
+    /** TwoPiece */
+    UpperCamel,
+    /** twoPiece */
+    LowerCamel,
+    /** TWO_PIECE */
+    ScreamingSnake,
+    /** two_piece */
+    Snake
+}
+
+/**
+ * The code for a language, as templates with ${name} placeholders.
+ */
+struct Template {
+    extension: &'static str,
+    /** How the file is named after the class */
+    file_case: Case,
+    /** How constants and functions are named after a path */
+    constant_case: Case,
+    function_case: Case,
+    /** The whole file, with the auto's config and its ${paths} */
+    file: &'static str,
+    /** Declares the ${package}, or empty if the language doesn't have them */
+    package: &'static str,
+    /** The constants and config of a path */
+    path: &'static str,
+    /** A waypoint, at ${x} and ${y} with the path heading ${th} in degrees */
+    pose: &'static str,
+    /** Which way the robot faces at a waypoint, ${degrees} */
+    rotation: &'static str,
+    /** Sets a ${value} on a path's config */
+    start_velocity: &'static str,
+    end_velocity: &'static str,
+    reversed: &'static str,
+    kinematics: &'static str,
+    constraint: &'static str,
+    /** Kinematics of a differential drive with the ${track_width} */
+    differential_kinematics: &'static str,
+    kinematics_constraint: &'static str,
+    voltage_constraint: &'static str,
+    true_literal: &'static str,
+    false_literal: &'static str,
+    /** Keywords and literals that can't be used as names */
+    reserved: &'static [&'static str]
+}
+
+/** How far list items are indented, one level further than the list */
+const ITEM_INDENT: &str = "        ";
+const LIST_INDENT: &str = "    ";
+
+/**
+ * Generates robot code with the waypoints, stop points and trajectory config
+ * of every path of an auto, including the constraints of its drivetrain.
+ */
+pub fn generate_code(auto: &Auto, options: &CodegenOptions) -> GeneratedCode {
+    let template = match options.language {
+        Language::Java => &java::TEMPLATE,
+        Language::Cpp => &cpp::TEMPLATE,
+        Language::Python => &python::TEMPLATE
+    };
+    let class_words = options.class_name.as_deref().map_or_else(|| words(&auto.title), words);
+    let class_name = identifier(&class_words, Case::UpperCamel, "Auto", template.reserved);
+    let boolean = |value: bool| if value { template.true_literal } else { template.false_literal };
+    let kinematics = options.kinematics.clone().or_else(|| match auto.robot.drivetrain {
+        DrivetrainType::Differential { .. } => Some(fill(template.differential_kinematics, &[("track_width", &number(auto.robot.track_width))])),
+        DrivetrainType::Swerve => None
+    });
+
+    let mut constants = HashSet::new();
+    let mut functions = HashSet::new();
+    let paths: String = auto.paths.iter().enumerate().map(|(i, path)| {
+        let name = options.path_names.get(i).filter(|name| !name.trim().is_empty())
+            .cloned()
+            .unwrap_or_else(|| format!("Path {}", i + 1));
+        // Names like "Score" and "score", or "Path 1" and "path-1", come out
+        // the same in code, so every one after the first is numbered.
+        let name_words = words(&name);
+        let mut number_after = 1;
+        let (constant, function) = loop {
+            let mut numbered = name_words.clone();
+            if number_after > 1 {
+                numbered.push(number_after.to_string());
+            }
+            let constant = identifier(&numbered, template.constant_case, "Path", template.reserved);
+            let function = identifier(&numbered, template.function_case, "path", template.reserved);
+            if !constants.contains(&constant) && !functions.contains(&function) {
+                break (constant, function);
+            }
+            number_after += 1;
+        };
+        constants.insert(constant.clone());
+        functions.insert(function.clone());
+        let config = auto.path_config(i);
+
+        let waypoints = list(path.waypoints.iter().map(|waypoint| fill(template.pose, &[
+            ("x", &number(waypoint.x)),
+            ("y", &number(waypoint.y)),
+            ("th", &number(waypoint.th.unwrap_or(0.)))
+        ])));
+        // A waypoint without an orientation keeps the one before it.
+        let mut facing = 0.;
+        let rotations = list(path.waypoints.iter().map(|waypoint| {
+            facing = waypoint.psi.unwrap_or(facing);
+            fill(template.rotation, &[("degrees", &number(facing))])
+        }));
+        let stops = path.waypoints.iter().enumerate()
+            .filter(|(_, waypoint)| waypoint.stop)
+            .map(|(j, _)| j.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let mut setters = vec![
+            fill(template.start_velocity, &[("value", &number(config.start_velocity))]),
+            fill(template.end_velocity, &[("value", &number(config.end_velocity))]),
+            fill(template.reversed, &[("value", boolean(config.reversed))])
+        ];
+        match (auto.robot.drivetrain, &kinematics) {
+            (DrivetrainType::Differential { max_wheel_speed, feedforward, max_voltage }, Some(kinematics)) => {
+                let constraint = fill(template.kinematics_constraint, &[
+                    ("kinematics", kinematics),
+                    ("max_wheel_speed", &number(max_wheel_speed))
+                ]);
+                setters.push(fill(template.constraint, &[("value", &constraint)]));
+                if let Some(feedforward) = feedforward {
+                    let constraint = fill(template.voltage_constraint, &[
+                        ("ks", &number(feedforward.ks)),
+                        ("kv", &number(feedforward.kv)),
+                        ("ka", &number(feedforward.ka)),
+                        ("kinematics", kinematics),
+                        ("max_voltage", &number(max_voltage))
+                    ]);
+                    setters.push(fill(template.constraint, &[("value", &constraint)]));
+                }
+            },
+            (DrivetrainType::Swerve, Some(kinematics)) => setters.push(fill(template.kinematics, &[("value", kinematics)])),
+            (_, None) => {}
+        }
+
+        fill(template.path, &[
+            ("title", &text(&name)),
+            ("constant", &constant),
+            ("function", &function),
+            ("waypoints", &waypoints),
+            ("rotations", &rotations),
+            ("stops", &stops),
+            ("max_velocity", &number(config.max_velocity)),
+            ("max_acceleration", &number(config.max_acceleration)),
+            ("setters", &setters.concat())
+        ])
+    }).collect();
+
+    let package = options.package.as_deref().map(package_name).filter(|package| !package.is_empty())
+        .map_or_else(String::new, |package| fill(template.package, &[("package", &package)]));
+    let code = fill(template.file, &[
+        ("title", &text(&auto.title)),
+        ("package", &package),
+        ("class", &class_name),
+        ("max_velocity", &number(auto.config.max_velocity)),
+        ("max_acceleration", &number(auto.config.max_acceleration)),
+        ("reversed", boolean(auto.config.reversed)),
+        ("paths", &paths)
+    ]);
+    GeneratedCode {
+        file_name: format!("{}.{}", identifier(&class_words, template.file_case, "Auto", template.reserved), template.extension),
+        code
+    }
+}
+
+/**
+ * Replaces every ${name} in the template with its value in a single pass, so
+ * values that happen to contain a placeholder are inserted as they are.
+ * Placeholders without a value are left alone.
+ */
+fn fill(template: &str, values: &[(&str, &str)]) -> String {
+    let mut code = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("${") {
+        code.push_str(&rest[..start]);
+        let placeholder = &rest[start..];
+        let value = placeholder.find('}').and_then(|end| {
+            let name = &placeholder[2..end];
+            values.iter().find(|(key, _)| *key == name).map(|(_, value)| (*value, end + 1))
+        });
+        match value {
+            Some((value, length)) => {
+                code.push_str(value);
+                rest = &placeholder[length..];
+            },
+            None => {
+                code.push_str("${");
+                rest = &placeholder[2..];
+            }
+        }
+    }
+    code.push_str(rest);
+    code
+}
+
+/**
+ * A name as it can be written inside the comments and string literals of
+ * every language, without anything that would end them early.
+ */
+fn text(name: &str) -> String {
+    name.replace("*/", "* /")
+        .chars()
+        .map(|c| if c.is_control() { ' ' } else { c })
+        .filter(|&c| c != '"' && c != '\\')
+        .collect()
+}
+
+/**
+ * A package name with only the letters, digits, underscores and dots Java
+ * allows in one.
+ */
+pub fn package_name(package: &str) -> String {
+    package.chars().filter(|&c| c.is_ascii_alphanumeric() || c == '_' || c == '.').collect()
+}
+
+/**
+ * A number as a literal every language reads as floating point.
+ */
+fn number(value: f64) -> String {
+    format!("{:?}", value)
+}
+
+/**
+ * The items of a list one per line, or nothing if there are none.
+ */
+fn list(items: impl Iterator<Item = String>) -> String {
+    let items: Vec<_> = items.map(|item| format!("\n{}{}", ITEM_INDENT, item)).collect();
+    if items.is_empty() {
+        String::new()
+    } else {
+        format!("{}\n{}", items.join(","), LIST_INDENT)
+    }
+}
+
+/**
+ * The words of a name, split at anything that can't be in an identifier and
+ * where lower case turns to upper case.
+ */
+fn words(name: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    for part in name.split(|c: char| !c.is_ascii_alphanumeric()).filter(|part| !part.is_empty()) {
+        let mut word = String::new();
+        let mut previous_lowercase = false;
+        for c in part.chars() {
+            if c.is_ascii_uppercase() && previous_lowercase {
+                words.push(std::mem::take(&mut word));
+            }
+            previous_lowercase = c.is_ascii_lowercase() || c.is_ascii_digit();
+            word.push(c);
+        }
+        words.push(word);
+    }
+    words
+}
+
+/**
+ * Joins words into an identifier, starting it with the prefix if it would
+ * otherwise be empty, start with a digit or be a reserved word.
+ */
+fn identifier(words: &[String], case: Case, prefix: &str, reserved: &[&str]) -> String {
+    let mut words = words.to_vec();
+    if !matches!(words.first(), Some(word) if !word.starts_with(|c: char| c.is_ascii_digit())) {
+        words.insert(0, prefix.to_string());
+    }
+    let name = join(&words, case);
+    if reserved.contains(&name.as_str()) {
+        words.insert(0, prefix.to_string());
+        return join(&words, case);
+    }
+    name
+}
+
+fn join(words: &[String], case: Case) -> String {
+    let capitalized = |word: &String| {
+        let lower = word.to_ascii_lowercase();
+        lower[..1].to_ascii_uppercase() + &lower[1..]
+    };
+    match case {
+        Case::UpperCamel => words.iter().map(capitalized).collect(),
+        Case::LowerCamel => words[0].to_ascii_lowercase() + &words[1..].iter().map(capitalized).collect::<String>(),
+        Case::ScreamingSnake => words.iter().map(|word| word.to_ascii_uppercase()).collect::<Vec<_>>().join("_"),
+        Case::Snake => words.iter().map(|word| word.to_ascii_lowercase()).collect::<Vec<_>>().join("_")
+    }
+}
+
+mod cpp;
+pub mod import;
+mod java;
+mod python;
@@ -0,0 +1,56 @@
+use super::{Case, Template};
+
+pub(super) const TEMPLATE: Template = Template {
+    extension: "py",
+    file_case: Case::Snake,
+    constant_case: Case::ScreamingSnake,
+    function_case: Case::Snake,
+    file: r#"# Generated by Clairvoyance from "${title}". Changes will be overwritten.
+from wpimath.controller import SimpleMotorFeedforwardMeters
+from wpimath.geometry import Pose2d, Rotation2d
+from wpimath.kinematics import DifferentialDriveKinematics
+from wpimath.trajectory import TrajectoryConfig
+from wpimath.trajectory.constraint import (
+    DifferentialDriveKinematicsConstraint,
+    DifferentialDriveVoltageConstraint,
+)
+
+
+class ${class}:
+    MAX_VELOCITY = ${max_velocity}
+    MAX_ACCELERATION = ${max_acceleration}
+    REVERSED = ${reversed}
+${paths}"#,
+    package: "",
+    path: r#"
+    # Waypoints of ${title}, with the heading of the path at each
+    ${constant}_WAYPOINTS = [${waypoints}]
+    # Which way the robot faces at every waypoint of ${title}
+    ${constant}_ROTATIONS = [${rotations}]
+    # Indices of the waypoints of ${title} the robot stops at
+    ${constant}_STOPS = [${stops}]
+
+    @staticmethod
+    def ${function}_config() -> TrajectoryConfig:
+        config = TrajectoryConfig(${max_velocity}, ${max_acceleration})${setters}
+        return config
+"#,
+    pose: "Pose2d(${x}, ${y}, Rotation2d.fromDegrees(${th}))",
+    rotation: "Rotation2d.fromDegrees(${degrees})",
+    start_velocity: "\n        config.setStartVelocity(${value})",
+    end_velocity: "\n        config.setEndVelocity(${value})",
+    reversed: "\n        config.setReversed(${value})",
+    kinematics: "\n        config.setKinematics(${value})",
+    constraint: "\n        config.addConstraint(${value})",
+    differential_kinematics: "DifferentialDriveKinematics(${track_width})",
+    kinematics_constraint: "DifferentialDriveKinematicsConstraint(${kinematics}, ${max_wheel_speed})",
+    voltage_constraint: "DifferentialDriveVoltageConstraint(SimpleMotorFeedforwardMeters(${ks}, ${kv}, ${ka}), ${kinematics}, ${max_voltage})",
+    true_literal: "True",
+    false_literal: "False",
+    reserved: &[
+        "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class",
+        "continue", "def", "del", "elif", "else", "except", "finally", "for", "from", "global",
+        "if", "import", "in", "is", "lambda", "nonlocal", "not", "or", "pass", "raise", "return",
+        "try", "while", "with", "yield"
+    ]
+};
@@ -0,0 +1,85 @@
+use serde::{Serialize, Deserialize};
+
+use crate::auto::Headings;
+use crate::field::Field;
+use crate::geometry::{Pose2d, Translation2d};
+use crate::geometry::shapes::Polygon;
+use crate::robot::RobotConfig;
+use crate::trajectory::Trajectory;
+
+/**
+ * The furthest the robot may move between two collision checks, in meters.
+ * Small enough that the robot can't skip over the thinnest obstacle.
+ */
+const SWEEP_RESOLUTION: f64 = 0.02;
+
+/**
+ * A field element, as the area of the carpet it covers.
+ */
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Obstacle {
+    pub name: String,
+    pub shape: Polygon
+}
+impl Obstacle {
+    pub fn new(name: &str, shape: Polygon) -> Self {
+        Self { name: name.to_string(), shape }
+    }
+}
+
+/**
+ * Where the robot first hits an obstacle while following a trajectory.
+ */
+#[derive(Clone, Debug, Serialize)]
+pub struct Collision {
+    /** Time since the start of the trajectory in seconds */
+    pub t: f64,
+    /** Name of the obstacle, or "Field perimeter" if the robot left the field */
+    pub obstacle: String,
+    /** Pose of the robot when it collides */
+    pub pose: Pose2d,
+    /** Corners of the robot's footprint when it collides */
+    pub footprint: [Translation2d; 4]
+}
+
+/**
+ * Sweeps the robot's footprint along the trajectory and returns the first
+ * place it overlaps an obstacle or sticks out of the field.
+ *
+ * @param trajectory The trajectory driven by the robot.
+ * @param robot      The robot, whose drivetrain decides which way it faces.
+ * @param headings   The headings a swerve drive turns through on the way.
+ * @param field      The field, whose perimeter and obstacles the robot must
+ *                   stay clear of. Its slow zones are driven over.
+ * @return The first collision, or None if the robot never collides.
+ */
+pub fn check_collisions(trajectory: &Trajectory, robot: &RobotConfig, headings: &Headings, field: &Field) -> Option<Collision> {
+    if trajectory.num_states() == 0 {
+        return None;
+    }
+    let perimeter = field.perimeter();
+
+    let mut t = 0.;
+    loop {
+        let state = trajectory.sample(t);
+        let pose = robot.pose_at(&state, headings.at(state.t));
+        let footprint = robot.footprint(pose);
+
+        if !footprint.corners().iter().all(|corner| perimeter.contains(corner)) {
+            return Some(Collision { t, obstacle: "Field perimeter".to_string(), pose, footprint: footprint.corners() });
+        }
+        let polygon = footprint.to_polygon();
+        if let Some(obstacle) = field.obstacles.iter().find(|obstacle| obstacle.shape.intersects(&polygon)) {
+            return Some(Collision { t, obstacle: obstacle.name.clone(), pose, footprint: footprint.corners() });
+        }
+
+        if t >= *trajectory.total_time() {
+            return None;
+        }
+        // Step by distance rather than time so fast sections are checked as
+        // closely as slow ones. The cap keeps steps reasonable while the
+        // robot is barely moving.
+        let step = (SWEEP_RESOLUTION / state.velocity.abs()).min(0.02);
+        t = (t + step).min(*trajectory.total_time());
+    }
+}
@@ -0,0 +1,210 @@
+//! A compact binary encoding of trajectories, which robot code can read far
+//! faster than JSON. Every value is little-endian.
+//!
+//! The file starts with a 32 byte header:
+//!
+//! | Offset | Type     | Value                                                |
+//! |--------|----------|------------------------------------------------------|
+//! | 0      | [u8; 4]  | Magic bytes, `CLVT`                                  |
+//! | 4      | u16      | Version of the format, currently 1                   |
+//! | 6      | u8       | Distance unit: 0 meters, 1 feet, 2 inches            |
+//! | 7      | u8       | Angle unit: 0 radians, 1 degrees                     |
+//! | 8      | u32      | Number of states                                     |
+//! | 12     | u16      | Size of a state in bytes, currently 56               |
+//! | 14     | u16      | Reserved, always 0                                   |
+//! | 16     | f64      | Total time of the trajectory in seconds              |
+//! | 24     | u32      | CRC-32 (IEEE) of the states that follow the header   |
+//! | 28     | u32      | Reserved, always 0                                   |
+//!
+//! Followed by the states in order of time, each made of seven f64s: time in
+//! seconds, velocity, acceleration, x, y, heading and curvature. Velocity and
+//! acceleration are per second in the distance unit, and curvature is in the
+//! angle unit per distance unit. Every value is 8 byte aligned, so a memory
+//! mapped file can be read in place.
+//!
+//! Readers should reject versions newer than they know. Later versions only
+//! add values to the end of a state, so a reader can skip what it doesn't
+//! know using the state size, which is always a multiple of 8.
+
+use std::fmt;
+
+use crate::geometry::{Pose2d, Rotation2d, Translation2d};
+use crate::trajectory::{Trajectory, TrajectoryState};
+
+use super::{AngleUnit, DistanceUnit};
+
+pub const MAGIC: [u8; 4] = *b"CLVT";
+pub const VERSION: u16 = 1;
+pub const HEADER_SIZE: usize = 32;
+/** The size of a state in the current version */
+pub const STATE_SIZE: usize = 56;
+
+#[derive(Debug, PartialEq)]
+pub enum BinaryError {
+    /** The data ends before the header or the states it lists */
+    Truncated,
+    /** The data doesn't start with the magic bytes, so it's not a trajectory */
+    NotATrajectory,
+    /** The data is from a newer version of the format */
+    UnsupportedVersion(u16),
+    /** A unit or state size this version doesn't know */
+    InvalidHeader(String),
+    /** The states don't match the checksum in the header */
+    ChecksumMismatch { expected: u32, actual: u32 }
+}
+impl fmt::Display for BinaryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BinaryError::Truncated => write!(f, "The trajectory ends before all of its states."),
+            BinaryError::NotATrajectory => write!(f, "This isn't a binary trajectory."),
+            BinaryError::UnsupportedVersion(version) => write!(f, "The trajectory is from version {} of the format, but only up to {} can be read.", version, VERSION),
+            BinaryError::InvalidHeader(message) => write!(f, "{}", message),
+            BinaryError::ChecksumMismatch { expected, actual } => write!(f, "The trajectory is corrupted, its checksum is {:08x} instead of {:08x}.", actual, expected)
+        }
+    }
+}
+
+/**
+ * Encodes a trajectory, converting it from meters and radians into the given
+ * units.
+ */
+pub fn encode(trajectory: &Trajectory, distance_unit: DistanceUnit, angle_unit: AngleUnit) -> Vec<u8> {
+    let states = trajectory.states();
+    let length = |meters: f64| distance_unit.from_meters(meters);
+    let angle = |radians: f64| angle_unit.from_radians(radians);
+
+    let mut body = Vec::with_capacity(states.len() * STATE_SIZE);
+    for state in states {
+        let values = [
+            state.t,
+            length(state.velocity),
+            length(state.acceleration),
+            length(*state.pose.translation().x()),
+            length(*state.pose.translation().y()),
+            angle(*state.pose.rotation().radians()),
+            angle(state.curvature) / length(1.)
+        ];
+        for value in values {
+            body.extend_from_slice(&value.to_le_bytes());
+        }
+    }
+
+    let mut bytes = Vec::with_capacity(HEADER_SIZE + body.len());
+    bytes.extend_from_slice(&MAGIC);
+    bytes.extend_from_slice(&VERSION.to_le_bytes());
+    bytes.push(distance_code(distance_unit));
+    bytes.push(angle_code(angle_unit));
+    bytes.extend_from_slice(&(states.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(&(STATE_SIZE as u16).to_le_bytes());
+    bytes.extend_from_slice(&0u16.to_le_bytes());
+    bytes.extend_from_slice(&trajectory.total_time().to_le_bytes());
+    bytes.extend_from_slice(&crc32(&body).to_le_bytes());
+    bytes.extend_from_slice(&0u32.to_le_bytes());
+    bytes.extend_from_slice(&body);
+    bytes
+}
+
+/**
+ * Decodes a trajectory in any units back into meters and radians, checking
+ * its checksum.
+ */
+pub fn decode(bytes: &[u8]) -> Result<Trajectory, BinaryError> {
+    if bytes.len() < 4 || bytes[..4] != MAGIC {
+        return Err(if MAGIC.starts_with(bytes) { BinaryError::Truncated } else { BinaryError::NotATrajectory });
+    }
+    if bytes.len() < HEADER_SIZE {
+        return Err(BinaryError::Truncated);
+    }
+    let version = u16::from_le_bytes([bytes[4], bytes[5]]);
+    if version > VERSION {
+        return Err(BinaryError::UnsupportedVersion(version));
+    }
+    let distance_unit = match bytes[6] {
+        0 => DistanceUnit::Meters,
+        1 => DistanceUnit::Feet,
+        2 => DistanceUnit::Inches,
+        code => return Err(BinaryError::InvalidHeader(format!("{} isn't a distance unit.", code)))
+    };
+    let angle_unit = match bytes[7] {
+        0 => AngleUnit::Radians,
+        1 => AngleUnit::Degrees,
+        code => return Err(BinaryError::InvalidHeader(format!("{} isn't an angle unit.", code)))
+    };
+    let count = u32::from_le_bytes([bytes[8], bytes[9], bytes[10], bytes[11]]) as usize;
+    let state_size = usize::from(u16::from_le_bytes([bytes[12], bytes[13]]));
+    if state_size < STATE_SIZE {
+        return Err(BinaryError::InvalidHeader(format!("States have to be at least {} bytes, not {}.", STATE_SIZE, state_size)));
+    }
+    let expected = u32::from_le_bytes([bytes[24], bytes[25], bytes[26], bytes[27]]);
+
+    let body = count.checked_mul(state_size)
+        .and_then(|size| bytes.get(HEADER_SIZE..HEADER_SIZE + size))
+        .ok_or(BinaryError::Truncated)?;
+    let actual = crc32(body);
+    if actual != expected {
+        return Err(BinaryError::ChecksumMismatch { expected, actual });
+    }
+
+    let meters = |distance: f64| distance / distance_unit.from_meters(1.);
+    let radians = |angle: f64| angle / angle_unit.from_radians(1.);
+    let states = body.chunks_exact(state_size).map(|state| {
+        let value = |i: usize| {
+            let mut le_bytes = [0; 8];
+            le_bytes.copy_from_slice(&state[i * 8..i * 8 + 8]);
+            f64::from_le_bytes(le_bytes)
+        };
+        TrajectoryState {
+            t: value(0),
+            velocity: meters(value(1)),
+            acceleration: meters(value(2)),
+            pose: Pose2d::new(
+                Translation2d::new(meters(value(3)), meters(value(4))),
+                Rotation2d::from_radians(radians(value(5)))
+            ),
+            curvature: radians(value(6)) / meters(1.)
+        }
+    }).collect();
+    Ok(Trajectory::new(states))
+}
+
+const fn distance_code(unit: DistanceUnit) -> u8 {
+    match unit {
+        DistanceUnit::Meters => 0,
+        DistanceUnit::Feet => 1,
+        DistanceUnit::Inches => 2
+    }
+}
+
+const fn angle_code(unit: AngleUnit) -> u8 {
+    match unit {
+        AngleUnit::Radians => 0,
+        AngleUnit::Degrees => 1
+    }
+}
+
+/** Lookup table for CRC-32 with the reversed IEEE polynomial, one entry per byte */
+const CRC_TABLE: [u32; 256] = crc_table();
+
+const fn crc_table() -> [u32; 256] {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+}
+
+/**
+ * The CRC-32 of some bytes, as used by zip and PNG, so robot code can check
+ * it with java.util.zip.CRC32.
+ */
+pub fn crc32(bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(!0u32, |crc, byte| CRC_TABLE[((crc ^ u32::from(*byte)) & 0xFF) as usize] ^ (crc >> 8))
+}
@@ -0,0 +1,189 @@
+use std::fmt::Write;
+use std::path::Path;
+
+use serde::{Serialize, Deserialize};
+
+use crate::auto::{generate_auto, Auto, Headings};
+use crate::project::write_atomically;
+use crate::robot::RobotConfig;
+use crate::trajectory::{Trajectory, TrajectoryState};
+
+use super::{AngleUnit, DistanceUnit, ExportError};
+
+/**
+ * How to write a trajectory as CSV.
+ */
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct CsvOptions {
+    pub delimiter: char,
+    pub distance_unit: DistanceUnit,
+    pub angle_unit: AngleUnit,
+    /**
+     * Time between rows in seconds, or None for a row at every state of the
+     * trajectory, which are spaced unevenly.
+     */
+    pub sample_period: Option<f64>
+}
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: ',',
+            distance_unit: DistanceUnit::Meters,
+            angle_unit: AngleUnit::Degrees,
+            sample_period: None
+        }
+    }
+}
+impl CsvOptions {
+    fn validate(&self) -> Result<(), ExportError> {
+        if self.delimiter.is_ascii_alphanumeric() || matches!(self.delimiter, '.' | '-' | '+' | '\n' | '\r' | '"') {
+            return Err(ExportError::InvalidOption(format!("{:?} can't be used as a delimiter.", self.delimiter)));
+        }
+        match self.sample_period {
+            Some(period) if period <= 0. || !period.is_finite() => Err(ExportError::InvalidOption(format!("The sample period has to be positive, not {}.", period))),
+            _ => Ok(())
+        }
+    }
+}
+
+/**
+ * A row of the CSV, in meters and radians.
+ */
+struct Row {
+    /** Time since the start of the auto */
+    time: f64,
+    state: TrajectoryState,
+    /** Rotation of the robot, which only differs from the heading on a swerve drive */
+    rotation: f64,
+    /** Distance driven since the start of the auto */
+    distance: f64
+}
+
+/**
+ * Generates an auto and writes it as CSV, with time and distance carrying on
+ * from one path to the next.
+ */
+pub fn auto_to_csv(auto: &Auto, options: &CsvOptions) -> Result<String, ExportError> {
+    let generated = generate_auto(auto);
+    trajectories_to_csv(generated.paths(), generated.headings(), &auto.robot, options)
+}
+
+/**
+ * Writes trajectories driven one after another as CSV, with a header row
+ * naming the columns and their units.
+ *
+ * @param paths    The trajectories in the order they're driven.
+ * @param headings Which way a swerve drive faces along each trajectory.
+ * @param robot    The robot driving them, which decides its rotation.
+ * @param options  The delimiter, units and sampling to use.
+ */
+pub fn trajectories_to_csv(paths: &[Trajectory], headings: &[Headings], robot: &RobotConfig, options: &CsvOptions) -> Result<String, ExportError> {
+    options.validate()?;
+    let distance = options.distance_unit.abbreviation();
+    let angle = options.angle_unit.abbreviation();
+    let header = [
+        "time (s)".to_string(),
+        format!("x ({})", distance),
+        format!("y ({})", distance),
+        format!("heading ({})", angle),
+        format!("rotation ({})", angle),
+        format!("velocity ({}/s)", distance),
+        format!("acceleration ({}/s^2)", distance),
+        format!("curvature ({}/{})", angle, distance),
+        format!("distance ({})", distance)
+    ];
+    let delimiter = options.delimiter.to_string();
+    let mut csv = header.join(&delimiter);
+    csv.push('\n');
+
+    for row in rows(paths, headings, robot, options.sample_period) {
+        let length = |meters: f64| options.distance_unit.from_meters(meters);
+        let angle = |radians: f64| options.angle_unit.from_radians(radians);
+        let pose = row.state.pose;
+        let values = [
+            row.time,
+            length(*pose.translation().x()),
+            length(*pose.translation().y()),
+            angle(*pose.rotation().radians()),
+            angle(row.rotation),
+            length(row.state.velocity),
+            length(row.state.acceleration),
+            // Curvature is per distance, so it shrinks as the unit grows.
+            angle(row.state.curvature) / length(1.),
+            length(row.distance)
+        ];
+        let line: Vec<String> = values.iter().map(|value| value.to_string()).collect();
+        let _ = writeln!(csv, "{}", line.join(&delimiter));
+    }
+    Ok(csv)
+}
+
+/**
+ * Generates an auto and saves it as a CSV file, replacing the file in one
+ * step.
+ */
+pub fn write_csv(path: &Path, auto: &Auto, options: &CsvOptions) -> Result<(), ExportError> {
+    let csv = auto_to_csv(auto, options)?;
+    write_atomically(path, csv.as_bytes())?;
+    Ok(())
+}
+
+/**
+ * Every row of the CSV, either one per state or one every sample period.
+ * Paths without states are skipped.
+ */
+fn rows(paths: &[Trajectory], headings: &[Headings], robot: &RobotConfig, sample_period: Option<f64>) -> Vec<Row> {
+    let mut rows = Vec::new();
+    let mut start_time = 0.;
+    let mut start_distance = 0.;
+    // Samples are counted rather than summed so rounding doesn't build up,
+    // and carry on from one path to the next.
+    let mut sample = 0;
+
+    for (path, headings) in paths.iter().zip(headings).filter(|(path, _)| path.num_states() > 0) {
+        let states = path.states();
+        let distances = cumulative_distances(states);
+        let mut push = |state: TrajectoryState, distance: f64| rows.push(Row {
+            time: start_time + state.t,
+            state,
+            rotation: *robot.pose_at(&state, headings.at(state.t)).rotation().radians(),
+            distance: start_distance + distance
+        });
+
+        match sample_period {
+            None => {
+                for (state, distance) in states.iter().zip(&distances) {
+                    push(*state, *distance);
+                }
+            },
+            Some(period) => {
+                while sample as f64 * period <= start_time + path.total_time() + 1E-9 {
+                    let t = sample as f64 * period - start_time;
+                    let state = path.sample(t);
+                    // Distance to the state before the sample, and from it to the sample.
+                    let previous = states.partition_point(|state| state.t <= t).max(1) - 1;
+                    let distance = distances[previous] + states[previous].pose.translation().distance_to(state.pose.translation());
+                    push(state, distance);
+                    sample += 1;
+                }
+            }
+        }
+        start_time += path.total_time();
+        start_distance += distances.last().copied().unwrap_or(0.);
+    }
+    rows
+}
+
+/** Distance driven to reach every state, measured along straight lines between states */
+fn cumulative_distances(states: &[TrajectoryState]) -> Vec<f64> {
+    let mut distance = 0.;
+    let mut distances = Vec::with_capacity(states.len());
+    for (i, state) in states.iter().enumerate() {
+        if i > 0 {
+            distance += states[i - 1].pose.translation().distance_to(state.pose.translation());
+        }
+        distances.push(distance);
+    }
+    distances
+}
@@ -0,0 +1,108 @@
+use std::{fmt, io};
+use std::str::FromStr;
+
+use serde::{Serialize, Deserialize};
+
+#[derive(Debug)]
+pub enum ExportError {
+    Io(io::Error),
+    /** An option the exporter can't work with, like a delimiter that's part of a number */
+    InvalidOption(String)
+}
+impl fmt::Display for ExportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExportError::Io(err) => write!(f, "{}", err),
+            ExportError::InvalidOption(message) => write!(f, "{}", message)
+        }
+    }
+}
+impl From<io::Error> for ExportError {
+    fn from(err: io::Error) -> Self {
+        ExportError::Io(err)
+    }
+}
+
+/**
+ * The unit distances are exported in. Trajectories are always in meters.
+ */
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DistanceUnit {
+    #[default]
+    Meters,
+    Feet,
+    Inches
+}
+impl DistanceUnit {
+    /** Converts a distance in meters to this unit */
+    pub fn from_meters(self, meters: f64) -> f64 {
+        match self {
+            DistanceUnit::Meters => meters,
+            DistanceUnit::Feet => meters / 0.3048,
+            DistanceUnit::Inches => meters / 0.0254
+        }
+    }
+
+    pub const fn abbreviation(self) -> &'static str {
+        match self {
+            DistanceUnit::Meters => "m",
+            DistanceUnit::Feet => "ft",
+            DistanceUnit::Inches => "in"
+        }
+    }
+}
+impl FromStr for DistanceUnit {
+    type Err = ExportError;
+
+    fn from_str(unit: &str) -> Result<Self, Self::Err> {
+        match unit {
+            "m" | "meters" => Ok(DistanceUnit::Meters),
+            "ft" | "feet" => Ok(DistanceUnit::Feet),
+            "in" | "inches" => Ok(DistanceUnit::Inches),
+            _ => Err(ExportError::InvalidOption(format!("\"{}\" isn't a distance unit, use m, ft or in.", unit)))
+        }
+    }
+}
+
+/**
+ * The unit angles are exported in. Trajectories are always in radians.
+ */
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AngleUnit {
+    #[default]
+    Degrees,
+    Radians
+}
+impl AngleUnit {
+    /** Converts an angle in radians to this unit */
+    pub fn from_radians(self, radians: f64) -> f64 {
+        match self {
+            AngleUnit::Degrees => radians.to_degrees(),
+            AngleUnit::Radians => radians
+        }
+    }
+
+    pub const fn abbreviation(self) -> &'static str {
+        match self {
+            AngleUnit::Degrees => "deg",
+            AngleUnit::Radians => "rad"
+        }
+    }
+}
+impl FromStr for AngleUnit {
+    type Err = ExportError;
+
+    fn from_str(unit: &str) -> Result<Self, Self::Err> {
+        match unit {
+            "deg" | "degrees" => Ok(AngleUnit::Degrees),
+            "rad" | "radians" => Ok(AngleUnit::Radians),
+            _ => Err(ExportError::InvalidOption(format!("\"{}\" isn't an angle unit, use deg or rad.", unit)))
+        }
+    }
+}
+
+pub mod binary;
+pub mod csv;
+pub mod motion_profile;
@@ -0,0 +1,282 @@
+use std::f64::consts::PI;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+use serde::{Serialize, Deserialize};
+
+use crate::auto::{generate_auto, Auto, AutoTrajectory};
+use crate::kinematics::WheelStates;
+use crate::project::write_atomically;
+use crate::robot::RobotConfig;
+use crate::trajectory::Trajectory;
+
+use super::ExportError;
+
+/** The longest a point of a CTRE motion profile can last */
+const CTRE_MAX_DURATION_MS: u32 = 127;
+const DIFFERENTIAL_WHEELS: [&str; 2] = ["left", "right"];
+/** Swerve modules, in the order the kinematics return them */
+const SWERVE_WHEELS: [&str; 4] = ["front-left", "front-right", "back-left", "back-right"];
+
+/**
+ * How the points of a motion profile are laid out, following what each
+ * vendor's motor controllers are given.
+ */
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MotionProfileFormat {
+    /**
+     * Position, velocity and duration of every point without a header, like
+     * the profiles streamed into a Talon's motion profile buffer.
+     */
+    #[default]
+    Ctre,
+    /**
+     * Time, position and velocity of every point with a header, for robot
+     * code setting the reference of a SPARK MAX every period.
+     */
+    Rev
+}
+
+/**
+ * The version of CTRE's Phoenix library a CTRE profile is run with, which
+ * decides its units.
+ */
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PhoenixVersion {
+    /** Encoder counts and counts per 100 ms */
+    #[default]
+    Phoenix5,
+    /** Rotations and rotations per second */
+    Phoenix6
+}
+
+/**
+ * How to turn an auto into motion profiles.
+ */
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct MotionProfileOptions {
+    pub format: MotionProfileFormat,
+    /** Time between points in milliseconds, which motor controllers keep to whole milliseconds */
+    pub period_ms: u32,
+    /** Which Phoenix a CTRE profile is for. Not used for REV profiles. */
+    pub phoenix_version: PhoenixVersion,
+    /**
+     * Encoder counts per rotation of the motor, used for Phoenix 5. The
+     * Talon FX's integrated sensor has 2048.
+     */
+    pub counts_per_rev: f64
+}
+impl Default for MotionProfileOptions {
+    fn default() -> Self {
+        Self { format: MotionProfileFormat::Ctre, period_ms: 10, phoenix_version: PhoenixVersion::Phoenix5, counts_per_rev: 2048. }
+    }
+}
+impl MotionProfileOptions {
+    /**
+     * The units the motor controllers take positions and velocities in.
+     */
+    pub fn units(&self) -> ProfileUnits {
+        match (self.format, self.phoenix_version) {
+            (MotionProfileFormat::Rev, _) =>
+                ProfileUnits { position: "rotations", velocity: "RPM", per_rotation: 1., per_rotation_per_second: 60. },
+            (MotionProfileFormat::Ctre, PhoenixVersion::Phoenix5) => ProfileUnits {
+                position: "sensor units",
+                velocity: "sensor units/100 ms",
+                per_rotation: self.counts_per_rev,
+                per_rotation_per_second: self.counts_per_rev / 10.
+            },
+            (MotionProfileFormat::Ctre, PhoenixVersion::Phoenix6) =>
+                ProfileUnits { position: "rotations", velocity: "rotations/s", per_rotation: 1., per_rotation_per_second: 1. }
+        }
+    }
+}
+
+/**
+ * The units of the points of a motion profile.
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileUnits {
+    pub position: &'static str,
+    pub velocity: &'static str,
+    /** How many position units make up a rotation of the motor */
+    pub per_rotation: f64,
+    /** How many velocity units make up a rotation of the motor per second */
+    pub per_rotation_per_second: f64
+}
+
+/**
+ * A point of a wheel's motion profile, in the native units of the motor
+ * controllers, see MotionProfileOptions::units.
+ */
+#[derive(Clone, Copy, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfilePoint {
+    /** How far the motor has turned since the start of the auto */
+    pub position: f64,
+    /** Speed of the motor */
+    pub velocity: f64,
+    /** How long the point lasts before the next one */
+    pub duration_ms: u32,
+    /**
+     * Angle of a swerve module in degrees, which its steering motor has to
+     * follow alongside the profile. None on a differential drive.
+     */
+    pub angle: Option<f64>
+}
+
+/**
+ * The motion profile of the drive motor of a single wheel or side.
+ */
+#[derive(Clone, Debug, Serialize)]
+pub struct WheelProfile {
+    /** The wheel driven, like left or front-right */
+    pub wheel: String,
+    pub points: Vec<ProfilePoint>
+}
+
+/**
+ * Works out the motion profile of every drive motor for an auto, with the
+ * paths driven one after another in a single profile.
+ *
+ * The auto is sampled every period, and positions are integrated from the
+ * wheel speeds given by the drivetrain's kinematics, then geared up from the
+ * wheels to the motors. A swerve drive's modules also turn it between the
+ * orientations of the waypoints, see RobotConfig::wheel_states.
+ */
+pub fn motion_profiles(auto: &Auto, options: &MotionProfileOptions) -> Result<Vec<WheelProfile>, ExportError> {
+    if options.format == MotionProfileFormat::Ctre && (options.period_ms == 0 || options.period_ms > CTRE_MAX_DURATION_MS) {
+        return Err(ExportError::InvalidOption(format!(
+            "Points have to last between 1 and {} ms, not {} ms.", CTRE_MAX_DURATION_MS, options.period_ms)));
+    }
+    if options.period_ms == 0 {
+        return Err(ExportError::InvalidOption("Points have to last at least 1 ms.".to_string()));
+    }
+    let robot = &auto.robot;
+    if !(robot.wheel_diameter > 0. && robot.gear_ratio > 0.) {
+        return Err(ExportError::InvalidOption("The wheel diameter and gear ratio have to be greater than 0.".to_string()));
+    }
+    let units = options.units();
+    if !(units.per_rotation > 0. && units.per_rotation.is_finite()) {
+        return Err(ExportError::InvalidOption(format!("The encoder has to have a positive number of counts per rotation, not {}.", options.counts_per_rev)));
+    }
+
+    let period = f64::from(options.period_ms) / 1000.;
+    let wheel_states = sampled_wheel_states(&generate_auto(auto), robot, period);
+    // Wheel speed in m/s to motor speed in rotations per second.
+    let motor_rotations = robot.gear_ratio / (PI * robot.wheel_diameter);
+
+    let names: &[&str] = match wheel_states.first() {
+        Some(WheelStates::Differential { .. }) | None => &DIFFERENTIAL_WHEELS,
+        Some(WheelStates::Swerve { .. }) => &SWERVE_WHEELS
+    };
+    let profiles = names.iter().enumerate().map(|(wheel, name)| {
+        let mut position = 0.;
+        let mut previous_speed = 0.;
+        let points = wheel_states.iter().enumerate().map(|(i, states)| {
+            let (speed, angle) = match states {
+                WheelStates::Differential { left, right } => ([*left, *right][wheel], None),
+                WheelStates::Swerve { modules } => (modules[wheel].speed, Some(modules[wheel].angle.radians().to_degrees()))
+            };
+            let speed = speed * motor_rotations;
+            if i > 0 {
+                // Speeds are close to linear over a period, so the trapezoid rule is enough.
+                position += (previous_speed + speed) / 2. * period;
+            }
+            previous_speed = speed;
+            ProfilePoint {
+                position: position * units.per_rotation,
+                velocity: speed * units.per_rotation_per_second,
+                duration_ms: options.period_ms,
+                angle
+            }
+        }).collect();
+        WheelProfile { wheel: name.to_string(), points }
+    }).collect();
+    Ok(profiles)
+}
+
+/**
+ * What the wheels do every period through the auto. The last point is the
+ * end of the last path, which keeps every point a period long but can put it
+ * up to a period late. The resampled states keep their time since the start
+ * of their path, so a swerve drive turns with the path's headings.
+ */
+fn sampled_wheel_states(auto: &AutoTrajectory, robot: &RobotConfig, period: f64) -> Vec<WheelStates> {
+    let mut wheel_states = Vec::new();
+    let mut start_time = 0.;
+    let mut sample = 0;
+    let paths: Vec<_> = auto.paths().iter().zip(auto.headings()).filter(|(path, _)| path.num_states() > 0).collect();
+    for (i, (path, headings)) in paths.iter().enumerate() {
+        let end_time = start_time + path.total_time();
+        let mut states = Vec::new();
+        while sample as f64 * period < end_time - 1E-9 {
+            states.push(path.sample(sample as f64 * period - start_time));
+            sample += 1;
+        }
+        if i == paths.len() - 1 {
+            states.push(*path.states().last().unwrap());
+        }
+        if !states.is_empty() {
+            wheel_states.extend(robot.wheel_states(&Trajectory::new(states), headings));
+        }
+        start_time = end_time;
+    }
+    wheel_states
+}
+
+/**
+ * The motion profile of every drive motor as files, named after the auto's
+ * file stem and the wheel, like Two Piece-left.ctre.csv.
+ *
+ * @return The name and contents of every file.
+ */
+pub fn motion_profile_files(stem: &str, auto: &Auto, options: &MotionProfileOptions) -> Result<Vec<(String, String)>, ExportError> {
+    let units = options.units();
+    let (extension, header) = match options.format {
+        MotionProfileFormat::Ctre => ("ctre.csv", None),
+        MotionProfileFormat::Rev => ("rev.csv", Some(format!("time (s),position ({}),velocity ({})", units.position, units.velocity)))
+    };
+    Ok(motion_profiles(auto, options)?.iter().map(|profile| {
+        let mut contents = String::new();
+        if let Some(header) = &header {
+            contents.push_str(header);
+            if profile.points.iter().any(|point| point.angle.is_some()) {
+                contents.push_str(",angle (deg)");
+            }
+            contents.push('\n');
+        }
+        for (i, point) in profile.points.iter().enumerate() {
+            let _ = match options.format {
+                MotionProfileFormat::Ctre => write!(contents, "{},{},{}", point.position, point.velocity, point.duration_ms),
+                MotionProfileFormat::Rev => {
+                    let time = f64::from(point.duration_ms) * i as f64 / 1000.;
+                    write!(contents, "{},{},{}", time, point.position, point.velocity)
+                }
+            };
+            if let Some(angle) = point.angle {
+                let _ = write!(contents, ",{}", angle);
+            }
+            contents.push('\n');
+        }
+        (format!("{}-{}.{}", stem, profile.wheel, extension), contents)
+    }).collect())
+}
+
+/**
+ * Writes the motion profile of every drive motor into a directory.
+ *
+ * @return The names of the files written.
+ */
+pub fn write_motion_profiles(directory: &Path, stem: &str, auto: &Auto, options: &MotionProfileOptions) -> Result<Vec<String>, ExportError> {
+    fs::create_dir_all(directory)?;
+    let files = motion_profile_files(stem, auto, options)?;
+    for (name, contents) in &files {
+        write_atomically(&directory.join(name), contents.as_bytes())?;
+    }
+    Ok(files.into_iter().map(|(name, _)| name).collect())
+}
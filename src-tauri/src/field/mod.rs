@@ -0,0 +1,327 @@
+use std::{fmt, fs, io};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use serde::{Serialize, Deserialize};
+
+use crate::collision::Obstacle;
+use crate::geometry::{Pose2d, Rotation2d, Translation2d};
+use crate::geometry::shapes::Polygon;
+use crate::project::write_atomically;
+
+/**
+ * The newest version of the field file layout this build can read. Bump it
+ * whenever a field is added or changes meaning, so older builds refuse files
+ * they would misread instead of silently ignoring the new data.
+ */
+pub const FIELD_VERSION: u32 = 1;
+
+/** The fields that ship with the app, by season. */
+const BUILT_IN_FIELDS: [(u32, &str); 1] = [
+    (2023, include_str!("../../fields/2023-charged-up.json"))
+];
+
+/** The season used when none is chosen. */
+pub const DEFAULT_SEASON: u32 = 2023;
+
+#[derive(Debug)]
+pub enum FieldError {
+    Io(io::Error),
+    Parse(serde_json::Error),
+    UnsupportedVersion(u32),
+    UnknownSeason(u32)
+}
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FieldError::Io(err) => write!(f, "Couldn't read the field file: {}", err),
+            FieldError::Parse(err) => write!(f, "The field file is malformed: {}", err),
+            FieldError::UnsupportedVersion(version) => write!(f, "The field file is version {}, but only versions up to {} are supported. Try updating the app.", version, FIELD_VERSION),
+            FieldError::UnknownSeason(season) => write!(f, "There is no built in field for the {} season.", season)
+        }
+    }
+}
+impl From<io::Error> for FieldError {
+    fn from(err: io::Error) -> Self {
+        FieldError::Io(err)
+    }
+}
+impl From<serde_json::Error> for FieldError {
+    fn from(err: serde_json::Error) -> Self {
+        FieldError::Parse(err)
+    }
+}
+
+/**
+ * A named place on the field, like a scoring location or where a game piece
+ * is staged.
+ */
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Landmark {
+    pub name: String,
+    pub pose: Pose2d
+}
+
+/**
+ * An AprilTag mounted on the field. The pose's rotation is the direction the
+ * tag faces.
+ */
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AprilTag {
+    pub id: u32,
+    pub pose: Pose2d,
+    /** Height of the center of the tag above the carpet in meters */
+    pub height: f64
+}
+
+/**
+ * An AprilTag field layout as written by WPILib's AprilTagFieldLayout, with
+ * only the parts needed here.
+ */
+#[derive(Deserialize)]
+struct WpilibLayout {
+    tags: Vec<WpilibTag>
+}
+
+#[derive(Deserialize)]
+struct WpilibTag {
+    #[serde(rename = "ID")]
+    id: u32,
+    pose: WpilibPose
+}
+impl WpilibTag {
+    fn to_april_tag(&self) -> AprilTag {
+        let WpilibQuaternion { w, x, y, z } = self.pose.rotation.quaternion;
+        // The yaw of the quaternion, tags are mounted upright so pitch and
+        // roll don't matter.
+        let yaw = (2. * (w * z + x * y)).atan2(1. - 2. * (y * y + z * z));
+        let WpilibTranslation { x, y, z } = self.pose.translation;
+        AprilTag {
+            id: self.id,
+            pose: Pose2d::new(Translation2d::new(x, y), Rotation2d::from_radians(yaw)),
+            height: z
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct WpilibPose {
+    translation: WpilibTranslation,
+    rotation: WpilibRotation
+}
+
+#[derive(Clone, Copy, Deserialize)]
+struct WpilibTranslation {
+    x: f64,
+    y: f64,
+    z: f64
+}
+
+#[derive(Deserialize)]
+struct WpilibRotation {
+    quaternion: WpilibQuaternion
+}
+
+#[derive(Clone, Copy, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+struct WpilibQuaternion {
+    w: f64,
+    x: f64,
+    y: f64,
+    z: f64
+}
+
+/**
+ * Everything about a season's field in the blue alliance coordinate system,
+ * with the origin in the corner to the right of the blue drivers.
+ */
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Field {
+    /** Version of the field file layout, see FIELD_VERSION */
+    pub version: u32,
+    pub season: u32,
+    pub name: String,
+    /** Length of the field along the x axis in meters */
+    pub length: f64,
+    /** Width of the field along the y axis in meters */
+    pub width: f64,
+    /** Field elements the robot can't drive through */
+    #[serde(default)]
+    pub obstacles: Vec<Obstacle>,
+    /** Field elements the robot can drive over, but slowly, so paths avoid them */
+    #[serde(default)]
+    pub slow_zones: Vec<Obstacle>,
+    #[serde(default)]
+    pub scoring_locations: Vec<Landmark>,
+    #[serde(default)]
+    pub named_poses: Vec<Landmark>,
+    #[serde(default)]
+    pub april_tags: Vec<AprilTag>
+}
+impl Field {
+    /**
+     * Reads a field from the contents of a field file.
+     */
+    pub fn from_json(json: &str) -> Result<Self, FieldError> {
+        // Check the version on its own first, since a newer file may not
+        // parse as this version at all.
+        #[derive(Deserialize)]
+        struct Versioned {
+            version: u32
+        }
+        let Versioned { version } = serde_json::from_str(json)?;
+        if version > FIELD_VERSION {
+            return Err(FieldError::UnsupportedVersion(version));
+        }
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /**
+     * Reads a field from a field file, for seasons or practice fields that
+     * don't ship with the app.
+     */
+    pub fn load(path: &Path) -> Result<Self, FieldError> {
+        Self::from_json(&fs::read_to_string(path)?)
+    }
+
+    /**
+     * The field that ships with the app for a season.
+     */
+    pub fn for_season(season: u32) -> Result<Self, FieldError> {
+        let (_, json) = BUILT_IN_FIELDS.iter()
+            .find(|(built_in, _)| *built_in == season)
+            .ok_or(FieldError::UnknownSeason(season))?;
+        Self::from_json(json)
+    }
+
+    /**
+     * The current season's field, only parsed the first time it's needed
+     * since it's checked against every time an auto is generated.
+     */
+    pub fn current() -> &'static Self {
+        static CURRENT: OnceLock<Field> = OnceLock::new();
+        CURRENT.get_or_init(|| Self::for_season(DEFAULT_SEASON).expect("the built in fields are valid"))
+    }
+
+    /**
+     * The seasons that have a field shipped with the app.
+     */
+    pub fn seasons() -> Vec<u32> {
+        BUILT_IN_FIELDS.iter().map(|(season, _)| *season).collect()
+    }
+
+    /**
+     * The inside of the field perimeter.
+     */
+    pub fn perimeter(&self) -> Polygon {
+        Polygon::rectangle(0., 0., self.length, self.width)
+    }
+
+    /**
+     * The obstacles and the slow zones together, for when the robot should
+     * stay off of both.
+     */
+    pub fn avoided_obstacles(&self) -> Vec<Obstacle> {
+        self.obstacles.iter().chain(&self.slow_zones).cloned().collect()
+    }
+
+    /**
+     * Mirrors a point onto the other alliance's side of the field.
+     */
+    pub fn mirror_translation(&self, translation: &Translation2d) -> Translation2d {
+        Translation2d::new(self.length - translation.x(), *translation.y())
+    }
+
+    /**
+     * Mirrors a pose onto the other alliance's side of the field, so a blue
+     * auto can be run from the red side.
+     */
+    pub fn mirror_pose(&self, pose: &Pose2d) -> Pose2d {
+        Pose2d::new(
+            self.mirror_translation(pose.translation()),
+            Rotation2d::from_vector(-pose.rotation().cos(), pose.rotation().sin()))
+    }
+
+    /**
+     * The scoring location or named pose closest to a point, if any is within
+     * the distance. Used to snap waypoints onto the field's landmarks.
+     */
+    pub fn nearest_landmark(&self, translation: &Translation2d, max_distance: f64) -> Option<&Landmark> {
+        self.scoring_locations.iter().chain(&self.named_poses)
+            .map(|landmark| (landmark, landmark.pose.translation().distance_to(translation)))
+            .filter(|(_, distance)| *distance <= max_distance)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(landmark, _)| landmark)
+    }
+
+    /**
+     * Replaces the field's AprilTags with the ones in a WPILib AprilTag field
+     * layout file, for when the tags are placed differently than on the
+     * official field.
+     */
+    pub fn with_april_tag_layout(mut self, json: &str) -> Result<Self, FieldError> {
+        let layout: WpilibLayout = serde_json::from_str(json)?;
+        self.april_tags = layout.tags.iter().map(WpilibTag::to_april_tag).collect();
+        Ok(self)
+    }
+
+    /**
+     * Replaces the field's AprilTags with the ones in a WPILib AprilTag field
+     * layout file.
+     */
+    pub fn with_april_tag_layout_file(self, path: &Path) -> Result<Self, FieldError> {
+        self.with_april_tag_layout(&fs::read_to_string(path)?)
+    }
+
+    /**
+     * The AprilTag with an id, if it's on the field.
+     */
+    pub fn april_tag(&self, id: u32) -> Option<&AprilTag> {
+        self.april_tags.iter().find(|tag| tag.id == id)
+    }
+}
+impl Default for Field {
+    fn default() -> Self {
+        Self::current().clone()
+    }
+}
+
+/**
+ * Changes to the field kept between runs of the app.
+ */
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct FieldSettings {
+    /** A WPILib AprilTag field layout file used instead of the field's own tags */
+    pub april_tag_layout: Option<PathBuf>
+}
+impl FieldSettings {
+    /**
+     * Reads the settings from a file. A missing or unreadable file just means
+     * nothing was changed.
+     */
+    pub fn load(path: &Path) -> Self {
+        fs::read_to_string(path).ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), FieldError> {
+        if let Some(directory) = path.parent() {
+            fs::create_dir_all(directory)?;
+        }
+        write_atomically(path, serde_json::to_string_pretty(self)?.as_bytes())?;
+        Ok(())
+    }
+
+    /**
+     * The current season's field with these changes.
+     */
+    pub fn field(&self) -> Result<Field, FieldError> {
+        match &self.april_tag_layout {
+            Some(layout) => Field::default().with_april_tag_layout_file(layout),
+            None => Ok(Field::default())
+        }
+    }
+}
+
@@ -1,10 +1,9 @@
 use std::ops::{Add, Sub, Neg};
 
-use ndarray::ArrayView1;
 use serde::{Serialize, Deserialize};
 
 
-#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Translation2d {
     x: f64,
     y: f64
@@ -13,9 +12,6 @@ impl Translation2d {
     pub const fn new(x: f64, y: f64) -> Self {
         Self {x,y}
     }
-    pub fn from_vector(vector: &ArrayView1<f64>) -> Self {
-        Self { x: vector[0], y: vector[1] }
-    }
     pub const fn default() -> Self {
         Translation2d::new(0., 0.)
     }
@@ -63,7 +59,7 @@ impl Neg for Translation2d {
 }
 
 
-#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Rotation2d {
     radians: f64
 }
@@ -72,6 +68,10 @@ impl Rotation2d {
         Self { radians: degrees.to_radians() }
     }
 
+    pub const fn from_radians(radians: f64) -> Self {
+        Self { radians }
+    }
+
     pub fn from_vector(x: f64, y: f64) -> Self {
         // const auto magnitude = gcem::hypot(x, y);
         let magnitude = x.hypot(y);
@@ -111,6 +111,10 @@ impl Rotation2d {
     pub const fn radians(&self) -> &f64 {
         &self.radians
     }
+
+    pub fn times(self, scalar: f64) -> Self {
+        Self { radians: self.radians * scalar }
+    }
 }
 impl Add for Rotation2d {
     type Output = Self;
@@ -137,7 +141,7 @@ impl Neg for Rotation2d {
 }
 
 
-#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Pose2d {
     translation: Translation2d,
     rotation: Rotation2d
@@ -167,6 +171,43 @@ impl Pose2d {
         // return new Pose2d(transform.getTranslation(), transform.getRotation());
         Pose2d::new(transform.translation().clone(), transform.rotation().clone())
     }
+    /**
+     * Linearly interpolates between this pose and another.
+     *
+     * @param end The pose to interpolate towards.
+     * @param t   How far between the two poses to interpolate, from 0 to 1.
+     */
+    pub fn lerp(self, end: &Pose2d, t: f64) -> Self {
+        self.transform_by(&Transform2d::between(&self, end).times(t))
+    }
+    /**
+     * Obtains a new pose from a constant curvature velocity, by moving along
+     * the arc described by the twist.
+     *
+     * @param twist The change in pose in the robot's coordinate frame since
+     * the previous pose update.
+     * @return The new pose of the robot.
+     */
+    pub fn exp(self, twist: &Twist2d) -> Self {
+        let sin_theta = twist.dth.sin();
+        let cos_theta = twist.dth.cos();
+
+        let s: f64;
+        let c: f64;
+        if twist.dth.abs() < 1E-9 {
+            s = 1.0 - 1.0 / 6.0 * twist.dth * twist.dth;
+            c = 0.5 * twist.dth;
+        } else {
+            s = sin_theta / twist.dth;
+            c = (1. - cos_theta) / twist.dth;
+        }
+
+        let transform = Transform2d::new(
+            Translation2d::new(twist.dx * s - twist.dy * c, twist.dx * c + twist.dy * s),
+            Rotation2d::from_vector(cos_theta, sin_theta)
+        );
+        self.transform_by(&transform)
+    }
     pub fn log(self, end: &Pose2d) -> Twist2d{
         let transform = end.relative_to(&self);
 
@@ -218,6 +259,14 @@ impl Transform2d {
         &self.rotation
     }
 }
+impl Transform2d {
+    pub fn times(self, scalar: f64) -> Self {
+        Self {
+            translation: self.translation.scale_by(scalar),
+            rotation: self.rotation.times(scalar)
+        }
+    }
+}
 impl Add for Transform2d {
     type Output = Self;
 
@@ -229,8 +278,11 @@ impl Add for Transform2d {
     }
 }
 
+#[derive(Clone, Copy, Debug)]
 pub struct Twist2d {
     pub dx: f64,
     pub dy: f64,
     pub dth: f64
-}
\ No newline at end of file
+}
+
+pub mod shapes;
@@ -0,0 +1,228 @@
+use std::f64::consts::PI;
+
+use serde::{Serialize, Deserialize};
+
+use super::{Pose2d, Translation2d};
+
+/**
+ * A convex polygon. Vertices can be in either winding order, but the polygon
+ * must be convex for the intersection test to be correct.
+ */
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Polygon {
+    vertices: Vec<Translation2d>
+}
+impl Polygon {
+    pub fn new(vertices: Vec<Translation2d>) -> Self {
+        Self { vertices }
+    }
+
+    /**
+     * An axis aligned rectangle between two opposite corners.
+     */
+    pub fn rectangle(min_x: f64, min_y: f64, max_x: f64, max_y: f64) -> Self {
+        Self::new(vec![
+            Translation2d::new(min_x, min_y),
+            Translation2d::new(max_x, min_y),
+            Translation2d::new(max_x, max_y),
+            Translation2d::new(min_x, max_y)
+        ])
+    }
+
+    pub const fn vertices(&self) -> &Vec<Translation2d> {
+        &self.vertices
+    }
+
+    /**
+     * Whether the point lies inside or on the edge of the polygon.
+     */
+    pub fn contains(&self, point: &Translation2d) -> bool {
+        let mut sign = 0.;
+        for (start, end) in self.edges() {
+            let cross = (end.x() - start.x()) * (point.y() - start.y())
+                - (end.y() - start.y()) * (point.x() - start.x());
+            if cross == 0. {
+                continue;
+            }
+            if sign == 0. {
+                sign = cross.signum();
+            } else if cross.signum() != sign {
+                return false;
+            }
+        }
+        true
+    }
+
+    /**
+     * Whether the point lies inside the polygon and not on its edge.
+     */
+    pub fn strictly_contains(&self, point: &Translation2d) -> bool {
+        // A segment that starts and ends at the point only passes through the
+        // inside of the polygon if the point is inside it.
+        self.crosses(point, point)
+    }
+
+    /**
+     * Whether the two polygons overlap, using the separating axis theorem.
+     * Polygons that only touch don't count as overlapping, so a robot pushed
+     * up against a field element isn't colliding with it.
+     */
+    pub fn intersects(&self, other: &Polygon) -> bool {
+        if self.vertices.is_empty() || other.vertices.is_empty() {
+            return false;
+        }
+        // Two convex polygons don't overlap if and only if one of their edge
+        // normals separates their projections.
+        !self.edges().chain(other.edges()).any(|(start, end)| {
+            let normal = (start.y() - end.y(), end.x() - start.x());
+            let (min_a, max_a) = self.project(normal);
+            let (min_b, max_b) = other.project(normal);
+            max_a <= min_b || max_b <= min_a
+        })
+    }
+
+    /**
+     * Whether the line segment between the two points passes through the
+     * inside of the polygon. Segments that only run along its edges or touch
+     * its corners don't.
+     */
+    pub fn crosses(&self, start: &Translation2d, end: &Translation2d) -> bool {
+        const EPSILON: f64 = 1E-9;
+        if self.vertices.len() < 3 {
+            return false;
+        }
+        // Clip the segment against the half plane inside each edge. Whatever
+        // is left of it lies inside the polygon.
+        let orientation = self.signed_area().signum();
+        let direction = (end.x() - start.x(), end.y() - start.y());
+        let mut enter = 0.0_f64;
+        let mut exit = 1.0_f64;
+        for (edge_start, edge_end) in self.edges() {
+            let outward_normal = (
+                orientation * (edge_end.y() - edge_start.y()),
+                -orientation * (edge_end.x() - edge_start.x())
+            );
+            let distance = outward_normal.0 * (start.x() - edge_start.x()) + outward_normal.1 * (start.y() - edge_start.y());
+            let rate = outward_normal.0 * direction.0 + outward_normal.1 * direction.1;
+            if rate.abs() < EPSILON {
+                if distance >= -EPSILON {
+                    return false;
+                }
+            } else if rate > 0. {
+                exit = exit.min((-EPSILON - distance) / rate);
+            } else {
+                enter = enter.max((-EPSILON - distance) / rate);
+            }
+            if enter >= exit {
+                return false;
+            }
+        }
+        true
+    }
+
+    /**
+     * The Minkowski sum of the two polygons, the shape swept out by moving
+     * the other polygon's origin over every point of this one.
+     */
+    pub fn minkowski_sum(&self, other: &Polygon) -> Polygon {
+        let mut points = Vec::<Translation2d>::with_capacity(self.vertices.len() * other.vertices.len());
+        for vertex in &self.vertices {
+            for other_vertex in &other.vertices {
+                points.push(*vertex + *other_vertex);
+            }
+        }
+        Polygon::new(convex_hull(points))
+    }
+
+    /**
+     * A regular octagon around the origin that contains the circle of the
+     * given radius.
+     */
+    pub fn circumscribed_octagon(radius: f64) -> Polygon {
+        // Place the corners far enough out that the edges, and not just the
+        // corners, are the radius away from the origin.
+        let corner_radius = radius / (PI / 8.).cos();
+        Polygon::new((0..8).map(|i| {
+            let angle = PI / 8. + i as f64 * PI / 4.;
+            Translation2d::new(corner_radius * angle.cos(), corner_radius * angle.sin())
+        }).collect())
+    }
+
+    fn signed_area(&self) -> f64 {
+        self.edges().map(|(start, end)| start.x() * end.y() - end.x() * start.y()).sum::<f64>() / 2.
+    }
+
+    fn edges(&self) -> impl Iterator<Item = (&Translation2d, &Translation2d)> {
+        self.vertices.iter().zip(self.vertices.iter().cycle().skip(1))
+    }
+
+    fn project(&self, axis: (f64, f64)) -> (f64, f64) {
+        self.vertices.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), vertex| {
+            let projection = vertex.x() * axis.0 + vertex.y() * axis.1;
+            (min.min(projection), max.max(projection))
+        })
+    }
+}
+
+/**
+ * Returns the convex hull of the points counterclockwise, using Andrew's
+ * monotone chain algorithm.
+ */
+fn convex_hull(mut points: Vec<Translation2d>) -> Vec<Translation2d> {
+    points.sort_by(|a, b| a.x().total_cmp(b.x()).then(a.y().total_cmp(b.y())));
+    if points.len() < 3 {
+        return points;
+    }
+    let cross = |o: &Translation2d, a: &Translation2d, b: &Translation2d|
+        (a.x() - o.x()) * (b.y() - o.y()) - (a.y() - o.y()) * (b.x() - o.x());
+
+    let mut hull = Vec::<Translation2d>::with_capacity(points.len() + 1);
+    // Lower hull, then upper hull.
+    for pass in 0..2 {
+        let start = hull.len();
+        let ordered: Box<dyn Iterator<Item = &Translation2d>> =
+            if pass == 0 { Box::new(points.iter()) } else { Box::new(points.iter().rev()) };
+        for point in ordered {
+            while hull.len() >= start + 2 && cross(&hull[hull.len() - 2], &hull[hull.len() - 1], point) <= 0. {
+                hull.pop();
+            }
+            hull.push(*point);
+        }
+        // The last point of each half is the first point of the other.
+        hull.pop();
+    }
+    hull
+}
+
+/**
+ * A rectangle that can be rotated, such as the footprint of the robot.
+ */
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct OrientedRectangle {
+    /** Center of the rectangle, with the rotation of its length */
+    pub center: Pose2d,
+    /** Side length along the rotation of the center */
+    pub length: f64,
+    /** Side length perpendicular to the rotation of the center */
+    pub width: f64
+}
+impl OrientedRectangle {
+    pub const fn new(center: Pose2d, length: f64, width: f64) -> Self {
+        Self { center, length, width }
+    }
+
+    /**
+     * The corners of the rectangle, counterclockwise starting from the front
+     * left.
+     */
+    pub fn corners(&self) -> [Translation2d; 4] {
+        let half_length = self.length / 2.;
+        let half_width = self.width / 2.;
+        [(half_length, half_width), (-half_length, half_width), (-half_length, -half_width), (half_length, -half_width)]
+            .map(|(x, y)| *self.center.translation() + Translation2d::new(x, y).rotate_by(*self.center.rotation()))
+    }
+
+    pub fn to_polygon(&self) -> Polygon {
+        Polygon::new(self.corners().to_vec())
+    }
+}
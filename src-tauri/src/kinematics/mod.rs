@@ -0,0 +1,193 @@
+use serde::{Serialize, Deserialize};
+
+use crate::geometry::{Rotation2d, Translation2d};
+
+/**
+ * The velocity of a robot in its own coordinate frame, where vx is forwards,
+ * vy is to the left and omega is counterclockwise.
+ */
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub struct ChassisSpeeds {
+    pub vx: f64,
+    pub vy: f64,
+    pub omega: f64
+}
+impl ChassisSpeeds {
+    pub const fn new(vx: f64, vy: f64, omega: f64) -> Self {
+        Self { vx, vy, omega }
+    }
+
+    /**
+     * Converts field relative speeds into robot relative speeds.
+     *
+     * @param vx    Velocity towards the opposing alliance wall.
+     * @param vy    Velocity towards the left of the field from the robot's
+     * alliance wall.
+     * @param omega Angular velocity, counterclockwise positive.
+     * @param robot_angle The angle of the robot as measured by a gyroscope.
+     */
+    pub fn from_field_relative_speeds(vx: f64, vy: f64, omega: f64, robot_angle: Rotation2d) -> Self {
+        Self {
+            vx: vx * robot_angle.cos() + vy * robot_angle.sin(),
+            vy: -vx * robot_angle.sin() + vy * robot_angle.cos(),
+            omega
+        }
+    }
+}
+
+/**
+ * The speeds of the left and right side of a differential drive in m/s.
+ */
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub struct DifferentialDriveWheelSpeeds {
+    pub left: f64,
+    pub right: f64
+}
+impl DifferentialDriveWheelSpeeds {
+    pub const fn new(left: f64, right: f64) -> Self {
+        Self { left, right }
+    }
+
+    /**
+     * Renormalizes the wheel speeds if either side is above the specified
+     * maximum. Both sides are scaled down by the same factor so the ratio
+     * between them, and with it the path the robot drives, stays the same.
+     *
+     * @param attainable_max_speed The absolute max speed that a wheel can
+     * reach.
+     */
+    pub fn desaturate(self, attainable_max_speed: f64) -> Self {
+        let real_max_speed = self.left.abs().max(self.right.abs());
+        if real_max_speed <= attainable_max_speed {
+            return self;
+        }
+        Self {
+            left: self.left / real_max_speed * attainable_max_speed,
+            right: self.right / real_max_speed * attainable_max_speed
+        }
+    }
+}
+
+/**
+ * Converts between chassis speeds and the speeds of the two sides of a
+ * differential drive.
+ */
+#[derive(Clone, Copy, Debug)]
+pub struct DifferentialDriveKinematics {
+    pub track_width: f64
+}
+impl DifferentialDriveKinematics {
+    /**
+     * @param track_width The distance between the left and right wheels in
+     * meters.
+     */
+    pub const fn new(track_width: f64) -> Self {
+        Self { track_width }
+    }
+
+    /**
+     * Returns the chassis speeds from the given wheel speeds. The sideways
+     * velocity is always zero.
+     */
+    pub fn to_chassis_speeds(&self, wheel_speeds: &DifferentialDriveWheelSpeeds) -> ChassisSpeeds {
+        ChassisSpeeds::new(
+            (wheel_speeds.left + wheel_speeds.right) / 2.,
+            0.,
+            (wheel_speeds.right - wheel_speeds.left) / self.track_width
+        )
+    }
+
+    /**
+     * Returns the wheel speeds from the given chassis speeds. Any sideways
+     * velocity is ignored since a differential drive can't move sideways.
+     */
+    pub fn to_wheel_speeds(&self, chassis_speeds: &ChassisSpeeds) -> DifferentialDriveWheelSpeeds {
+        DifferentialDriveWheelSpeeds::new(
+            chassis_speeds.vx - self.track_width / 2. * chassis_speeds.omega,
+            chassis_speeds.vx + self.track_width / 2. * chassis_speeds.omega
+        )
+    }
+}
+
+/**
+ * The speed and azimuth of a single swerve module.
+ */
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct SwerveModuleState {
+    /** Speed of the wheel in m/s */
+    pub speed: f64,
+    /** Angle of the module relative to the front of the robot */
+    pub angle: Rotation2d
+}
+
+/**
+ * Converts chassis speeds into the states of the four modules of a swerve
+ * drive. Modules are always in the order front left, front right, back left,
+ * back right.
+ */
+#[derive(Clone, Copy, Debug)]
+pub struct SwerveDriveKinematics {
+    module_locations: [Translation2d; 4]
+}
+impl SwerveDriveKinematics {
+    /**
+     * @param module_locations The locations of the modules relative to the
+     * center of the robot, front left first, then front right, back left and
+     * back right.
+     */
+    pub const fn new(module_locations: [Translation2d; 4]) -> Self {
+        Self { module_locations }
+    }
+
+    /**
+     * Places a module in each corner of a rectangle centered on the robot.
+     *
+     * @param wheelbase   Distance between the front and back modules.
+     * @param track_width Distance between the left and right modules.
+     */
+    pub fn from_dimensions(wheelbase: f64, track_width: f64) -> Self {
+        let x = wheelbase / 2.;
+        let y = track_width / 2.;
+        Self::new([
+            Translation2d::new(x, y),
+            Translation2d::new(x, -y),
+            Translation2d::new(-x, y),
+            Translation2d::new(-x, -y)
+        ])
+    }
+
+    /**
+     * Returns the state of every module that makes the robot move at the given
+     * speeds. When a module isn't moving its angle would be meaningless, so it
+     * keeps the angle it had in the previous states instead, or faces forwards
+     * if there are none.
+     *
+     * @param chassis_speeds The desired robot relative speeds.
+     * @param previous       The module states of the previous timestep.
+     */
+    pub fn to_module_states(&self, chassis_speeds: &ChassisSpeeds, previous: Option<&[SwerveModuleState; 4]>) -> [SwerveModuleState; 4] {
+        let mut states = [SwerveModuleState { speed: 0., angle: Rotation2d::default() }; 4];
+        for (i, location) in self.module_locations.iter().enumerate() {
+            let vx = chassis_speeds.vx - chassis_speeds.omega * location.y();
+            let vy = chassis_speeds.vy + chassis_speeds.omega * location.x();
+            let speed = vx.hypot(vy);
+            let angle = if speed > 1E-9 {
+                Rotation2d::from_vector(vx, vy)
+            } else {
+                previous.map_or(Rotation2d::default(), |previous| previous[i].angle)
+            };
+            states[i] = SwerveModuleState { speed, angle };
+        }
+        states
+    }
+}
+
+/**
+ * What the wheels of the robot are doing at a single point in time.
+ */
+#[derive(Clone, Copy, Debug, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum WheelStates {
+    Swerve { modules: [SwerveModuleState; 4] },
+    Differential { left: f64, right: f64 }
+}
@@ -0,0 +1,18 @@
+//! Trajectory generation for Clairvoyance, kept separate from the Tauri app so
+//! it can be benchmarked and tested without a window.
+
+pub mod auto;
+pub mod codegen;
+pub mod collision;
+pub mod export;
+pub mod field;
+pub mod geometry;
+pub mod kinematics;
+pub mod pathfinding;
+pub mod project;
+pub mod replay;
+pub mod requests;
+pub mod robot;
+pub mod simulation;
+pub mod trajectory;
+pub mod vision;
@@ -3,11 +3,47 @@
   windows_subsystem = "windows"
 )]
 
-use geometry::Pose2d;
-use trajectory::{Trajectory, trajectory_generator::generate_trajectory, TrajectoryConfigNoConstraints};
+use clairvoyance::auto::{Auto, AutoPath, AutoTrajectory, Headings, try_generate_auto_on};
+use clairvoyance::auto::schema::parse_auto;
+use clairvoyance::codegen::{CodegenOptions, GeneratedCode};
+use clairvoyance::codegen::import::JavaImport;
+use clairvoyance::export::csv::{write_csv, CsvOptions};
+use clairvoyance::export::motion_profile::{write_motion_profiles, MotionProfileOptions};
+use clairvoyance::field::{Field, FieldSettings};
+use clairvoyance::geometry::Pose2d;
+use clairvoyance::pathfinding::find_trajectory;
+use clairvoyance::project::{file_name_for, AutoEntry, Project, RecentProjects};
+use clairvoyance::project::deploy::{DeployManifest, ExportFormat};
+use clairvoyance::project::watch::{AutoChange, ProjectWatcher};
+use clairvoyance::replay::{replay, Replay, ReplayOptions};
+use clairvoyance::replay::wpilog::{LogEntry, WpiLog};
+use clairvoyance::requests::{GenerationRequests, PathModelCache};
+use clairvoyance::robot::RobotConfig;
+use clairvoyance::simulation::{simulate, SimulationConfig, SimulationResult};
+use clairvoyance::trajectory::{Trajectory, trajectory_generator::{generate_trajectory, try_generate_trajectory}, TrajectoryConfigNoConstraints};
+use clairvoyance::trajectory::cancellation::CancellationToken;
+use serde::Serialize;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tauri::Manager;
 
-mod geometry;
-mod trajectory;
+/**
+ * The field autos are checked against, with the AprilTag layout loaded in
+ * the field settings.
+ */
+#[derive(Default)]
+struct CurrentField(Mutex<Arc<Field>>);
+impl CurrentField {
+  fn get(&self) -> Arc<Field> {
+    self.0.lock().unwrap().clone()
+  }
+  fn set(&self, field: Field) {
+    *self.0.lock().unwrap() = Arc::new(field);
+  }
+}
 
 #[tauri::command]
 fn test_for_tauri() -> bool {
@@ -22,9 +58,423 @@ fn generate_trajectory_tauri(waypoints: Vec<Pose2d>, config: TrajectoryConfigNoC
   generate_trajectory(waypoints, config.to_trajectory_config())
 }
 
+/**
+ * Generates an auto, checked against the current field with the loaded
+ * AprilTag layout.
+ */
+#[tauri::command]
+fn generate_auto_tauri(auto: Auto, field: tauri::State<CurrentField>) -> Result<AutoTrajectory, String> {
+  println!("Generating {} paths for auto \"{}\"", auto.paths.len(), auto.title);
+  try_generate_auto_on(&auto, &field.get(), &CancellationToken::new()).map_err(|err| err.to_string())
+}
+
+/**
+ * Generates a trajectory off the main thread. A newer request for the same
+ * path cancels this one, in which case an error is returned.
+ */
+#[tauri::command]
+async fn generate_trajectory_async(
+  path_id: String,
+  request_id: u64,
+  waypoints: Vec<Pose2d>,
+  config: TrajectoryConfigNoConstraints,
+  requests: tauri::State<'_, GenerationRequests>
+) -> Result<Trajectory, String> {
+  let cancel = requests.begin(&path_id, request_id).ok_or("Request superseded by a newer request")?;
+  let result = tauri::async_runtime::spawn_blocking(move || {
+    try_generate_trajectory(waypoints, config.to_trajectory_config(), &cancel)
+  }).await;
+  requests.finish(&path_id, request_id);
+  result.map_err(|err| err.to_string())?.map_err(|err| err.to_string())
+}
+
+/**
+ * Generates every path of an auto off the main thread. A newer request for
+ * the same auto cancels this one, in which case an error is returned.
+ */
+#[tauri::command]
+async fn generate_auto_async(
+  auto_id: String,
+  request_id: u64,
+  auto: Auto,
+  requests: tauri::State<'_, GenerationRequests>,
+  field: tauri::State<'_, CurrentField>
+) -> Result<AutoTrajectory, String> {
+  let cancel = requests.begin(&auto_id, request_id).ok_or("Request superseded by a newer request")?;
+  let field = field.get();
+  let result = tauri::async_runtime::spawn_blocking(move || try_generate_auto_on(&auto, &field, &cancel)).await;
+  requests.finish(&auto_id, request_id);
+  result.map_err(|err| err.to_string())?.map_err(|err| err.to_string())
+}
+
+/**
+ * Regenerates a path from its cached model, only re-splining the segments
+ * touching waypoints that moved since the last request for the same path.
+ * The robot's drivetrain constraints apply, so the path matches the one
+ * generated for the whole auto.
+ */
+#[tauri::command]
+async fn generate_path_incremental(
+  app: tauri::AppHandle,
+  path_id: String,
+  request_id: u64,
+  waypoints: Vec<Pose2d>,
+  config: TrajectoryConfigNoConstraints,
+  robot: RobotConfig
+) -> Result<Trajectory, String> {
+  let requests = app.state::<GenerationRequests>();
+  let cancel = requests.begin(&path_id, request_id).ok_or("Request superseded by a newer request")?;
+  let handle = app.clone();
+  let id = path_id.clone();
+  let result = tauri::async_runtime::spawn_blocking(move || {
+    let cache = handle.state::<PathModelCache>();
+    let model = cache.take_updated(&id, &waypoints, config.reversed).map_err(|err| err.to_string())?;
+    let mut config = config.to_trajectory_config();
+    config.constraints = robot.constraints();
+    let trajectory = model.trajectory(config, &cancel).map_err(|err| err.to_string());
+    cache.put_back(&id, model);
+    trajectory
+  }).await;
+  requests.finish(&path_id, request_id);
+  result.map_err(|err| err.to_string())?
+}
+
+/**
+ * Simulates a drivetrain following a generated trajectory, returning the
+ * simulated pose track and how far it strayed from the planned path. A swerve
+ * drive turns between the orientations of the path's waypoints.
+ */
+#[tauri::command]
+async fn simulate_trajectory(trajectory: Trajectory, path: AutoPath, config: SimulationConfig) -> Result<SimulationResult, String> {
+  tauri::async_runtime::spawn_blocking(move || simulate(&trajectory, &Headings::new(&path, &trajectory), &config))
+    .await
+    .map_err(|err| err.to_string())?
+    .map_err(|err| err.to_string())
+}
+
+/**
+ * Finds waypoints from the start to the goal that steer the robot around the
+ * current field's obstacles and slow zones.
+ */
+#[tauri::command]
+async fn find_path(
+  start: Pose2d,
+  goal: Pose2d,
+  robot: RobotConfig,
+  config: TrajectoryConfigNoConstraints,
+  field: tauri::State<'_, CurrentField>
+) -> Result<Vec<Pose2d>, String> {
+  let field = field.get();
+  tauri::async_runtime::spawn_blocking(move || {
+    find_trajectory(&start, &goal, &robot, &field, config)
+      .map(|(waypoints, _)| waypoints)
+      .map_err(|err| err.to_string())
+  }).await.map_err(|err| err.to_string())?
+}
+
+/**
+ * The field for a season, or the current season's field with the loaded
+ * AprilTag layout if none is given.
+ */
+#[tauri::command]
+fn get_field(field: tauri::State<CurrentField>, season: Option<u32>) -> Result<Field, String> {
+  season.map_or_else(|| Ok((*field.get()).clone()), Field::for_season).map_err(|err| err.to_string())
+}
+
+/**
+ * Where the field settings are kept, in the app's config directory.
+ */
+fn field_settings_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+  app.path_resolver().app_config_dir()
+    .map(|directory| directory.join("field-settings.json"))
+    .ok_or_else(|| "The app's config directory couldn't be found.".to_string())
+}
+
+/**
+ * The WPILib AprilTag layout file used instead of the field's own tags, if
+ * one was loaded.
+ */
+#[tauri::command]
+fn april_tag_layout(app: tauri::AppHandle) -> Result<Option<PathBuf>, String> {
+  Ok(FieldSettings::load(&field_settings_path(&app)?).april_tag_layout)
+}
+
+/**
+ * Uses the tags in a WPILib AprilTag layout file instead of the field's own,
+ * or goes back to the field's own tags if no file is given, and remembers
+ * the choice.
+ *
+ * @return The field with the new tags.
+ */
+#[tauri::command]
+fn load_april_tag_layout(
+  app: tauri::AppHandle,
+  field: tauri::State<CurrentField>,
+  path: Option<PathBuf>
+) -> Result<Field, String> {
+  let settings_path = field_settings_path(&app)?;
+  let settings = FieldSettings { april_tag_layout: path, ..FieldSettings::load(&settings_path) };
+  let loaded = settings.field().map_err(|err| err.to_string())?;
+  settings.save(&settings_path).map_err(|err| err.to_string())?;
+  field.set(loaded.clone());
+  Ok(loaded)
+}
+
+/**
+ * Where the list of recently opened projects is kept, in the app's config
+ * directory.
+ */
+fn recent_projects_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+  app.path_resolver().app_config_dir()
+    .map(|directory| directory.join("recent-projects.json"))
+    .ok_or_else(|| "The app's config directory couldn't be found.".to_string())
+}
+
+/**
+ * Opens the project in an existing directory, setting it up if it's new, and remembers
+ * it as the most recent project.
+ *
+ * @return The autos in the project.
+ */
+#[tauri::command]
+fn open_project(
+  app: tauri::AppHandle,
+  watcher: tauri::State<ProjectWatcher>,
+  root: PathBuf
+) -> Result<Vec<AutoEntry>, String> {
+  let project = Project::open(&root).map_err(|err| err.to_string())?;
+  let recent_path = recent_projects_path(&app)?;
+  let mut recent = RecentProjects::load(&recent_path);
+  recent.add(project.root());
+  recent.save(&recent_path).map_err(|err| err.to_string())?;
+  let autos = project.list_autos().map_err(|err| err.to_string())?;
+  watcher.watch(project);
+  Ok(autos)
+}
+
+/**
+ * The recently opened projects that still exist, newest first.
+ */
+#[tauri::command]
+fn recent_projects(app: tauri::AppHandle) -> Result<Vec<PathBuf>, String> {
+  Ok(RecentProjects::load(&recent_projects_path(&app)?).projects())
+}
+
+#[tauri::command]
+fn list_autos(root: PathBuf) -> Result<Vec<AutoEntry>, String> {
+  Project::open(&root).and_then(|project| project.list_autos()).map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+fn load_auto(root: PathBuf, file_name: String) -> Result<Auto, String> {
+  Project::open(&root).and_then(|project| project.load_auto(&file_name)).map_err(|err| err.to_string())
+}
+
+/**
+ * Saves an auto into a project, replacing the file in one step.
+ *
+ * @return The name of the file the auto was saved to, which is named after
+ * the auto's title if no file name is given.
+ */
+#[tauri::command]
+fn save_auto(
+  watcher: tauri::State<ProjectWatcher>,
+  root: PathBuf,
+  file_name: Option<String>,
+  auto: Auto
+) -> Result<String, String> {
+  Project::open(&root)
+    .and_then(|project| watcher.save_auto(&project, file_name.as_deref(), &auto))
+    .map_err(|err| err.to_string())
+}
+
+/**
+ * What the last deploy sync of a project wrote, or None if it's never been
+ * synced.
+ */
+#[tauri::command]
+fn deploy_manifest(root: PathBuf) -> Result<Option<DeployManifest>, String> {
+  Project::open(&root).map(|project| project.deploy_manifest()).map_err(|err| err.to_string())
+}
+
+/**
+ * Writes every auto in a project to the robot code in the given format,
+ * deleting files from the last sync that are no longer generated. Java
+ * classes are put in the given package.
+ */
+#[tauri::command]
+async fn sync_deploy(root: PathBuf, format: ExportFormat, package: Option<String>) -> Result<DeployManifest, String> {
+  tauri::async_runtime::spawn_blocking(move || {
+    Project::open(&root)
+      .and_then(|project| project.sync_deploy(format, package.as_deref()))
+      .map_err(|err| err.to_string())
+  }).await.map_err(|err| err.to_string())?
+}
+
+/**
+ * Reads an auto file of any version, upgrading it to the current layout and
+ * checking that it can be generated.
+ */
+#[tauri::command]
+fn import_auto(json: String) -> Result<Auto, String> {
+  parse_auto(&json).map_err(|err| err.to_string())
+}
+
+/**
+ * Reads the paths out of Java source as autos, working out constants like
+ * fieldWidth with the field of the given season.
+ */
+#[tauri::command]
+fn import_java(source: String, season: Option<u32>) -> Result<JavaImport, String> {
+  let field = season.map_or_else(|| Ok(Field::default()), Field::for_season).map_err(|err| err.to_string())?;
+  clairvoyance::codegen::import::import_java(&source, &field).map_err(|err| err.to_string())
+}
+
+/**
+ * Generates robot code with the paths and configs of an auto.
+ */
+#[tauri::command]
+fn generate_code(auto: Auto, options: CodegenOptions) -> GeneratedCode {
+  clairvoyance::codegen::generate_code(&auto, &options)
+}
+
+/**
+ * Generates an auto and saves it as a CSV file for spreadsheets.
+ */
+#[tauri::command]
+async fn save_csv(auto: Auto, options: CsvOptions, path: PathBuf) -> Result<(), String> {
+  tauri::async_runtime::spawn_blocking(move || write_csv(&path, &auto, &options).map_err(|err| err.to_string()))
+    .await
+    .map_err(|err| err.to_string())?
+}
+
+/**
+ * Saves a motion profile for every drive motor into a directory, named after
+ * the auto's title.
+ *
+ * @return The names of the files written.
+ */
+#[tauri::command]
+async fn save_motion_profiles(auto: Auto, options: MotionProfileOptions, directory: PathBuf) -> Result<Vec<String>, String> {
+  tauri::async_runtime::spawn_blocking(move || {
+    let file_name = PathBuf::from(file_name_for(&auto.title));
+    let stem = file_name.file_stem().and_then(|stem| stem.to_str()).unwrap_or("Untitled");
+    write_motion_profiles(&directory, stem, &auto, &options).map_err(|err| err.to_string())
+  }).await.map_err(|err| err.to_string())?
+}
+
+/**
+ * The entries of a WPILib data log that the robot's poses can be read from.
+ */
+#[tauri::command]
+async fn wpilog_pose_entries(path: PathBuf) -> Result<Vec<LogEntry>, String> {
+  tauri::async_runtime::spawn_blocking(move || {
+    let bytes = fs::read(&path).map_err(|err| err.to_string())?;
+    let log = WpiLog::parse(&bytes).map_err(|err| err.to_string())?;
+    Ok(log.pose_entries().into_iter().cloned().collect())
+  }).await.map_err(|err| err.to_string())?
+}
+
+/**
+ * Reads the poses logged while the robot drove an auto, lined up with the
+ * auto's trajectories.
+ */
+#[tauri::command]
+async fn replay_wpilog(path: PathBuf, auto: Auto, options: ReplayOptions) -> Result<Replay, String> {
+  tauri::async_runtime::spawn_blocking(move || {
+    let bytes = fs::read(&path).map_err(|err| err.to_string())?;
+    let log = WpiLog::parse(&bytes).map_err(|err| err.to_string())?;
+    replay(&log, &auto, &options).map_err(|err| err.to_string())
+  }).await.map_err(|err| err.to_string())?
+}
+
+/** How often the open project is checked for autos changed outside of the app */
+const WATCH_INTERVAL: Duration = Duration::from_secs(1);
+
+/**
+ * Sent to the window when autos in the open project are changed outside of
+ * the app, like by a git pull.
+ */
+#[derive(Clone, Serialize)]
+struct AutosChanged {
+  root: PathBuf,
+  changes: Vec<AutoChange>,
+  /** Why the deploy folder couldn't be brought up to date, if it couldn't */
+  deploy_error: Option<String>
+}
+
+/**
+ * Checks the open project for changed autos until the app exits, bringing
+ * the deploy folder up to date and letting the window know about them along
+ * with their regenerated trajectories.
+ */
+fn watch_project(app: tauri::AppHandle) {
+  loop {
+    thread::sleep(WATCH_INTERVAL);
+    let (project, mut changes) = match app.state::<ProjectWatcher>().poll() {
+      Some((project, changes)) if !changes.is_empty() => (project, changes),
+      _ => continue
+    };
+    // Half written files are picked up again once they're finished.
+    let readable = changes.iter().all(|change| change.error.is_none());
+    let deploy_error = match project.deploy_manifest() {
+      Some(manifest) if readable => project.sync_deploy(manifest.format, manifest.package.as_deref()).err().map(|err| err.to_string()),
+      _ => None
+    };
+    let field = app.state::<CurrentField>().get();
+    for change in &mut changes {
+      change.generate_on(&field);
+    }
+    let event = AutosChanged { root: project.root().to_path_buf(), changes, deploy_error };
+    if let Err(err) = app.emit_all("autos-changed", event) {
+      eprintln!("Couldn't report changed autos: {}", err);
+    }
+  }
+}
+
 fn main() {
   tauri::Builder::default()
-    .invoke_handler(tauri::generate_handler![test_for_tauri, generate_trajectory_tauri])
+    .manage(GenerationRequests::default())
+    .manage(PathModelCache::default())
+    .manage(ProjectWatcher::default())
+    .manage(CurrentField::default())
+    .setup(|app| {
+      let handle = app.handle();
+      let settings = field_settings_path(&handle).map(|path| FieldSettings::load(&path)).unwrap_or_default();
+      match settings.field() {
+        Ok(field) => app.state::<CurrentField>().set(field),
+        Err(err) => eprintln!("Couldn't load the AprilTag layout, using the field's own tags: {}", err)
+      }
+      thread::spawn(move || watch_project(handle));
+      Ok(())
+    })
+    .invoke_handler(tauri::generate_handler![
+      test_for_tauri,
+      generate_trajectory_tauri,
+      generate_auto_tauri,
+      generate_trajectory_async,
+      generate_auto_async,
+      generate_path_incremental,
+      simulate_trajectory,
+      find_path,
+      get_field,
+      april_tag_layout,
+      load_april_tag_layout,
+      open_project,
+      recent_projects,
+      list_autos,
+      load_auto,
+      save_auto,
+      import_auto,
+      import_java,
+      deploy_manifest,
+      sync_deploy,
+      generate_code,
+      save_csv,
+      save_motion_profiles,
+      wpilog_pose_entries,
+      replay_wpilog
+    ])
     .run(tauri::generate_context!())
     .expect("error while running tauri application");
 }
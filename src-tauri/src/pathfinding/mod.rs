@@ -0,0 +1,275 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::fmt;
+
+use crate::auto::Headings;
+use crate::collision::{check_collisions, Obstacle};
+use crate::field::Field;
+use crate::geometry::{Pose2d, Rotation2d, Translation2d};
+use crate::geometry::shapes::{OrientedRectangle, Polygon};
+use crate::robot::{DrivetrainType, RobotConfig};
+use crate::trajectory::{Trajectory, TrajectoryConfigNoConstraints};
+use crate::trajectory::cancellation::CancellationToken;
+use crate::trajectory::trajectory_generator::{try_generate_trajectory, GenerationError};
+
+/**
+ * Extra room in meters left between the robot and obstacles, since the
+ * splines through the waypoints cut corners slightly.
+ */
+const CLEARANCE: f64 = 0.1;
+/**
+ * How many times the waypoints are refined when the spline through them
+ * swings into an obstacle.
+ */
+const MAX_REFINEMENTS: usize = 2;
+
+#[derive(Debug)]
+pub enum PathfindingError {
+    StartBlocked,
+    GoalBlocked,
+    NoPath,
+    Generation(GenerationError)
+}
+impl fmt::Display for PathfindingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PathfindingError::StartBlocked => write!(f, "The robot overlaps an obstacle or leaves the field at the start."),
+            PathfindingError::GoalBlocked => write!(f, "The robot overlaps an obstacle or leaves the field at the goal."),
+            PathfindingError::NoPath => write!(f, "There is no way around the obstacles from the start to the goal."),
+            PathfindingError::Generation(err) => write!(f, "{}", err)
+        }
+    }
+}
+impl From<GenerationError> for PathfindingError {
+    fn from(err: GenerationError) -> Self {
+        PathfindingError::Generation(err)
+    }
+}
+
+/**
+ * The obstacles as seen by the center of the robot. Every obstacle is grown by
+ * the robot's footprint, so the robot is clear of the obstacles exactly when
+ * its center is clear of the grown ones. The field is shrunk the same way.
+ */
+struct ConfigurationSpace {
+    obstacles: Vec<Polygon>,
+    field: Polygon
+}
+impl ConfigurationSpace {
+    /**
+     * @param field     The field, for its perimeter.
+     * @param obstacles The obstacles on the field.
+     * @param robot     The shape of the robot, centered on the origin.
+     */
+    fn new(field: &Field, obstacles: &[Obstacle], robot: &Polygon) -> Self {
+        let half_length = robot.vertices().iter().fold(0.0_f64, |max, vertex| max.max(vertex.x().abs()));
+        let half_width = robot.vertices().iter().fold(0.0_f64, |max, vertex| max.max(vertex.y().abs()));
+        Self {
+            // The robot's shape is symmetric around its center, so it doesn't
+            // need to be mirrored before growing the obstacles by it.
+            obstacles: obstacles.iter().map(|obstacle| obstacle.shape.minkowski_sum(robot)).collect(),
+            field: Polygon::rectangle(half_length, half_width, field.length - half_length, field.width - half_width)
+        }
+    }
+
+    fn is_free(&self, point: &Translation2d) -> bool {
+        self.field.contains(point) && !self.obstacles.iter().any(|obstacle| obstacle.strictly_contains(point))
+    }
+
+    /**
+     * Whether the robot can drive straight between the two points. Obstacles
+     * the start or end point are already inside of are ignored, so that a
+     * robot that starts or ends within the clearance can still get out.
+     */
+    fn is_visible(&self, start: &Translation2d, end: &Translation2d) -> bool {
+        !self.obstacles.iter().any(|obstacle|
+            !obstacle.strictly_contains(start) && !obstacle.strictly_contains(end) && obstacle.crosses(start, end))
+    }
+}
+
+/** An entry of the A* open set, ordered so the heap pops the lowest estimate first. */
+struct Candidate {
+    estimate: f64,
+    node: usize
+}
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.estimate == other.estimate
+    }
+}
+impl Eq for Candidate {}
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.estimate.total_cmp(&self.estimate)
+    }
+}
+
+/**
+ * Finds the shortest path from the start to the goal around the obstacles,
+ * using A* over the visibility graph of the obstacles' corners. The returned
+ * waypoints can be passed straight to the trajectory generator, which smooths
+ * them into quintic splines. Waypoints in between face halfway between the
+ * directions of the segments on either side of them.
+ *
+ * @param start     Where the robot starts, facing the direction of travel.
+ * @param goal      Where the robot ends, facing the direction of travel.
+ * @param robot     The robot. A swerve drive keeps the heading it starts
+ * with, while a differential drive can face any way along the path.
+ * @param field     The field. Both its obstacles and its slow zones are
+ * avoided.
+ * @return The waypoints from the start to the goal.
+ */
+pub fn find_path(start: &Pose2d, goal: &Pose2d, robot: &RobotConfig, field: &Field) -> Result<Vec<Pose2d>, PathfindingError> {
+    let obstacles = field.avoided_obstacles();
+    // Only a start or goal where the robot actually collides is blocked, the
+    // robot is allowed to start or end closer to an obstacle than the
+    // clearance. A swerve drive still faces the way it started at the goal.
+    let footprint = |heading: Rotation2d| OrientedRectangle::new(
+        Pose2d::new(Translation2d::default(), heading), robot.length, robot.width).to_polygon();
+    let goal_heading = match robot.drivetrain {
+        DrivetrainType::Swerve => *start.rotation(),
+        DrivetrainType::Differential { .. } => *goal.rotation()
+    };
+    if !ConfigurationSpace::new(field, &obstacles, &footprint(*start.rotation())).is_free(start.translation()) {
+        return Err(PathfindingError::StartBlocked);
+    }
+    if !ConfigurationSpace::new(field, &obstacles, &footprint(goal_heading)).is_free(goal.translation()) {
+        return Err(PathfindingError::GoalBlocked);
+    }
+
+    let padded_footprint = match robot.drivetrain {
+        DrivetrainType::Swerve => OrientedRectangle::new(
+            Pose2d::new(Translation2d::default(), *start.rotation()),
+            robot.length + 2. * CLEARANCE, robot.width + 2. * CLEARANCE).to_polygon(),
+        // The robot turns along the path, so keep the whole circle it could
+        // turn in clear.
+        DrivetrainType::Differential { .. } =>
+            Polygon::circumscribed_octagon(robot.length.hypot(robot.width) / 2. + CLEARANCE)
+    };
+    let space = ConfigurationSpace::new(field, &obstacles, &padded_footprint);
+    let mut nodes = vec![*start.translation(), *goal.translation()];
+    for obstacle in &space.obstacles {
+        nodes.extend(obstacle.vertices().iter().filter(|vertex| space.is_free(vertex)));
+    }
+    let path = shortest_path(&space, &nodes).ok_or(PathfindingError::NoPath)?;
+
+    let mut waypoints = Vec::<Pose2d>::with_capacity(path.len());
+    waypoints.push(*start);
+    for window in path.windows(3) {
+        let (previous, corner, next) = (nodes[window[0]], nodes[window[1]], nodes[window[2]]);
+        let incoming = (corner - previous).scale_by(1. / corner.distance_to(&previous));
+        let outgoing = (next - corner).scale_by(1. / next.distance_to(&corner));
+        let direction = incoming + outgoing;
+        waypoints.push(Pose2d::new(corner, Rotation2d::from_vector(*direction.x(), *direction.y())));
+    }
+    waypoints.push(*goal);
+    Ok(waypoints)
+}
+
+/**
+ * A* from node 0 to node 1, where every pair of nodes that can see each other
+ * is connected.
+ *
+ * @return The indices of the nodes along the shortest path, or None if the
+ * goal can't be reached.
+ */
+fn shortest_path(space: &ConfigurationSpace, nodes: &[Translation2d]) -> Option<Vec<usize>> {
+    let goal = 1;
+    let mut distance = vec![f64::INFINITY; nodes.len()];
+    let mut previous = vec![usize::MAX; nodes.len()];
+    let mut open = BinaryHeap::new();
+    distance[0] = 0.;
+    open.push(Candidate { estimate: nodes[0].distance_to(&nodes[goal]), node: 0 });
+
+    while let Some(Candidate { estimate, node }) = open.pop() {
+        if node == goal {
+            let mut path = vec![goal];
+            while *path.last().unwrap() != 0 {
+                path.push(previous[*path.last().unwrap()]);
+            }
+            path.reverse();
+            return Some(path);
+        }
+        // Skip entries made stale by finding a shorter way to the node.
+        if estimate > distance[node] + nodes[node].distance_to(&nodes[goal]) {
+            continue;
+        }
+        for next in 0..nodes.len() {
+            if next == node || !space.is_visible(&nodes[node], &nodes[next]) {
+                continue;
+            }
+            let next_distance = distance[node] + nodes[node].distance_to(&nodes[next]);
+            if next_distance < distance[next] {
+                distance[next] = next_distance;
+                previous[next] = node;
+                open.push(Candidate { estimate: next_distance + nodes[next].distance_to(&nodes[goal]), node: next });
+            }
+        }
+    }
+    None
+}
+
+/**
+ * Finds a path from the start to the goal like find_path, then generates the
+ * trajectory through its waypoints. Splines can swing wide of the straight
+ * segments between waypoints, so while the trajectory hits an obstacle a
+ * waypoint is added halfway along every segment, facing along it, to pull the
+ * splines closer to the segments.
+ *
+ * @param start     Where the robot starts, facing the direction of travel.
+ * @param goal      Where the robot ends, facing the direction of travel.
+ * @param robot     The robot, whose constraints also apply to the trajectory.
+ * @param field     The field. Both its obstacles and its slow zones are
+ * avoided.
+ * @param config    The configuration for the trajectory.
+ * @return The waypoints and the trajectory through them. The trajectory may
+ * still collide if refining the waypoints didn't help.
+ */
+pub fn find_trajectory(
+    start: &Pose2d,
+    goal: &Pose2d,
+    robot: &RobotConfig,
+    field: &Field,
+    config: TrajectoryConfigNoConstraints
+) -> Result<(Vec<Pose2d>, Trajectory), PathfindingError> {
+    let mut waypoints = find_path(start, goal, robot, field)?;
+    // The trajectory is checked against the slow zones too, since they're
+    // avoided like everything else.
+    let avoided = Field { obstacles: field.avoided_obstacles(), slow_zones: Vec::new(), ..field.clone() };
+    let generate = |waypoints: &Vec<Pose2d>| {
+        let mut trajectory_config = config.to_trajectory_config();
+        trajectory_config.constraints = robot.constraints();
+        try_generate_trajectory(waypoints.clone(), trajectory_config, &CancellationToken::new())
+    };
+
+    let mut trajectory = generate(&waypoints)?;
+    for _ in 0..MAX_REFINEMENTS {
+        // A found path doesn't turn a swerve drive, it holds the start heading.
+        let headings = Headings::holding(*start.rotation());
+        if check_collisions(&trajectory, robot, &headings, &avoided).is_none() {
+            break;
+        }
+        waypoints = with_midpoints(&waypoints);
+        trajectory = generate(&waypoints)?;
+    }
+    Ok((waypoints, trajectory))
+}
+
+fn with_midpoints(waypoints: &[Pose2d]) -> Vec<Pose2d> {
+    let mut refined = Vec::<Pose2d>::with_capacity(waypoints.len() * 2 - 1);
+    for pair in waypoints.windows(2) {
+        let (from, to) = (pair[0].translation(), pair[1].translation());
+        let direction = *to - *from;
+        refined.push(pair[0]);
+        refined.push(Pose2d::new(
+            (*from + *to).scale_by(0.5),
+            Rotation2d::from_vector(*direction.x(), *direction.y())));
+    }
+    refined.push(*waypoints.last().unwrap());
+    refined
+}
@@ -0,0 +1,221 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Serialize, Deserialize};
+
+use crate::auto::{generate_auto, Auto};
+use crate::codegen::{generate_code, package_name, CodegenOptions, Language};
+use crate::export::{binary, AngleUnit, DistanceUnit};
+use crate::geometry::Pose2d;
+use crate::trajectory::Trajectory;
+
+use super::{write_atomically, Project, ProjectError};
+
+/** Where robot code reads its paths from, relative to the project root */
+const DEPLOY_DIRECTORY: [&str; 4] = ["src", "main", "deploy", "paths"];
+/** Where robot code's Java sources are, relative to the project root */
+const SOURCE_DIRECTORY: [&str; 3] = ["src", "main", "java"];
+/** The package generated Java classes go in when no other is given */
+pub const DEFAULT_JAVA_PACKAGE: &str = "frc.robot.autos";
+/** Lists what was deployed so it can be cleaned up once it's stale */
+const MANIFEST_FILE: &str = "clairvoyance-manifest.json";
+const MANIFEST_VERSION: u32 = 1;
+
+/**
+ * What gets written for every auto.
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ExportFormat {
+    /** One trajectory per path, as read by WPILib's TrajectoryUtil */
+    WpilibJson,
+    /**
+     * A Java class with the waypoints and trajectory configs of the auto,
+     * written to the robot code's sources in a package instead of the deploy
+     * folder
+     */
+    JavaConstants,
+    /** One trajectory per path in the binary format, in meters and radians */
+    Binary
+}
+
+/**
+ * The files written by the last sync. The manifest is always kept in the
+ * deploy folder, even when the files are written somewhere else.
+ */
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DeployManifest {
+    pub version: u32,
+    pub format: ExportFormat,
+    /** Package of the generated Java classes, only set for Java */
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub package: Option<String>,
+    /** Files written for every auto by the auto's file name, relative to the folder they were written to */
+    pub autos: BTreeMap<String, Vec<String>>,
+    /** Files from the previous sync that were deleted since they're no longer generated */
+    #[serde(skip_deserializing)]
+    pub removed: Vec<String>
+}
+
+/**
+ * A trajectory state as WPILib's TrajectoryUtil reads it.
+ */
+#[derive(Serialize)]
+struct WpilibState {
+    time: f64,
+    velocity: f64,
+    acceleration: f64,
+    pose: Pose2d,
+    curvature: f64
+}
+
+impl Project {
+    pub fn deploy_directory(&self) -> PathBuf {
+        DEPLOY_DIRECTORY.iter().fold(self.root.clone(), |path, directory| path.join(directory))
+    }
+
+    /**
+     * Where a sync writes its files. Java classes go in the folder of their
+     * package under the robot code's sources, and everything else in the
+     * deploy folder.
+     */
+    pub fn output_directory(&self, format: ExportFormat, package: Option<&str>) -> PathBuf {
+        match format {
+            ExportFormat::JavaConstants => {
+                // Only the parts of a package name can end up in the path, even
+                // if the manifest it came from was edited by hand.
+                let package = package_name(package.unwrap_or(DEFAULT_JAVA_PACKAGE));
+                let sources = SOURCE_DIRECTORY.iter().fold(self.root.clone(), |path, directory| path.join(directory));
+                package.split('.').filter(|part| !part.is_empty()).fold(sources, |path, part| path.join(part))
+            },
+            _ => self.deploy_directory()
+        }
+    }
+
+    /**
+     * The manifest of the last sync, or None if the project has never been
+     * synced or the manifest can't be read.
+     */
+    pub fn deploy_manifest(&self) -> Option<DeployManifest> {
+        fs::read_to_string(self.deploy_directory().join(MANIFEST_FILE)).ok()
+            .and_then(|json| serde_json::from_str::<DeployManifest>(&json).ok())
+            .filter(|manifest| manifest.version <= MANIFEST_VERSION)
+    }
+
+    /**
+     * Writes every auto in the project to the robot code, then deletes the
+     * files the last sync wrote that are no longer generated. Only files
+     * listed in the manifest are ever deleted. Nothing is written if any auto
+     * can't be read, since its deployed files would otherwise be cleaned up.
+     *
+     * @param format  What to write for every auto.
+     * @param package Package of the generated Java classes, or None for the
+     * default one. Only used for Java.
+     * @return The manifest of what was written and removed.
+     */
+    pub fn sync_deploy(&self, format: ExportFormat, package: Option<&str>) -> Result<DeployManifest, ProjectError> {
+        let package = match format {
+            ExportFormat::JavaConstants => Some(package.map(package_name)
+                .filter(|package| package.split('.').all(|part| !part.is_empty()))
+                .unwrap_or_else(|| DEFAULT_JAVA_PACKAGE.to_string())),
+            _ => None
+        };
+        let autos = self.list_autos()?.into_iter()
+            .map(|entry| self.load_auto(&entry.file_name).map(|auto| (entry.file_name, auto)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut files = BTreeMap::new();
+        for (file_name, auto) in &autos {
+            let stem = Path::new(file_name).file_stem().and_then(|stem| stem.to_str()).unwrap_or(file_name);
+            let exported = match format {
+                ExportFormat::WpilibJson => wpilib_json_files(stem, auto)?,
+                ExportFormat::JavaConstants => {
+                    let options = CodegenOptions {
+                        language: Language::Java,
+                        class_name: Some(stem.to_string()),
+                        package: package.clone(),
+                        ..CodegenOptions::default()
+                    };
+                    let generated = generate_code(auto, &options);
+                    vec![(generated.file_name, generated.code.into_bytes())]
+                },
+                ExportFormat::Binary => binary_files(stem, auto)
+            };
+            files.insert(file_name.clone(), exported);
+        }
+
+        let mut written = BTreeSet::new();
+        for name in files.values().flatten().map(|(name, _)| name) {
+            if !written.insert(name.clone()) {
+                return Err(ProjectError::DeployConflict(name.clone()));
+            }
+        }
+
+        let deploy_directory = self.deploy_directory();
+        let directory = self.output_directory(format, package.as_deref());
+        fs::create_dir_all(&deploy_directory)?;
+        fs::create_dir_all(&directory)?;
+        for (name, contents) in files.values().flatten() {
+            let path = directory.join(name);
+            // Leave unchanged files alone so robot code deploys don't resend them.
+            if fs::read(&path).ok().as_deref() != Some(contents.as_slice()) {
+                write_atomically(&path, contents)?;
+            }
+        }
+
+        let mut removed = Vec::new();
+        if let Some(previous) = self.deploy_manifest() {
+            let previous_directory = self.output_directory(previous.format, previous.package.as_deref());
+            for name in previous.autos.values().flatten() {
+                let is_plain = Path::new(name).file_name().and_then(|file_name| file_name.to_str()) == Some(name);
+                let still_written = previous_directory == directory && written.contains(name);
+                if is_plain && !still_written && previous_directory.join(name).is_file() {
+                    fs::remove_file(previous_directory.join(name))?;
+                    removed.push(name.clone());
+                }
+            }
+        }
+
+        let manifest = DeployManifest {
+            version: MANIFEST_VERSION,
+            format,
+            package,
+            autos: files.into_iter()
+                .map(|(file_name, exported)| (file_name, exported.into_iter().map(|(name, _)| name).collect()))
+                .collect(),
+            removed
+        };
+        write_atomically(&deploy_directory.join(MANIFEST_FILE), serde_json::to_string_pretty(&manifest)?.as_bytes())?;
+        Ok(manifest)
+    }
+}
+
+/**
+ * A WPILib trajectory file for every path of an auto, numbered from 1.
+ */
+fn wpilib_json_files(stem: &str, auto: &Auto) -> Result<Vec<(String, Vec<u8>)>, ProjectError> {
+    generate_auto(auto).paths().iter().enumerate()
+        .map(|(i, trajectory)| Ok((format!("{}-{}.wpilib.json", stem, i + 1), wpilib_json(trajectory)?.into_bytes())))
+        .collect()
+}
+
+/**
+ * A binary trajectory file for every path of an auto, numbered from 1.
+ */
+fn binary_files(stem: &str, auto: &Auto) -> Vec<(String, Vec<u8>)> {
+    generate_auto(auto).paths().iter().enumerate()
+        .map(|(i, trajectory)| (format!("{}-{}.bin", stem, i + 1), binary::encode(trajectory, DistanceUnit::Meters, AngleUnit::Radians)))
+        .collect()
+}
+
+pub fn wpilib_json(trajectory: &Trajectory) -> Result<String, serde_json::Error> {
+    let states: Vec<_> = trajectory.states().iter().map(|state| WpilibState {
+        time: state.t,
+        velocity: state.velocity,
+        acceleration: state.acceleration,
+        pose: state.pose,
+        curvature: state.curvature
+    }).collect();
+    serde_json::to_string(&states)
+}
@@ -0,0 +1,242 @@
+use std::{fmt, fs, io};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use serde::{Serialize, Deserialize};
+
+use crate::auto::Auto;
+use crate::auto::schema::{self, SchemaError};
+
+/** The directory inside a project where its autos are kept */
+const AUTOS_DIRECTORY: &str = "autos";
+const AUTO_EXTENSION: &str = "json";
+/** How many recently opened projects are remembered */
+const MAX_RECENT_PROJECTS: usize = 10;
+
+#[derive(Debug)]
+pub enum ProjectError {
+    Io(io::Error),
+    Parse(serde_json::Error),
+    /** The auto file is from a newer version or isn't a valid auto */
+    Schema(SchemaError),
+    /** The project's folder doesn't exist or isn't a folder */
+    NotADirectory(PathBuf),
+    /** The file name would reach outside of the project's autos */
+    InvalidFileName(String),
+    /** Two autos would be deployed to the same file */
+    DeployConflict(String)
+}
+impl fmt::Display for ProjectError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProjectError::Io(err) => write!(f, "{}", err),
+            ProjectError::Parse(err) => write!(f, "{}", err),
+            ProjectError::Schema(err) => write!(f, "{}", err),
+            ProjectError::NotADirectory(root) => write!(f, "\"{}\" isn't a folder.", root.display()),
+            ProjectError::InvalidFileName(name) => write!(f, "\"{}\" isn't a valid auto file name.", name),
+            ProjectError::DeployConflict(name) => write!(f, "More than one auto would be deployed as \"{}\". Rename one of them.", name)
+        }
+    }
+}
+impl From<io::Error> for ProjectError {
+    fn from(err: io::Error) -> Self {
+        ProjectError::Io(err)
+    }
+}
+impl From<serde_json::Error> for ProjectError {
+    fn from(err: serde_json::Error) -> Self {
+        ProjectError::Parse(err)
+    }
+}
+impl From<SchemaError> for ProjectError {
+    fn from(err: SchemaError) -> Self {
+        ProjectError::Schema(err)
+    }
+}
+
+/**
+ * An auto file in a project, as listed before it's opened.
+ */
+#[derive(Clone, Debug, Serialize)]
+pub struct AutoEntry {
+    pub file_name: String,
+    /** Title of the auto, or None if the file couldn't be read */
+    pub title: Option<String>,
+    /** Why the file couldn't be read, if it couldn't */
+    pub error: Option<String>
+}
+
+/**
+ * A directory holding the autos for a robot, one file per auto.
+ */
+#[derive(Clone, Debug)]
+pub struct Project {
+    root: PathBuf
+}
+impl Project {
+    /**
+     * Opens the project in an existing directory, adding the autos folder if
+     * it's new. A missing directory is an error rather than being created, so
+     * a mistyped path doesn't leave folders behind.
+     */
+    pub fn open(root: &Path) -> Result<Self, ProjectError> {
+        if !root.is_dir() {
+            return Err(ProjectError::NotADirectory(root.to_path_buf()));
+        }
+        let project = Self { root: root.to_path_buf() };
+        fs::create_dir_all(project.autos_directory())?;
+        Ok(project)
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    fn autos_directory(&self) -> PathBuf {
+        self.root.join(AUTOS_DIRECTORY)
+    }
+
+    /**
+     * The path of an auto file, as long as the name can't escape the
+     * project's autos.
+     */
+    fn auto_path(&self, file_name: &str) -> Result<PathBuf, ProjectError> {
+        let path = Path::new(file_name);
+        if path.file_name().and_then(|name| name.to_str()) != Some(file_name)
+                || path.extension().and_then(|extension| extension.to_str()) != Some(AUTO_EXTENSION) {
+            return Err(ProjectError::InvalidFileName(file_name.to_string()));
+        }
+        Ok(self.autos_directory().join(path))
+    }
+
+    /**
+     * The names of the auto files in the project, in order.
+     */
+    pub fn list_files(&self) -> Result<Vec<String>, ProjectError> {
+        let mut file_names = Vec::new();
+        for entry in fs::read_dir(self.autos_directory())? {
+            let path = entry?.path();
+            if path.extension().and_then(|extension| extension.to_str()) != Some(AUTO_EXTENSION) {
+                continue;
+            }
+            if let Some(name) = path.file_name().and_then(|name| name.to_str()) {
+                file_names.push(name.to_string());
+            }
+        }
+        file_names.sort();
+        Ok(file_names)
+    }
+
+    /**
+     * Lists the autos in the project by file name. Files that can't be read
+     * are still listed, with the reason they couldn't be.
+     */
+    pub fn list_autos(&self) -> Result<Vec<AutoEntry>, ProjectError> {
+        Ok(self.list_files()?.into_iter().map(|file_name| {
+            let (title, error) = match self.load_auto(&file_name) {
+                Ok(auto) => (Some(auto.title), None),
+                Err(err) => (None, Some(err.to_string()))
+            };
+            AutoEntry { file_name, title, error }
+        }).collect())
+    }
+
+    pub fn load_auto(&self, file_name: &str) -> Result<Auto, ProjectError> {
+        Ok(schema::parse_auto(&fs::read_to_string(self.auto_path(file_name)?)?)?)
+    }
+
+    /**
+     * Saves an auto into the project. The file is replaced in one step, so
+     * it's never left half written if saving fails partway.
+     *
+     * @param file_name The file to save to, or None to name it after the
+     * auto's title.
+     * @return The name of the file the auto was saved to.
+     */
+    pub fn save_auto(&self, file_name: Option<&str>, auto: &Auto) -> Result<String, ProjectError> {
+        let file_name = file_name.map_or_else(|| file_name_for(&auto.title), str::to_string);
+        write_atomically(&self.auto_path(&file_name)?, schema::to_json(auto)?.as_bytes())?;
+        Ok(file_name)
+    }
+}
+
+/**
+ * A file name for an auto, made from its title with anything that isn't safe
+ * in a file name replaced.
+ */
+pub fn file_name_for(title: &str) -> String {
+    let stem: String = title.trim().chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' || c == ' ' { c } else { '_' })
+        .collect();
+    let stem = if stem.is_empty() { "Untitled".to_string() } else { stem };
+    format!("{}.{}", stem, AUTO_EXTENSION)
+}
+
+/**
+ * Writes a file by writing a temporary file next to it and renaming it over
+ * the original. Renaming within a directory replaces the file in one step,
+ * so readers see either the old contents or the new ones.
+ */
+pub fn write_atomically(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let file_name = path.file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "no file name to write to"))?;
+    let mut temporary_name = file_name.to_os_string();
+    temporary_name.push(".tmp");
+    let temporary = path.with_file_name(temporary_name);
+
+    let result = fs::File::create(&temporary).and_then(|mut file| {
+        file.write_all(contents)?;
+        file.sync_all()
+    }).and_then(|_| fs::rename(&temporary, path));
+    if result.is_err() {
+        let _ = fs::remove_file(&temporary);
+    }
+    result
+}
+
+/**
+ * The projects opened most recently, newest first.
+ */
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct RecentProjects {
+    projects: Vec<PathBuf>
+}
+impl RecentProjects {
+    /**
+     * Reads the recent projects from a file. A missing or unreadable file
+     * just means there are no recent projects.
+     */
+    pub fn load(path: &Path) -> Self {
+        fs::read_to_string(path).ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), ProjectError> {
+        if let Some(directory) = path.parent() {
+            fs::create_dir_all(directory)?;
+        }
+        write_atomically(path, serde_json::to_string_pretty(self)?.as_bytes())?;
+        Ok(())
+    }
+
+    /**
+     * Moves a project to the front, forgetting the oldest one if there are
+     * too many.
+     */
+    pub fn add(&mut self, root: &Path) {
+        self.projects.retain(|project| project != root);
+        self.projects.insert(0, root.to_path_buf());
+        self.projects.truncate(MAX_RECENT_PROJECTS);
+    }
+
+    /**
+     * The recent projects that still exist.
+     */
+    pub fn projects(&self) -> Vec<PathBuf> {
+        self.projects.iter().filter(|project| project.is_dir()).cloned().collect()
+    }
+}
+
+pub mod deploy;
+pub mod watch;
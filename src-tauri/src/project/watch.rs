@@ -0,0 +1,159 @@
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+use std::sync::Mutex;
+
+use serde::Serialize;
+
+use crate::auto::{try_generate_auto_on, Auto, AutoTrajectory};
+use crate::field::Field;
+use crate::trajectory::cancellation::CancellationToken;
+
+use super::{Project, ProjectError};
+
+/**
+ * How an auto file changed since it was last seen.
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ChangeKind {
+    Created,
+    Modified,
+    Removed
+}
+
+/**
+ * An auto file that was changed by something other than the app, like a git
+ * pull or a text editor.
+ */
+#[derive(Debug, Serialize)]
+pub struct AutoChange {
+    pub file_name: String,
+    pub kind: ChangeKind,
+    /** The auto as it is now, or None if it was removed or can't be read */
+    pub auto: Option<Auto>,
+    /** Why the auto can't be read, which may just be that it's half written */
+    pub error: Option<String>,
+    /** The auto's trajectories, once generated, see AutoChange::generate_on */
+    pub trajectory: Option<AutoTrajectory>
+}
+impl AutoChange {
+    /**
+     * Generates the trajectories of the auto as it is now, checked against
+     * the field, so the app can show it without generating it again. Does
+     * nothing if the auto was removed or can't be read.
+     */
+    pub fn generate_on(&mut self, field: &Field) {
+        self.trajectory = self.auto.as_ref()
+            .and_then(|auto| try_generate_auto_on(auto, field, &CancellationToken::new()).ok());
+    }
+}
+
+struct Watched {
+    project: Project,
+    /** Hash of the contents of every auto file when it was last seen */
+    files: HashMap<String, u64>
+}
+impl Watched {
+    fn record(&mut self, file_name: &str) {
+        match hash_file(&self.project, file_name) {
+            Some(hash) => self.files.insert(file_name.to_string(), hash),
+            None => self.files.remove(file_name)
+        };
+    }
+}
+
+/**
+ * Watches the autos of the open project for changes made outside of the app.
+ * Files are compared by their contents, so saves that don't change anything
+ * and the app's own saves, once recorded, aren't reported.
+ */
+#[derive(Default)]
+pub struct ProjectWatcher {
+    watched: Mutex<Option<Watched>>
+}
+impl ProjectWatcher {
+    /**
+     * Starts watching a project, replacing the one watched before.
+     */
+    pub fn watch(&self, project: Project) {
+        let files = snapshot(&project);
+        *self.watched.lock().unwrap() = Some(Watched { project, files });
+    }
+
+    /**
+     * Records a file the app wrote itself, so it isn't reported as changed.
+     * A poll between writing the file and recording it would still report
+     * it, so prefer save_auto for the app's own saves.
+     */
+    pub fn record(&self, root: &Path, file_name: &str) {
+        if let Some(watched) = self.watched.lock().unwrap().as_mut().filter(|watched| watched.project.root() == root) {
+            watched.record(file_name);
+        }
+    }
+
+    /**
+     * Saves an auto into a project and records it as the app's own save. The
+     * watcher stays locked from the write until it's recorded, so a poll can
+     * never see the save and report it as a change.
+     *
+     * @return The name of the file the auto was saved to.
+     */
+    pub fn save_auto(&self, project: &Project, file_name: Option<&str>, auto: &Auto) -> Result<String, ProjectError> {
+        let mut watched = self.watched.lock().unwrap();
+        let file_name = project.save_auto(file_name, auto)?;
+        if let Some(watched) = watched.as_mut().filter(|watched| watched.project.root() == project.root()) {
+            watched.record(&file_name);
+        }
+        Ok(file_name)
+    }
+
+    /**
+     * Checks the watched project for autos that were created, modified or
+     * removed since the last check.
+     *
+     * @return The project and what changed in it, or None if no project is
+     * being watched.
+     */
+    pub fn poll(&self) -> Option<(Project, Vec<AutoChange>)> {
+        let mut watched = self.watched.lock().unwrap();
+        let watched = watched.as_mut()?;
+        let files = snapshot(&watched.project);
+
+        let mut changes = Vec::new();
+        for (file_name, hash) in &files {
+            let kind = match watched.files.get(file_name) {
+                None => ChangeKind::Created,
+                Some(previous) if previous != hash => ChangeKind::Modified,
+                Some(_) => continue
+            };
+            let (auto, error) = match watched.project.load_auto(file_name) {
+                Ok(auto) => (Some(auto), None),
+                Err(err) => (None, Some(err.to_string()))
+            };
+            changes.push(AutoChange { file_name: file_name.clone(), kind, auto, error, trajectory: None });
+        }
+        for file_name in watched.files.keys().filter(|file_name| !files.contains_key(*file_name)) {
+            changes.push(AutoChange { file_name: file_name.clone(), kind: ChangeKind::Removed, auto: None, error: None, trajectory: None });
+        }
+        changes.sort_by(|a, b| a.file_name.cmp(&b.file_name));
+
+        watched.files = files;
+        Some((watched.project.clone(), changes))
+    }
+}
+
+fn snapshot(project: &Project) -> HashMap<String, u64> {
+    project.list_files().unwrap_or_default().into_iter()
+        .filter_map(|file_name| hash_file(project, &file_name).map(|hash| (file_name, hash)))
+        .collect()
+}
+
+fn hash_file(project: &Project, file_name: &str) -> Option<u64> {
+    let contents = fs::read(project.auto_path(file_name).ok()?).ok()?;
+    let mut hasher = DefaultHasher::new();
+    contents.hash(&mut hasher);
+    Some(hasher.finish())
+}
@@ -0,0 +1,212 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+use serde::{Serialize, Deserialize};
+
+use crate::auto::{generate_auto, Auto, Headings};
+use crate::geometry::Pose2d;
+use crate::robot::RobotConfig;
+use crate::trajectory::Trajectory;
+
+use self::wpilog::{WpiLog, WpiLogError};
+
+/** Entries DriverStation.startDataLog logs the robot's mode to */
+const ENABLED_ENTRY: &str = "DS:enabled";
+const AUTONOMOUS_ENTRY: &str = "DS:autonomous";
+/** How far in meters the robot has to move before it counts as driving */
+const MOVING_DISTANCE: f64 = 0.05;
+/** How far in meters the robot can drift from where it started, from odometry noise, and still be still */
+const STILL_DISTANCE: f64 = 0.01;
+
+#[derive(Debug)]
+pub enum ReplayError {
+    Log(WpiLogError),
+    /** The log has no entries poses can be read from */
+    NoPoses,
+    /** The chosen entry isn't in the log or has no poses */
+    UnknownEntry(String)
+}
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::Log(err) => write!(f, "{}", err),
+            ReplayError::NoPoses => write!(f, "The log doesn't have any poses. Log odometry as a Pose2d struct or a Field2d."),
+            ReplayError::UnknownEntry(name) => write!(f, "The log doesn't have any poses for \"{}\".", name)
+        }
+    }
+}
+impl From<WpiLogError> for ReplayError {
+    fn from(err: WpiLogError) -> Self {
+        ReplayError::Log(err)
+    }
+}
+
+/**
+ * How to line up a log with an auto.
+ */
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ReplayOptions {
+    /** The entry the robot's poses were logged to, or None to pick the first one */
+    pub entry: Option<String>,
+    /**
+     * When the auto started in seconds since the log started, or None to
+     * use when the robot was enabled in autonomous. Logs without the driver
+     * station's mode use when the robot started moving instead.
+     */
+    pub start_time: Option<f64>,
+    /** Whether rotations in double arrays are in degrees, as Field2d logs them */
+    pub degrees: bool
+}
+
+/**
+ * Where the robot was at a point in the auto, next to where it was planned
+ * to be.
+ */
+#[derive(Clone, Copy, Debug, Serialize)]
+pub struct ReplayedState {
+    /** Time since the start of the auto */
+    pub t: f64,
+    pub pose: Pose2d,
+    pub planned: Pose2d,
+    /** Distance between the logged and planned position in meters */
+    pub error: f64
+}
+
+/**
+ * The poses logged while the robot drove an auto, lined up with its
+ * trajectories.
+ */
+#[derive(Debug, Serialize)]
+pub struct Replay {
+    /** The entry the poses were read from */
+    entry: String,
+    /** When the auto started in seconds since the log started */
+    start_time: f64,
+    states: Vec<ReplayedState>,
+    max_error: f64,
+    rms_error: f64
+}
+impl Replay {
+    pub fn entry(&self) -> &str {
+        &self.entry
+    }
+    pub const fn start_time(&self) -> &f64 {
+        &self.start_time
+    }
+    pub const fn states(&self) -> &Vec<ReplayedState> {
+        &self.states
+    }
+    pub const fn max_error(&self) -> &f64 {
+        &self.max_error
+    }
+    pub const fn rms_error(&self) -> &f64 {
+        &self.rms_error
+    }
+}
+
+/**
+ * Lines up the poses logged while the robot drove an auto with the auto's
+ * trajectories, keeping the poses logged from the start to the end of the
+ * auto.
+ *
+ * @param log     The robot's data log.
+ * @param auto    The auto the robot drove.
+ * @param options Which poses to read and when the auto started.
+ */
+pub fn replay(log: &WpiLog, auto: &Auto, options: &ReplayOptions) -> Result<Replay, ReplayError> {
+    let entry = match &options.entry {
+        Some(entry) => entry.clone(),
+        None => {
+            let entries = log.pose_entries();
+            // Struct poses are always poses, while double arrays may be something else.
+            let entry = entries.iter().find(|entry| entry.type_name.starts_with("struct:")).or_else(|| entries.first());
+            entry.ok_or(ReplayError::NoPoses)?.name.clone()
+        }
+    };
+    let poses = log.poses(&entry, options.degrees);
+    if poses.is_empty() {
+        return Err(ReplayError::UnknownEntry(entry));
+    }
+    let start_time = options.start_time
+        .or_else(|| autonomous_start(log))
+        .unwrap_or_else(|| moving_start(&poses));
+
+    let generated = generate_auto(auto);
+    let end_time = start_time + generated.total_time();
+    let states: Vec<ReplayedState> = poses.iter()
+        .filter(|(time, _)| (start_time..=end_time).contains(time))
+        .filter_map(|(time, pose)| {
+            let t = time - start_time;
+            let planned = planned_pose(generated.paths(), generated.headings(), &auto.robot, t)?;
+            Some(ReplayedState { t, pose: *pose, planned, error: pose.translation().distance_to(planned.translation()) })
+        })
+        .collect();
+
+    let max_error = states.iter().map(|state| state.error).fold(0., f64::max);
+    let rms_error = if states.is_empty() {
+        0.
+    } else {
+        (states.iter().map(|state| state.error * state.error).sum::<f64>() / states.len() as f64).sqrt()
+    };
+    Ok(Replay { entry, start_time, states, max_error, rms_error })
+}
+
+/**
+ * When the robot was first enabled in autonomous, if the log has the driver
+ * station's mode.
+ */
+fn autonomous_start(log: &WpiLog) -> Option<f64> {
+    let mut changes: Vec<(f64, bool, bool)> = log.booleans(ENABLED_ENTRY).into_iter()
+        .map(|(time, enabled)| (time, true, enabled))
+        .chain(log.booleans(AUTONOMOUS_ENTRY).into_iter().map(|(time, autonomous)| (time, false, autonomous)))
+        .collect();
+    changes.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal));
+
+    let (mut enabled, mut autonomous) = (false, false);
+    for (time, is_enabled, value) in changes {
+        if is_enabled {
+            enabled = value;
+        } else {
+            autonomous = value;
+        }
+        if enabled && autonomous {
+            return Some(time);
+        }
+    }
+    None
+}
+
+/**
+ * The last time the robot was still before it started moving, or the first
+ * pose if it never moved. Moving is only counted once the robot is well
+ * away from where it started, so noise doesn't look like driving, then
+ * traced back to when it left.
+ */
+fn moving_start(poses: &[(f64, Pose2d)]) -> f64 {
+    let first = poses[0].1.translation();
+    let distance = |pose: &Pose2d| pose.translation().distance_to(first);
+    poses.iter()
+        .position(|(_, pose)| distance(pose) > MOVING_DISTANCE)
+        .and_then(|moving| poses[..moving].iter().rposition(|(_, pose)| distance(pose) <= STILL_DISTANCE))
+        .map_or(poses[0].0, |still| poses[still].0)
+}
+
+/**
+ * Where the robot is planned to be at a time since the start of the auto,
+ * facing the way a swerve drive turns to along each path.
+ */
+fn planned_pose(paths: &[Trajectory], headings: &[Headings], robot: &RobotConfig, t: f64) -> Option<Pose2d> {
+    let paths: Vec<(&Trajectory, &Headings)> = paths.iter().zip(headings).filter(|(path, _)| path.num_states() > 0).collect();
+    let mut start_time = 0.;
+    for (i, (path, headings)) in paths.iter().enumerate() {
+        if t <= start_time + path.total_time() || i == paths.len() - 1 {
+            let state = path.sample(t - start_time);
+            return Some(robot.pose_at(&state, headings.at(t - start_time)));
+        }
+        start_time += path.total_time();
+    }
+    None
+}
+
+pub mod wpilog;
@@ -0,0 +1,252 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::geometry::{Pose2d, Rotation2d, Translation2d};
+
+const MAGIC: &[u8] = b"WPILOG";
+/** Version 1.0, the only one WPILib writes */
+const VERSION: u16 = 0x0100;
+/** Records for this entry id start, finish and describe the other entries */
+const CONTROL_ENTRY: u32 = 0;
+const CONTROL_START: u8 = 0;
+const CONTROL_FINISH: u8 = 1;
+const CONTROL_SET_METADATA: u8 = 2;
+/** Size of a pose as three doubles, x, y and rotation, in a struct or an array */
+const POSE_SIZE: usize = 24;
+
+#[derive(Debug, PartialEq)]
+pub enum WpiLogError {
+    /** The data doesn't start with the WPILOG magic bytes */
+    NotAWpiLog,
+    UnsupportedVersion(u16),
+    /** The log ends partway through its header, at the given offset */
+    Truncated(usize),
+    /** A control record at the given offset can't be read */
+    InvalidControlRecord(usize)
+}
+impl fmt::Display for WpiLogError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WpiLogError::NotAWpiLog => write!(f, "This isn't a WPILib data log."),
+            WpiLogError::UnsupportedVersion(version) => write!(f, "Version {}.{} data logs can't be read.", version >> 8, version & 0xFF),
+            WpiLogError::Truncated(offset) => write!(f, "The log ends partway through the record at byte {}.", offset),
+            WpiLogError::InvalidControlRecord(offset) => write!(f, "The control record at byte {} can't be read.", offset)
+        }
+    }
+}
+
+/**
+ * An entry of a data log, like a logged value or a NetworkTables topic.
+ */
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct LogEntry {
+    pub name: String,
+    /** The type of the entry's data, like double[] or struct:Pose2d */
+    pub type_name: String,
+    pub metadata: String
+}
+
+/**
+ * A value logged for an entry.
+ */
+#[derive(Clone, Copy, Debug)]
+pub struct Record<'a> {
+    /** Index of the entry in the log's entries */
+    pub entry: usize,
+    /** Time the value was logged in microseconds */
+    pub timestamp: u64,
+    pub data: &'a [u8]
+}
+
+/**
+ * A parsed WPILib data log, borrowing the values of its records from the
+ * bytes it was read from.
+ */
+#[derive(Clone, Debug)]
+pub struct WpiLog<'a> {
+    /** Extra information written with the log's header */
+    pub extra_header: String,
+    /**
+     * Every entry started in the log. An id reused after its entry is
+     * finished starts a new entry.
+     */
+    pub entries: Vec<LogEntry>,
+    /** The data records in the order they were written */
+    pub records: Vec<Record<'a>>
+}
+impl<'a> WpiLog<'a> {
+    /**
+     * Reads a data log in the format written by WPILib's DataLog. A log that
+     * ends partway through its last record, like when the robot lost power
+     * while writing it, is read up to the last complete record.
+     */
+    pub fn parse(bytes: &'a [u8]) -> Result<Self, WpiLogError> {
+        if !bytes.starts_with(MAGIC) {
+            return Err(WpiLogError::NotAWpiLog);
+        }
+        let mut reader = Reader { bytes, position: MAGIC.len() };
+        let version = reader.int(2).ok_or(WpiLogError::Truncated(0))? as u16;
+        if version >> 8 != VERSION >> 8 {
+            return Err(WpiLogError::UnsupportedVersion(version));
+        }
+        let extra_header = reader.string().ok_or(WpiLogError::Truncated(0))?;
+
+        let mut log = Self { extra_header, entries: Vec::new(), records: Vec::new() };
+        // Index into entries of every entry id currently started.
+        let mut started: HashMap<u32, usize> = HashMap::new();
+        while reader.position < bytes.len() {
+            let offset = reader.position;
+            let (id, timestamp, data) = match reader.record() {
+                Some(record) => record,
+                None => break
+            };
+            if id == CONTROL_ENTRY {
+                log.control(data, &mut started).ok_or(WpiLogError::InvalidControlRecord(offset))?;
+            } else if let Some(&entry) = started.get(&id) {
+                log.records.push(Record { entry, timestamp, data });
+            }
+            // Records for entries that were never started are skipped, as WPILib does.
+        }
+        Ok(log)
+    }
+
+    /** Starts, finishes or sets the metadata of an entry */
+    fn control(&mut self, data: &[u8], started: &mut HashMap<u32, usize>) -> Option<()> {
+        let mut reader = Reader { bytes: data, position: 1 };
+        let id = reader.int(4)? as u32;
+        match *data.first()? {
+            CONTROL_START => {
+                let entry = LogEntry { name: reader.string()?, type_name: reader.string()?, metadata: reader.string()? };
+                started.insert(id, self.entries.len());
+                self.entries.push(entry);
+            },
+            CONTROL_FINISH => {
+                started.remove(&id);
+            },
+            CONTROL_SET_METADATA => {
+                let metadata = reader.string()?;
+                let &entry = started.get(&id)?;
+                self.entries[entry].metadata = metadata;
+            },
+            _ => return None
+        }
+        Some(())
+    }
+
+    /**
+     * The entries that poses can be read from: struct encoded Pose2ds, and
+     * double arrays of poses like the ones Field2d logs.
+     */
+    pub fn pose_entries(&self) -> Vec<&LogEntry> {
+        let mut holds_poses = vec![false; self.entries.len()];
+        for record in &self.records {
+            holds_poses[record.entry] |= !record.data.is_empty() && record.data.len() % POSE_SIZE == 0;
+        }
+        let mut names: Vec<&str> = Vec::new();
+        self.entries.iter().enumerate()
+            .filter(|(i, entry)| match entry.type_name.as_str() {
+                "struct:Pose2d" | "struct:Pose2d[]" => true,
+                "double[]" => holds_poses[*i],
+                _ => false
+            })
+            .map(|(_, entry)| entry)
+            .filter(|entry| {
+                let new = !names.contains(&entry.name.as_str());
+                names.push(&entry.name);
+                new
+            })
+            .collect()
+    }
+
+    /**
+     * Every pose logged for an entry, with the time it was logged in
+     * seconds. Only the first pose of an array is read. Records that aren't
+     * a pose, like empty arrays, are skipped.
+     *
+     * @param name                 The name of the entry.
+     * @param double_array_degrees Whether rotations in double arrays are in
+     * degrees, as Field2d logs them, rather than radians.
+     */
+    pub fn poses(&self, name: &str, double_array_degrees: bool) -> Vec<(f64, Pose2d)> {
+        self.records.iter()
+            .filter(|record| self.entries[record.entry].name == name)
+            .filter_map(|record| {
+                let pose = match self.entries[record.entry].type_name.as_str() {
+                    "struct:Pose2d" | "struct:Pose2d[]" if record.data.len() >= POSE_SIZE => {
+                        let value = |i: usize| f64_at(record.data, i * 8);
+                        Pose2d::new(Translation2d::new(value(0), value(1)), Rotation2d::from_radians(value(2)))
+                    },
+                    "double[]" if record.data.len() >= POSE_SIZE => {
+                        let value = |i: usize| f64_at(record.data, i * 8);
+                        let rotation = if double_array_degrees { Rotation2d::from_degrees(value(2)) } else { Rotation2d::from_radians(value(2)) };
+                        Pose2d::new(Translation2d::new(value(0), value(1)), rotation)
+                    },
+                    _ => return None
+                };
+                Some((record.timestamp as f64 / 1E6, pose))
+            })
+            .collect()
+    }
+
+    /**
+     * Every boolean logged for an entry, with the time it was logged in
+     * seconds.
+     */
+    pub fn booleans(&self, name: &str) -> Vec<(f64, bool)> {
+        self.records.iter()
+            .filter(|record| {
+                let entry = &self.entries[record.entry];
+                entry.name == name && entry.type_name == "boolean" && record.data.len() == 1
+            })
+            .map(|record| (record.timestamp as f64 / 1E6, record.data[0] != 0))
+            .collect()
+    }
+}
+
+fn f64_at(data: &[u8], offset: usize) -> f64 {
+    let mut bytes = [0; 8];
+    bytes.copy_from_slice(&data[offset..offset + 8]);
+    f64::from_le_bytes(bytes)
+}
+
+/**
+ * Reads the little-endian values of a log.
+ */
+struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize
+}
+impl<'a> Reader<'a> {
+    fn take(&mut self, length: usize) -> Option<&'a [u8]> {
+        let taken = self.bytes.get(self.position..self.position.checked_add(length)?)?;
+        self.position += length;
+        Some(taken)
+    }
+
+    /** An unsigned integer of 1 to 8 bytes */
+    fn int(&mut self, length: usize) -> Option<u64> {
+        Some(self.take(length)?.iter().rev().fold(0, |value, byte| value << 8 | u64::from(*byte)))
+    }
+
+    /** A UTF-8 string prefixed by its length as a u32 */
+    fn string(&mut self) -> Option<String> {
+        let length = self.int(4)? as usize;
+        Some(String::from_utf8_lossy(self.take(length)?).into_owned())
+    }
+
+    /**
+     * A record, whose first byte gives the lengths of its entry id, payload
+     * size and timestamp, which follow it.
+     *
+     * @return The entry id, timestamp and payload of the record.
+     */
+    fn record(&mut self) -> Option<(u32, u64, &'a [u8])> {
+        let lengths = *self.take(1)?.first()?;
+        let id = self.int(usize::from(lengths & 0x3) + 1)? as u32;
+        let size = self.int(usize::from(lengths >> 2 & 0x3) + 1)? as usize;
+        let timestamp = self.int(usize::from(lengths >> 4 & 0x7) + 1)?;
+        Some((id, timestamp, self.take(size)?))
+    }
+}
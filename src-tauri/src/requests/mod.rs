@@ -0,0 +1,80 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::geometry::Pose2d;
+use crate::trajectory::cancellation::CancellationToken;
+use crate::trajectory::path_model::PathModel;
+use crate::trajectory::spline_parameterizer::MalformedSplineError;
+
+/**
+ * Keeps track of the latest generation request for every path so that a
+ * newer request can cancel a stale one that is still being generated.
+ * Request ids are expected to increase for every new request of a path.
+ */
+#[derive(Default)]
+pub struct GenerationRequests {
+    in_flight: Mutex<HashMap<String, (u64, CancellationToken)>>
+}
+impl GenerationRequests {
+    /**
+     * Registers a new request, cancelling any older request for the same path.
+     *
+     * @param path_id    Identifies the path being generated.
+     * @param request_id Id of this request, increasing for every new request.
+     * @return The token to generate the request with, or None if a newer
+     * request for the path has already been made.
+     */
+    pub fn begin(&self, path_id: &str, request_id: u64) -> Option<CancellationToken> {
+        let mut in_flight = self.in_flight.lock().unwrap();
+        if let Some((latest_id, token)) = in_flight.get(path_id) {
+            if *latest_id > request_id {
+                return None;
+            }
+            token.cancel();
+        }
+        let token = CancellationToken::new();
+        in_flight.insert(path_id.to_string(), (request_id, token.clone()));
+        Some(token)
+    }
+
+    /**
+     * Removes a finished request, unless it has already been superseded.
+     */
+    pub fn finish(&self, path_id: &str, request_id: u64) {
+        let mut in_flight = self.in_flight.lock().unwrap();
+        if in_flight.get(path_id).is_some_and(|(latest_id, _)| *latest_id == request_id) {
+            in_flight.remove(path_id);
+        }
+    }
+}
+
+/**
+ * Cached path models keyed by path id, used to regenerate a path
+ * incrementally when only some of its waypoints moved.
+ */
+#[derive(Default)]
+pub struct PathModelCache {
+    models: Mutex<HashMap<String, PathModel>>
+}
+impl PathModelCache {
+    /**
+     * Brings the cached model for a path in line with the given waypoints,
+     * creating it if needed, and returns it to be used for generation. The
+     * model is taken out of the cache so that other paths aren't blocked while
+     * it is being updated, and has to be handed back with put_back.
+     */
+    pub fn take_updated(&self, path_id: &str, waypoints: &[Pose2d], reversed: bool) -> Result<PathModel, MalformedSplineError> {
+        let cached = self.models.lock().unwrap().remove(path_id);
+        match cached {
+            Some(mut model) => {
+                model.update(waypoints, reversed)?;
+                Ok(model)
+            },
+            None => PathModel::new(waypoints.to_vec(), reversed)
+        }
+    }
+
+    pub fn put_back(&self, path_id: &str, model: PathModel) {
+        self.models.lock().unwrap().insert(path_id.to_string(), model);
+    }
+}
@@ -0,0 +1,219 @@
+use serde::{Serialize, Deserialize};
+
+use crate::auto::Headings;
+use crate::kinematics::{ChassisSpeeds, DifferentialDriveKinematics, SwerveDriveKinematics, SwerveModuleState, WheelStates};
+use crate::geometry::{Pose2d, Rotation2d};
+use crate::geometry::shapes::OrientedRectangle;
+use crate::trajectory::{Trajectory, TrajectoryConstraint, TrajectoryState};
+use crate::trajectory::constraints::{DifferentialDriveKinematicsConstraint, DifferentialDriveVoltageConstraint};
+use crate::vision::Camera;
+
+/**
+ * Returns the sign of the value, or 0 if the value is 0. Unlike f64::signum,
+ * which treats 0 as positive.
+ */
+fn sign(value: f64) -> f64 {
+    if value == 0. { 0. } else { value.signum() }
+}
+
+/**
+ * A feedforward for a permanent magnet DC motor, in volts, from its
+ * characterized static, velocity and acceleration gains.
+ */
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct SimpleMotorFeedforward {
+    /** Static gain in volts */
+    pub ks: f64,
+    /** Velocity gain in V/(m/s) */
+    pub kv: f64,
+    /** Acceleration gain in V/(m/s²) */
+    pub ka: f64
+}
+impl SimpleMotorFeedforward {
+    pub const fn new(ks: f64, kv: f64, ka: f64) -> Self {
+        Self { ks, kv, ka }
+    }
+
+    /**
+     * Calculates the voltage needed for the given velocity and acceleration.
+     */
+    pub fn calculate(&self, velocity: f64, acceleration: f64) -> f64 {
+        self.ks * sign(velocity) + self.kv * velocity + self.ka * acceleration
+    }
+
+    /**
+     * Calculates the maximum achievable acceleration given a maximum voltage
+     * supply and a velocity. Useful for ensuring that velocity and
+     * acceleration constraints for a trapezoidal profile are simultaneously
+     * achievable - enter the acceleration constraint, and this will give you
+     * a simultaneously-achievable velocity constraint.
+     *
+     * @param max_voltage The maximum voltage that can be supplied to the
+     * motor.
+     * @param velocity    The velocity of the motor.
+     */
+    pub fn max_achievable_acceleration(&self, max_voltage: f64, velocity: f64) -> f64 {
+        (max_voltage - self.ks * sign(velocity) - velocity * self.kv) / self.ka
+    }
+
+    /**
+     * Calculates the minimum achievable acceleration given a maximum voltage
+     * supply and a velocity.
+     *
+     * @param max_voltage The maximum voltage that can be supplied to the
+     * motor.
+     * @param velocity    The velocity of the motor.
+     */
+    pub fn min_achievable_acceleration(&self, max_voltage: f64, velocity: f64) -> f64 {
+        self.max_achievable_acceleration(-max_voltage, velocity)
+    }
+}
+
+/**
+ * The kind of drivetrain the robot has, along with the limits specific to it.
+ */
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum DrivetrainType {
+    /** A swerve drive, which the trajectory doesn't need to know more about */
+    #[default]
+    Swerve,
+    /** A differential (tank) drive */
+    #[serde(rename_all = "camelCase")]
+    Differential {
+        /** Top speed of either side of the drivetrain in m/s */
+        max_wheel_speed: f64,
+        /**
+         * Feedforward of either side of the drivetrain. If set, trajectories
+         * are limited to what the motors can do with max_voltage.
+         */
+        feedforward: Option<SimpleMotorFeedforward>,
+        /** Voltage available to the drivetrain, usually below battery voltage */
+        max_voltage: f64
+    }
+}
+
+const fn default_wheel_diameter() -> f64 {
+    0.1016 // 4 in
+}
+
+const fn default_gear_ratio() -> f64 {
+    6.75 // A common swerve module drive ratio
+}
+
+/**
+ * Physical dimensions of the robot, in meters.
+ */
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RobotConfig {
+    pub width: f64,
+    pub length: f64,
+    /** Distance between the front and back wheels */
+    pub wheelbase: f64,
+    /** Distance between the left and right wheels */
+    pub track_width: f64,
+    #[serde(default)]
+    pub drivetrain: DrivetrainType,
+    /** Diameter of the drive wheels */
+    #[serde(default = "default_wheel_diameter")]
+    pub wheel_diameter: f64,
+    /** Rotations of a drive motor for every rotation of its wheel */
+    #[serde(default = "default_gear_ratio")]
+    pub gear_ratio: f64,
+    /** The camera used to see AprilTags, if the robot has one */
+    #[serde(default)]
+    pub camera: Option<Camera>
+}
+impl RobotConfig {
+    /**
+     * The constraints every trajectory driven by this robot has to respect on
+     * top of its max velocity and acceleration.
+     */
+    pub fn constraints(&self) -> Vec<Box<dyn TrajectoryConstraint>> {
+        let mut constraints = Vec::<Box<dyn TrajectoryConstraint>>::new();
+        if let DrivetrainType::Differential { max_wheel_speed, feedforward, max_voltage } = self.drivetrain {
+            let kinematics = DifferentialDriveKinematics::new(self.track_width);
+            constraints.push(Box::new(DifferentialDriveKinematicsConstraint::new(kinematics, max_wheel_speed)));
+            if let Some(feedforward) = feedforward {
+                constraints.push(Box::new(DifferentialDriveVoltageConstraint::new(feedforward, kinematics, max_voltage)));
+            }
+        }
+        constraints
+    }
+
+    /**
+     * The pose of the robot at a state of a trajectory. A differential drive
+     * faces the direction of travel, while a swerve drive faces whichever way
+     * it's told to, see auto::Headings.
+     *
+     * @param state   The state of the trajectory.
+     * @param heading Which way a swerve drive faces at the state.
+     */
+    pub fn pose_at(&self, state: &TrajectoryState, heading: Rotation2d) -> Pose2d {
+        match self.drivetrain {
+            DrivetrainType::Swerve => Pose2d::new(*state.pose.translation(), heading),
+            DrivetrainType::Differential { .. } => state.pose
+        }
+    }
+
+    /**
+     * The outline of the robot's frame when it's at the given pose.
+     */
+    pub fn footprint(&self, pose: Pose2d) -> OrientedRectangle {
+        OrientedRectangle::new(pose, self.length, self.width)
+    }
+
+    /**
+     * Works out what the wheels of the robot do at every state of the
+     * trajectory. A swerve drive's modules both drive it along the path and
+     * turn it between the orientations of the waypoints.
+     *
+     * @param trajectory The trajectory driven by the robot.
+     * @param headings   Which way a swerve drive faces along the trajectory.
+     * @return The wheel states for every state of the trajectory, in the same
+     * order.
+     */
+    pub fn wheel_states(&self, trajectory: &Trajectory, headings: &Headings) -> Vec<WheelStates> {
+        if trajectory.num_states() == 0 {
+            return Vec::new();
+        }
+        match self.drivetrain {
+            DrivetrainType::Swerve => {
+                let kinematics = SwerveDriveKinematics::from_dimensions(self.wheelbase, self.track_width);
+                let mut previous: Option<[SwerveModuleState; 4]> = None;
+                trajectory.states().iter().map(|state| {
+                    let direction = state.pose.rotation();
+                    let chassis_speeds = ChassisSpeeds::from_field_relative_speeds(
+                        state.velocity * direction.cos(), state.velocity * direction.sin(),
+                        headings.angular_velocity(state.t), headings.at(state.t));
+                    let modules = kinematics.to_module_states(&chassis_speeds, previous.as_ref());
+                    previous = Some(modules);
+                    WheelStates::Swerve { modules }
+                }).collect()
+            },
+            DrivetrainType::Differential { .. } => {
+                let kinematics = DifferentialDriveKinematics::new(self.track_width);
+                trajectory.states().iter().map(|state| {
+                    let wheel_speeds = kinematics.to_wheel_speeds(
+                        &ChassisSpeeds::new(state.velocity, 0., state.velocity * state.curvature));
+                    WheelStates::Differential { left: wheel_speeds.left, right: wheel_speeds.right }
+                }).collect()
+            }
+        }
+    }
+}
+impl Default for RobotConfig {
+    fn default() -> Self {
+        Self {
+            width: 0.762, // 30 in
+            length: 0.762, // 30 in
+            wheelbase: 0.6096, // 24 in
+            track_width: 0.6096, // 24 in
+            drivetrain: DrivetrainType::default(),
+            wheel_diameter: default_wheel_diameter(),
+            gear_ratio: default_gear_ratio(),
+            camera: None
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use std::f64::consts::PI;
+
+use crate::geometry::{Pose2d, Rotation2d};
+use crate::kinematics::ChassisSpeeds;
+use crate::trajectory::TrajectoryState;
+
+/**
+ * Wraps an angle in radians to the range [-pi, pi).
+ */
+pub fn angle_modulus(radians: f64) -> f64 {
+    (radians + PI).rem_euclid(2. * PI) - PI
+}
+
+/**
+ * Follows a trajectory with a holonomic drivetrain. The trajectory's velocity
+ * is used as a feedforward and proportional controllers on the x, y and
+ * heading errors correct for disturbances. Heading is controlled separately
+ * from the direction of travel, so the desired heading is passed in
+ * separately from the trajectory state.
+ */
+pub struct HolonomicDriveController {
+    translation_kp: f64,
+    rotation_kp: f64
+}
+impl HolonomicDriveController {
+    pub const fn new(translation_kp: f64, rotation_kp: f64) -> Self {
+        Self { translation_kp, rotation_kp }
+    }
+
+    /**
+     * Returns the robot relative speeds that move the robot towards the
+     * desired state.
+     *
+     * @param current_pose    The current pose of the robot.
+     * @param desired_state   The trajectory state the robot should be at.
+     * @param desired_heading The heading the robot should be facing.
+     */
+    pub fn calculate(&self, current_pose: &Pose2d, desired_state: &TrajectoryState, desired_heading: Rotation2d) -> ChassisSpeeds {
+        // Calculate feedforward velocities (field-relative).
+        let x_ff = desired_state.velocity * desired_state.pose.rotation().cos();
+        let y_ff = desired_state.velocity * desired_state.pose.rotation().sin();
+
+        let x_fb = self.translation_kp
+            * (desired_state.pose.translation().x() - current_pose.translation().x());
+        let y_fb = self.translation_kp
+            * (desired_state.pose.translation().y() - current_pose.translation().y());
+        let theta_fb = self.rotation_kp
+            * angle_modulus(desired_heading.radians() - current_pose.rotation().radians());
+
+        ChassisSpeeds::from_field_relative_speeds(
+            x_ff + x_fb, y_ff + y_fb, theta_fb, *current_pose.rotation())
+    }
+}
+
+/**
+ * Ramsete is a nonlinear time-varying feedback controller for unicycle models
+ * that drives the model to a desired pose along a two-dimensional trajectory.
+ * See <https://file.tavsys.net/control/controls-engineering-in-frc.pdf>
+ * section on Ramsete unicycle controller for a derivation and analysis.
+ */
+pub struct RamseteController {
+    b: f64,
+    zeta: f64
+}
+impl RamseteController {
+    /**
+     * @param b    Tuning parameter (b > 0 rad²/m²) for which larger values
+     * make convergence more aggressive like a proportional term.
+     * @param zeta Tuning parameter (0 rad⁻¹ < zeta < 1 rad⁻¹) for which larger
+     * values provide more damping in response.
+     */
+    pub const fn new(b: f64, zeta: f64) -> Self {
+        Self { b, zeta }
+    }
+
+    /**
+     * Returns the linear and angular velocity, as a ChassisSpeeds with no
+     * sideways velocity, that moves the robot towards the desired state.
+     *
+     * @param current_pose  The current pose of the robot.
+     * @param desired_state The trajectory state the robot should be at.
+     */
+    pub fn calculate(&self, current_pose: &Pose2d, desired_state: &TrajectoryState) -> ChassisSpeeds {
+        let linear_velocity_ref = desired_state.velocity;
+        let angular_velocity_ref = desired_state.velocity * desired_state.curvature;
+
+        let error = desired_state.pose.relative_to(current_pose);
+
+        // Aliases for equation readability
+        let e_x = *error.translation().x();
+        let e_y = *error.translation().y();
+        let e_theta = *error.rotation().radians();
+        let v_ref = linear_velocity_ref;
+        let omega_ref = angular_velocity_ref;
+
+        let k = 2.0 * self.zeta * (omega_ref * omega_ref + self.b * v_ref * v_ref).sqrt();
+
+        ChassisSpeeds::new(
+            v_ref * error.rotation().cos() + k * e_x,
+            0.0,
+            omega_ref + k * e_theta + self.b * v_ref * sinc(e_theta) * e_y
+        )
+    }
+}
+
+/**
+ * Returns sin(x) / x, which is 1 at x = 0.
+ */
+fn sinc(x: f64) -> f64 {
+    if x.abs() < 1E-9 {
+        1.0 - 1.0 / 6.0 * x * x
+    } else {
+        x.sin() / x
+    }
+}
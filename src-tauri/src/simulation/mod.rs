@@ -0,0 +1,225 @@
+use std::fmt;
+
+use serde::{Serialize, Deserialize};
+
+use crate::auto::Headings;
+use crate::geometry::{Pose2d, Twist2d};
+use crate::kinematics::{ChassisSpeeds, DifferentialDriveKinematics, SwerveDriveKinematics};
+use crate::robot::RobotConfig;
+use crate::trajectory::Trajectory;
+
+use self::controllers::{HolonomicDriveController, RamseteController};
+
+pub mod controllers;
+
+/**
+ * The controller used to follow the trajectory, which also decides how the
+ * drivetrain is modeled.
+ */
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum Controller {
+    /** A swerve or mecanum drive, turning between the waypoints' orientations. */
+    #[serde(rename_all = "camelCase")]
+    Holonomic { translation_kp: f64, rotation_kp: f64 },
+    /** A differential drive. */
+    #[serde(rename_all = "camelCase")]
+    Ramsete { b: f64, zeta: f64 }
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulationConfig {
+    pub controller: Controller,
+    pub robot: RobotConfig,
+    /** Top speed of a single wheel or module in m/s */
+    pub max_wheel_speed: f64,
+    /**
+     * How long in seconds the drivetrain takes to get about two thirds of the
+     * way to a commanded speed. Zero means commands are followed instantly.
+     */
+    pub time_constant: f64,
+    /** Simulation timestep in seconds, usually the robot loop period */
+    pub dt: f64
+}
+impl Default for SimulationConfig {
+    fn default() -> Self {
+        Self {
+            controller: Controller::Holonomic { translation_kp: 1.0, rotation_kp: 1.0 },
+            robot: RobotConfig::default(),
+            max_wheel_speed: 4.5,
+            time_constant: 0.1,
+            dt: 0.02
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Serialize)]
+pub struct SimulatedState {
+    pub t: f64,
+    pub pose: Pose2d,
+    /** Distance between the simulated and planned position in meters */
+    pub error: f64
+}
+
+#[derive(Serialize, Debug)]
+pub struct SimulationResult {
+    states: Vec<SimulatedState>,
+    max_error: f64,
+    rms_error: f64
+}
+impl SimulationResult {
+    pub const fn states(&self) -> &Vec<SimulatedState> {
+        &self.states
+    }
+    pub const fn max_error(&self) -> &f64 {
+        &self.max_error
+    }
+    pub const fn rms_error(&self) -> &f64 {
+        &self.rms_error
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SimulationError {
+    /** The timestep was not a positive number of seconds */
+    InvalidTimestep(f64),
+    /** The time constant was negative or not a number */
+    InvalidTimeConstant(f64)
+}
+impl fmt::Display for SimulationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SimulationError::InvalidTimestep(dt) =>
+                write!(f, "The simulation timestep must be a positive number of seconds, not {}", dt),
+            SimulationError::InvalidTimeConstant(time_constant) =>
+                write!(f, "The drivetrain time constant can't be negative, but was {}", time_constant)
+        }
+    }
+}
+
+/**
+ * Moves the actual speed towards the commanded speed as a first order system
+ * with the given time constant.
+ */
+fn approach(actual: f64, commanded: f64, time_constant: f64, dt: f64) -> f64 {
+    actual + (commanded - actual) * dt / (time_constant + dt)
+}
+
+/**
+ * A very simple drivetrain model. The chassis speeds of the robot lag behind
+ * the commanded speeds and are limited by how fast the wheels can spin.
+ */
+struct Drivetrain {
+    config: SimulationConfig,
+    speeds: ChassisSpeeds
+}
+impl Drivetrain {
+    /**
+     * Applies the commanded speeds for a timestep and returns how the robot
+     * moved during it.
+     */
+    fn step(&mut self, commanded: ChassisSpeeds) -> Twist2d {
+        let limited = match self.config.controller {
+            Controller::Holonomic { .. } => {
+                let kinematics = SwerveDriveKinematics::from_dimensions(
+                    self.config.robot.wheelbase, self.config.robot.track_width);
+                let fastest_module = kinematics.to_module_states(&commanded, None).iter()
+                    .fold(0.0_f64, |fastest, module| fastest.max(module.speed));
+                let scale = if fastest_module > self.config.max_wheel_speed {
+                    self.config.max_wheel_speed / fastest_module
+                } else {
+                    1.
+                };
+                ChassisSpeeds::new(commanded.vx * scale, commanded.vy * scale, commanded.omega * scale)
+            },
+            Controller::Ramsete { .. } => {
+                let kinematics = DifferentialDriveKinematics::new(self.config.robot.track_width);
+                let wheel_speeds = kinematics.to_wheel_speeds(&commanded).desaturate(self.config.max_wheel_speed);
+                kinematics.to_chassis_speeds(&wheel_speeds)
+            }
+        };
+
+        let time_constant = self.config.time_constant;
+        let dt = self.config.dt;
+        self.speeds = ChassisSpeeds::new(
+            approach(self.speeds.vx, limited.vx, time_constant, dt),
+            approach(self.speeds.vy, limited.vy, time_constant, dt),
+            approach(self.speeds.omega, limited.omega, time_constant, dt)
+        );
+        Twist2d { dx: self.speeds.vx * dt, dy: self.speeds.vy * dt, dth: self.speeds.omega * dt }
+    }
+}
+
+/**
+ * Simulates a robot following the trajectory, starting exactly on its first
+ * state, and measures how far it strays from the plan.
+ *
+ * @param trajectory The trajectory to follow.
+ * @param headings   Which way a holonomic drive faces along the trajectory.
+ * @param config     The controller and drivetrain to simulate.
+ * @return The simulated pose at every timestep and the tracking error, or why
+ * the config can't be simulated.
+ */
+pub fn simulate(trajectory: &Trajectory, headings: &Headings, config: &SimulationConfig) -> Result<SimulationResult, SimulationError> {
+    let start = trajectory.states().first().map_or_else(Pose2d::default, |state| match config.controller {
+        Controller::Holonomic { .. } => Pose2d::new(*state.pose.translation(), headings.at(state.t)),
+        Controller::Ramsete { .. } => state.pose
+    });
+    simulate_from(trajectory, headings, config, start)
+}
+
+/**
+ * Simulates a robot following the trajectory from some other starting pose,
+ * such as one where it was placed slightly off of the start of the path.
+ *
+ * @param trajectory The trajectory to follow.
+ * @param headings   Which way a holonomic drive faces along the trajectory.
+ *                   A differential drive faces along the path instead.
+ * @param config     The controller and drivetrain to simulate.
+ * @param start      Where the robot starts.
+ * @return The simulated pose at every timestep and the tracking error, or why
+ * the config can't be simulated.
+ */
+pub fn simulate_from(trajectory: &Trajectory, headings: &Headings, config: &SimulationConfig, start: Pose2d) -> Result<SimulationResult, SimulationError> {
+    // A timestep that isn't positive would never reach the end of the
+    // trajectory, and a negative time constant makes the model unstable.
+    if !config.dt.is_finite() || config.dt <= 0. {
+        return Err(SimulationError::InvalidTimestep(config.dt));
+    }
+    if !config.time_constant.is_finite() || config.time_constant < 0. {
+        return Err(SimulationError::InvalidTimeConstant(config.time_constant));
+    }
+    if trajectory.num_states() == 0 {
+        return Ok(SimulationResult { states: Vec::new(), max_error: 0., rms_error: 0. });
+    }
+
+    let mut drivetrain = Drivetrain { config: *config, speeds: ChassisSpeeds::default() };
+    let mut pose = start;
+
+    let num_steps = (trajectory.total_time() / config.dt).ceil() as usize;
+    let mut states = Vec::<SimulatedState>::with_capacity(num_steps + 1);
+    let mut squared_error_sum = 0.;
+    let mut max_error = 0.0_f64;
+
+    for step in 0..=num_steps {
+        let t = (step as f64 * config.dt).min(*trajectory.total_time());
+        let desired = trajectory.sample(t);
+
+        let error = pose.translation().distance_to(desired.pose.translation());
+        max_error = max_error.max(error);
+        squared_error_sum += error * error;
+        states.push(SimulatedState { t, pose, error });
+
+        let commanded = match config.controller {
+            Controller::Holonomic { translation_kp, rotation_kp } =>
+                HolonomicDriveController::new(translation_kp, rotation_kp).calculate(&pose, &desired, headings.at(t)),
+            Controller::Ramsete { b, zeta } =>
+                RamseteController::new(b, zeta).calculate(&pose, &desired)
+        };
+        pose = pose.exp(&drivetrain.step(commanded));
+    }
+
+    let rms_error = (squared_error_sum / states.len() as f64).sqrt();
+    Ok(SimulationResult { states, max_error, rms_error })
+}
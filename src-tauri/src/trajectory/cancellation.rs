@@ -0,0 +1,38 @@
+use std::fmt;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/**
+ * A flag shared between whoever requested a trajectory and the thread
+ * generating it. Once cancelled, generation stops at the next checkpoint and
+ * returns a CancelledError.
+ */
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>
+}
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+    pub fn check(&self) -> Result<(), CancelledError> {
+        if self.is_cancelled() {
+            return Err(CancelledError);
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CancelledError;
+impl fmt::Display for CancelledError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Trajectory generation was cancelled by a newer request.")
+    }
+}
@@ -0,0 +1,141 @@
+use crate::geometry::Pose2d;
+use crate::kinematics::{ChassisSpeeds, DifferentialDriveKinematics};
+use crate::robot::SimpleMotorFeedforward;
+
+use super::{MinMaxAcceleration, TrajectoryConstraint};
+
+const NO_ACCELERATION_LIMIT: MinMaxAcceleration = MinMaxAcceleration {
+    min_acceleration: f64::NEG_INFINITY,
+    max_acceleration: f64::INFINITY
+};
+
+/**
+ * A class that enforces constraints on the differential drive kinematics.
+ * This can be used to ensure that the trajectory is constructed so that the
+ * commanded velocities for both sides of the drivetrain stay below a certain
+ * limit.
+ */
+pub struct DifferentialDriveKinematicsConstraint {
+    kinematics: DifferentialDriveKinematics,
+    max_speed: f64
+}
+impl DifferentialDriveKinematicsConstraint {
+    /**
+     * @param kinematics A kinematics component describing the drive geometry.
+     * @param max_speed  The max speed that a side of the robot can travel at
+     * in m/s.
+     */
+    pub const fn new(kinematics: DifferentialDriveKinematics, max_speed: f64) -> Self {
+        Self { kinematics, max_speed }
+    }
+}
+impl TrajectoryConstraint for DifferentialDriveKinematicsConstraint {
+    fn max_velocity(&self, _pose: &Pose2d, curvature: f64, velocity: f64) -> f64 {
+        // Create an object to represent the current chassis speeds.
+        let chassis_speeds = ChassisSpeeds::new(velocity, 0., velocity * curvature);
+
+        // Get the wheel speeds and normalize them to within the max velocity.
+        let wheel_speeds = self.kinematics.to_wheel_speeds(&chassis_speeds).desaturate(self.max_speed);
+
+        // Return the new linear chassis speed.
+        self.kinematics.to_chassis_speeds(&wheel_speeds).vx
+    }
+
+    fn min_max_acceleration(&self, _pose: &Pose2d, _curvature: f64, _speed: f64) -> MinMaxAcceleration {
+        NO_ACCELERATION_LIMIT
+    }
+
+    fn name(&self) -> &str {
+        "Differential drive kinematics"
+    }
+}
+
+/**
+ * A class that enforces constraints on differential drive voltage
+ * expenditure based on the motor dynamics and the drive kinematics. Ensures
+ * that the acceleration of any wheel of the robot while following the
+ * trajectory is never higher than what can be achieved with the given
+ * maximum voltage.
+ */
+pub struct DifferentialDriveVoltageConstraint {
+    feedforward: SimpleMotorFeedforward,
+    kinematics: DifferentialDriveKinematics,
+    max_voltage: f64
+}
+impl DifferentialDriveVoltageConstraint {
+    /**
+     * @param feedforward A feedforward component describing the behavior of
+     * the drive.
+     * @param kinematics  A kinematics component describing the drive geometry.
+     * @param max_voltage The maximum voltage available to the motors while
+     * following the path. Should be somewhat less than the nominal battery
+     * voltage (12V) to account for "voltage sag" due to current draw.
+     */
+    pub const fn new(feedforward: SimpleMotorFeedforward, kinematics: DifferentialDriveKinematics, max_voltage: f64) -> Self {
+        Self { feedforward, kinematics, max_voltage }
+    }
+}
+impl TrajectoryConstraint for DifferentialDriveVoltageConstraint {
+    fn max_velocity(&self, _pose: &Pose2d, _curvature: f64, _velocity: f64) -> f64 {
+        f64::INFINITY
+    }
+
+    fn min_max_acceleration(&self, _pose: &Pose2d, curvature: f64, speed: f64) -> MinMaxAcceleration {
+        let wheel_speeds = self.kinematics.to_wheel_speeds(
+            &ChassisSpeeds::new(speed, 0., speed * curvature));
+
+        let max_wheel_speed = wheel_speeds.left.max(wheel_speeds.right);
+        let min_wheel_speed = wheel_speeds.left.min(wheel_speeds.right);
+
+        // Calculate maximum/minimum possible accelerations from motor dynamics
+        // and max/min wheel speeds
+        let max_wheel_acceleration = self.feedforward.max_achievable_acceleration(self.max_voltage, max_wheel_speed);
+        let min_wheel_acceleration = self.feedforward.min_achievable_acceleration(self.max_voltage, min_wheel_speed);
+
+        // Robot chassis turning on radius = 1/|curvature|. Outer wheel has
+        // radius increased by half of the trackwidth T. Inner wheel has radius
+        // decreased by half of the trackwidth. Achassis = Aouter * radius /
+        // (radius + T/2) = Aouter / (1 + |curvature|T/2). Inner wheel is
+        // similar.
+
+        // sgn(speed) term added to correctly account for which wheel is on
+        // outside of turn:
+        // If moving forward, max acceleration constraint corresponds to wheel
+        // on outside of turn. If moving backward, max acceleration constraint
+        // corresponds to wheel on inside of turn.
+
+        // When velocity is zero, then wheel velocities are uniformly zero
+        // (robot cannot be turning on its center) - we have to treat this as a
+        // special case, as it breaks the signum function. Both max and min
+        // acceleration are *reduced in magnitude* in this case.
+        let half_track_curvature = self.kinematics.track_width * curvature.abs() / 2.;
+        let mut max_chassis_acceleration: f64;
+        let mut min_chassis_acceleration: f64;
+        if speed == 0. {
+            max_chassis_acceleration = max_wheel_acceleration / (1. + half_track_curvature);
+            min_chassis_acceleration = min_wheel_acceleration / (1. + half_track_curvature);
+        } else {
+            max_chassis_acceleration = max_wheel_acceleration / (1. + half_track_curvature * speed.signum());
+            min_chassis_acceleration = min_wheel_acceleration / (1. - half_track_curvature * speed.signum());
+        }
+
+        // When turning about a point inside of the wheelbase (i.e. radius less
+        // than half the trackwidth), the inner wheel's direction changes, but
+        // the magnitude remains the same. The formula above changes sign for
+        // the inner wheel when this happens. We can accurately account for
+        // this by simply negating the inner wheel.
+        if self.kinematics.track_width / 2. > 1. / curvature.abs() {
+            if speed > 0. {
+                min_chassis_acceleration = -min_chassis_acceleration;
+            } else if speed < 0. {
+                max_chassis_acceleration = -max_chassis_acceleration;
+            }
+        }
+
+        MinMaxAcceleration { min_acceleration: min_chassis_acceleration, max_acceleration: max_chassis_acceleration }
+    }
+
+    fn name(&self) -> &str {
+        "Differential drive voltage"
+    }
+}
@@ -1,4 +1,3 @@
-use ndarray::{Array2, arr2, Array1, arr1, s};
 use serde::{Serialize, Deserialize};
 
 use crate::geometry::{Translation2d, Pose2d, Rotation2d};
@@ -13,9 +12,13 @@ pub struct MinMaxAcceleration {
 pub trait TrajectoryConstraint {
     fn max_velocity(&self, pose: &Pose2d, curvature: f64, velocity: f64) -> f64;
     fn min_max_acceleration(&self, pose: &Pose2d, curvature: f64, speed: f64) -> MinMaxAcceleration;
+    /** What the constraint is called when reporting what limits a trajectory */
+    fn name(&self) -> &str {
+        "Custom constraint"
+    }
 }
 
-#[derive(Deserialize)]
+#[derive(Clone, Copy, Debug, Deserialize)]
 pub struct TrajectoryConfigNoConstraints {
     pub max_velocity: f64,
     pub max_acceleration: f64,
@@ -69,29 +72,46 @@ pub type QuinticControlVector = ControlVector<3>;
 impl QuinticControlVector {
     pub fn from_point_and_scalar(scalar: f64, point: &Pose2d) -> Self {
         Self {
-            x: [point.translation().x().clone(), scalar * point.rotation().cos(), 0.0],
-            y: [point.translation().y().clone(), scalar * point.rotation().sin(), 0.0]
+            x: [*point.translation().x(), scalar * point.rotation().cos(), 0.0],
+            y: [*point.translation().y(), scalar * point.rotation().sin(), 0.0]
         }
     }
 }
 
-pub fn control_vector_from_arrays(init_vector: [f64; 3], final_vector: [f64; 3]) -> Array1<f64> {
-    arr1(&[init_vector[0], init_vector[1], init_vector[2], final_vector[0], final_vector[1], final_vector[2]])
+pub fn control_vector_from_arrays(init_vector: [f64; 3], final_vector: [f64; 3]) -> [f64; 6] {
+    [init_vector[0], init_vector[1], init_vector[2], final_vector[0], final_vector[1], final_vector[2]]
 }
 
-fn make_hermite_basis() -> Array2<f64> {
-    arr2(
-        &[[-06.0, -03.0, -00.5,  06.0, -03.0,  00.5],
-         [15.0,  08.0,  01.5, -15.0,  07.0, -01.0],
-        [-10.0, -06.0, -01.5,  10.0, -04.0,  00.5],
-         [00.0,  00.0,  00.5,  00.0,  00.0,  00.0],
-         [00.0,  01.0,  00.0,  00.0,  00.0,  00.0],
-         [01.0,  00.0,  00.0,  00.0,  00.0,  00.0]]
-    )
+const HERMITE_BASIS: [[f64; 6]; 6] = 
+    [[-06.0, -03.0, -00.5,  06.0, -03.0,  00.5],
+     [ 15.0,  08.0,  01.5, -15.0,  07.0, -01.0],
+     [-10.0, -06.0, -01.5,  10.0, -04.0,  00.5],
+     [ 00.0,  00.0,  00.5,  00.0,  00.0,  00.0],
+     [ 00.0,  01.0,  00.0,  00.0,  00.0,  00.0],
+     [ 01.0,  00.0,  00.0,  00.0,  00.0,  00.0]];
+
+/**
+ * The highest degree of spline supported. Coefficients are stored in arrays of
+ * this size so that evaluating a spline never has to allocate.
+ */
+pub const MAX_DEGREE: usize = 5;
+
+/**
+ * Evaluates the polynomial whose coefficients are given from highest to lowest
+ * power using Horner's method.
+ */
+fn horner(coefficients: &[f64], t: f64) -> f64 {
+    coefficients.iter().fold(0.0, |acc, coefficient| acc * t + coefficient)
 }
 
 pub struct Spline<const DEGREE: usize> {
-    coefficients: Array2<f64>
+    /**
+     * Rows 0 and 1 hold the coefficients of x(t) and y(t), rows 2 and 3 their
+     * first derivatives and rows 4 and 5 their second derivatives. Column i
+     * holds the coefficient of t^(DEGREE - i) in rows 0 and 1,
+     * t^(DEGREE - i - 1) in rows 2 and 3 and t^(DEGREE - i - 2) in rows 4 and 5.
+     */
+    coefficients: [[f64; MAX_DEGREE + 1]; 6]
 }
 impl<const DEGREE: usize> Spline<DEGREE> {
     /**
@@ -101,47 +121,22 @@ impl<const DEGREE: usize> Spline<DEGREE> {
      * @return The pose and curvature at that point.
      */
     pub fn get_point(&self, t: f64) -> PoseWithCurvature {
-        let mut polynomial_bases = Array1::<f64>::zeros(DEGREE+1);
+        let x = horner(&self.coefficients[0][..=DEGREE], t);
+        let y = horner(&self.coefficients[1][..=DEGREE], t);
 
-        // Populate the polynomial bases
-        let mut i = 0;
-        while i <= DEGREE {
-            polynomial_bases[i] = t.powf((DEGREE - i) as f64);
-            i += 1;
-        }
-
-        // This simply multiplies by the coefficients. We need to divide out t some
-        // n number of times where n is the derivative we want to take.
-        let combined = self.coefficients.dot(&polynomial_bases);
-
-        let dx: f64;
-        let dy: f64; 
-        let ddx: f64; 
-        let ddy: f64;
-
-        // If t = 0, all other terms in the equation cancel out to zero. We can use
-        // the last x^0 term in the equation.
-        if t == 0.0 {
-            dx = self.coefficients[[2, DEGREE - 1]];
-            dy = self.coefficients[[3, DEGREE - 1]];
-            ddx = self.coefficients[[4, DEGREE - 2]];
-            ddy = self.coefficients[[5, DEGREE - 2]];
-        } else {
-            // Divide out t for first derivative.
-            dx = combined[2] / t;
-            dy = combined[3] / t;
-
-            // Divide out t for second derivative.
-            ddx = combined[4] / t / t;
-            ddy = combined[5] / t / t;
-        }
+        // The derivatives have one and two fewer terms respectively, so there
+        // is no need to divide out t like the C++ implementation does.
+        let dx = horner(&self.coefficients[2][..DEGREE], t);
+        let dy = horner(&self.coefficients[3][..DEGREE], t);
+        let ddx = horner(&self.coefficients[4][..DEGREE - 1], t);
+        let ddy = horner(&self.coefficients[5][..DEGREE - 1], t);
 
         // Find the curvature.
         let curvature =
             (dx * ddy - ddx * dy) / ((dx * dx + dy * dy) * dx.hypot(dy));
         
         let point = (
-            Pose2d::new(Translation2d::from_vector(&combined.slice(s![0..2 as i32])), Rotation2d::from_vector(dx, dy)),
+            Pose2d::new(Translation2d::new(x, y), Rotation2d::from_vector(dx, dy)),
             curvature
         );
 
@@ -152,40 +147,35 @@ impl<const DEGREE: usize> Spline<DEGREE> {
 pub type QuinticHermiteSpline = Spline<5>; // 6x6 coefficients
 impl QuinticHermiteSpline {
     pub fn new(x_init_control_vector: [f64; 3], x_final_control_vector: [f64; 3], y_init_control_vector: [f64; 3], y_final_control_vector: [f64; 3]) -> Self {
-        let hermite = make_hermite_basis();
         let x = control_vector_from_arrays(x_init_control_vector, x_final_control_vector);
         let y = control_vector_from_arrays(y_init_control_vector, y_final_control_vector);
-        let mut coefficients = Array2::<f64>::zeros((6,6));
+        let mut coefficients = [[0.0; 6]; 6];
         
-        // TODO check this
-        coefficients.row_mut(0).assign(&hermite.dot(&x));
-        coefficients.row_mut(1).assign(&hermite.dot(&y));
+        for i in 0..6 {
+            for j in 0..6 {
+                coefficients[0][i] += HERMITE_BASIS[i][j] * x[j];
+                coefficients[1][i] += HERMITE_BASIS[i][j] * y[j];
+            }
+        }
 
         // Populate Row 2 and Row 3 with the derivatives of the equations above.
         // Then populate row 4 and 5 with the second derivatives.
-        let mut i = 0;
-        while i < 6 {
-            // Here, we are multiplying by (5 - i) to manually take the derivative. The
-            // power of the term in index 0 is 5, index 1 is 4 and so on. To find the
-            // coefficient of the derivative, we can use the power rule and multiply
-            // the existing coefficient by its power.
-            let k = 5. - i as f64;
-            coefficients[[2,i]] = coefficients[[0,i]] * k;
-            coefficients[[3,i]] = coefficients[[1,i]] * k;
-            i += 1;
-        }
-
-        i = 0;
-        while i < 6 {
-            // Here, we are multiplying by (4 - i) to manually take the derivative. The
-            // power of the term in index 0 is 4, index 1 is 3 and so on. To find the
-            // coefficient of the derivative, we can use the power rule and multiply
-            // the existing coefficient by its power.
-            let k = 4. - i as f64;
-            coefficients[[4,i]] = coefficients[[2,i]] * k;
-            coefficients[[5,i]] = coefficients[[3,i]] * k;
-            i += 1;
-        }
+        // Here, we are multiplying by (power - i) to manually take the
+        // derivative. The power of the term in index 0 is 5 for the equations
+        // and 4 for their derivatives, index 1 is one less and so on. To find
+        // the coefficient of the derivative, we can use the power rule and
+        // multiply the existing coefficient by its power.
+        let derive = |row: &[f64; 6], power: f64| {
+            let mut derivative = [0.0; 6];
+            for (i, (term, coefficient)) in derivative.iter_mut().zip(row).enumerate() {
+                *term = coefficient * (power - i as f64);
+            }
+            derivative
+        };
+        coefficients[2] = derive(&coefficients[0], 5.);
+        coefficients[3] = derive(&coefficients[1], 5.);
+        coefficients[4] = derive(&coefficients[2], 4.);
+        coefficients[5] = derive(&coefficients[3], 4.);
 
         Self {
             coefficients
@@ -193,7 +183,7 @@ impl QuinticHermiteSpline {
     }
 }
 
-#[derive(Clone, Copy, Serialize, Debug)]
+#[derive(Clone, Copy, Serialize, Deserialize, Debug)]
 pub struct TrajectoryState {
     pub t: f64,
     pub velocity: f64,
@@ -201,13 +191,59 @@ pub struct TrajectoryState {
     pub pose: Pose2d,
     pub curvature: f64
 }
-// impl TrajectoryState { TODO
-//     pub fn interpolate(self, end: &Self, i: f64) -> Self {
-//         todo!()
-//     }
-// }
+impl TrajectoryState {
+    /**
+     * Interpolates between this state and another, assuming constant
+     * acceleration between the two.
+     *
+     * @param end The state to interpolate towards.
+     * @param i   How far between the two states to interpolate, from 0 to 1.
+     */
+    pub fn interpolate(self, end: &Self, i: f64) -> Self {
+        // Find the new t value.
+        let new_t = lerp(self.t, end.t, i);
+
+        // Find the delta time between the current state and the interpolated state.
+        let delta_t = new_t - self.t;
+
+        // If delta time is negative, flip the order of interpolation.
+        if delta_t < 0. {
+            return end.interpolate(&self, 1. - i);
+        }
+
+        // Check whether the robot is reversing at this stage.
+        let reversing = self.velocity < 0. || (self.velocity.abs() < 1E-9 && self.acceleration < 0.);
 
-#[derive(Serialize, Debug)]
+        // Calculate the new velocity
+        // v_f = v_0 + at
+        let new_v = self.velocity + (self.acceleration * delta_t);
+
+        // Calculate the change in position.
+        // delta_s = v_0 t + 0.5at^2
+        let new_s = (self.velocity * delta_t + 0.5 * self.acceleration * delta_t * delta_t)
+            * if reversing { -1. } else { 1. };
+
+        // Return the new state. To find the new position for the new state, we need
+        // to interpolate between the two endpoint poses. The fraction for
+        // interpolation is the change in position (delta s) divided by the total
+        // distance between the two endpoints.
+        let interpolation_frac = new_s / end.pose.translation().distance_to(self.pose.translation());
+
+        Self {
+            t: new_t,
+            velocity: new_v,
+            acceleration: self.acceleration,
+            pose: self.pose.lerp(&end.pose, interpolation_frac),
+            curvature: lerp(self.curvature, end.curvature, interpolation_frac)
+        }
+    }
+}
+
+fn lerp(start: f64, end: f64, t: f64) -> f64 {
+    start + (end - start) * t
+}
+
+#[derive(Serialize, Deserialize, Debug)]
 pub struct Trajectory {
     states: Vec<TrajectoryState>,
     total_time: f64
@@ -237,8 +273,42 @@ impl Trajectory {
     pub fn init_pose(&self) -> &Pose2d {
         &self.states[0].pose
     }
+
+    /**
+     * Samples the trajectory at a point in time, interpolating between the
+     * two closest states.
+     *
+     * @param t The point in time since the beginning of the trajectory to
+     * sample. Times outside of the trajectory are clamped to it.
+     * @return The state at that point in time.
+     */
+    pub fn sample(&self, t: f64) -> TrajectoryState {
+        if t <= self.states[0].t {
+            return self.states[0];
+        }
+        if t >= self.total_time {
+            return *self.states.last().unwrap();
+        }
+
+        // Binary search for the first state after t. The state before t is
+        // guaranteed to exist since t is after the first state.
+        let low = self.states.partition_point(|state| state.t < t).max(1);
+        let sample = self.states[low];
+        let prev_sample = self.states[low - 1];
+
+        // If the difference in states is negligible, then we are spot on!
+        if (sample.t - prev_sample.t).abs() < 1E-9 {
+            return sample;
+        }
+        // Interpolate between the two states for the state that we want.
+        prev_sample.interpolate(&sample, (t - prev_sample.t) / (sample.t - prev_sample.t))
+    }
 }
 
+pub mod cancellation;
+pub mod constraints;
+pub mod path_model;
+pub mod report;
 pub mod spline_helper;
 pub mod spline_parameterizer;
 pub mod trajectory_generator;
@@ -0,0 +1,153 @@
+use crate::geometry::{Pose2d, Translation2d, Rotation2d, Transform2d};
+
+use super::{Trajectory, TrajectoryConfig, trajectory_parameterizer};
+use super::cancellation::CancellationToken;
+use super::spline_helper::quintic_spline_between;
+use super::spline_parameterizer::{PoseWithCurvature, self, MalformedSplineError};
+use super::trajectory_generator::GenerationError;
+
+/**
+ * A path whose spline points are cached per segment, where segment i is the
+ * quintic spline between waypoints i and i + 1. Moving a waypoint only
+ * re-splines and re-parameterizes the (at most two) segments touching it,
+ * which keeps regeneration cheap while a waypoint is dragged around on long
+ * paths. Time parameterization still runs over the whole path since the
+ * velocity profile depends on every point.
+ */
+pub struct PathModel {
+    waypoints: Vec<Pose2d>,
+    reversed: bool,
+    segments: Vec<Vec<PoseWithCurvature>>
+}
+impl PathModel {
+    /**
+     * Splines and parameterizes every segment of the path.
+     *
+     * @param waypoints List of waypoints.
+     * @param reversed  Whether the robot drives the path backwards.
+     */
+    pub fn new(waypoints: Vec<Pose2d>, reversed: bool) -> Result<Self, MalformedSplineError> {
+        let mut model = Self { waypoints, reversed, segments: Vec::new() };
+        model.segments = (0..model.num_segments())
+            .map(|i| model.parameterize_segment(i))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(model)
+    }
+
+    pub const fn waypoints(&self) -> &Vec<Pose2d> {
+        &self.waypoints
+    }
+
+    pub const fn reversed(&self) -> bool {
+        self.reversed
+    }
+
+    pub fn num_segments(&self) -> usize {
+        self.waypoints.len().saturating_sub(1)
+    }
+
+    /**
+     * Moves a single waypoint, re-parameterizing only the segments on either
+     * side of it. If either segment is malformed the model is left unchanged.
+     *
+     * @param index Index of the waypoint to move.
+     * @param pose  The new pose of the waypoint.
+     */
+    pub fn move_waypoint(&mut self, index: usize, pose: Pose2d) -> Result<(), MalformedSplineError> {
+        let previous = self.waypoints[index];
+        self.waypoints[index] = pose;
+
+        let touched = index.saturating_sub(1)..(index + 1).min(self.num_segments());
+        let mut new_segments = Vec::with_capacity(2);
+        for i in touched.clone() {
+            match self.parameterize_segment(i) {
+                Ok(points) => new_segments.push(points),
+                Err(err) => {
+                    self.waypoints[index] = previous;
+                    return Err(err);
+                }
+            }
+        }
+        for (i, points) in touched.zip(new_segments) {
+            self.segments[i] = points;
+        }
+        Ok(())
+    }
+
+    /**
+     * Brings the model in line with the given waypoints. If only some
+     * waypoints moved they are updated incrementally, otherwise every segment
+     * is rebuilt.
+     *
+     * @param waypoints List of waypoints.
+     * @param reversed  Whether the robot drives the path backwards.
+     */
+    pub fn update(&mut self, waypoints: &[Pose2d], reversed: bool) -> Result<(), MalformedSplineError> {
+        if waypoints.len() != self.waypoints.len() || reversed != self.reversed {
+            *self = Self::new(waypoints.to_vec(), reversed)?;
+            return Ok(());
+        }
+        for (i, waypoint) in waypoints.iter().enumerate() {
+            if *waypoint != self.waypoints[i] {
+                self.move_waypoint(i, *waypoint)?;
+            }
+        }
+        Ok(())
+    }
+
+    /**
+     * The spline points of the whole path, in the same form as
+     * trajectory_generator::spline_points_from_splines returns them.
+     */
+    pub fn points(&self) -> Vec<PoseWithCurvature> {
+        let mut points = Vec::<PoseWithCurvature>::with_capacity(
+            self.segments.iter().map(|segment| segment.len()).sum());
+        for (i, segment) in self.segments.iter().enumerate() {
+            // The first point of every segment is a duplicate of the last point
+            // of the previous segment.
+            let skip = if i == 0 { 0 } else { 1 };
+            points.extend_from_slice(&segment[skip..]);
+        }
+        points
+    }
+
+    /**
+     * Time parameterizes the cached spline points.
+     *
+     * @param config The configuration for the trajectory. Its reversed flag
+     * must match the model's.
+     * @param cancel Token checked before time parameterization.
+     */
+    pub fn trajectory(&self, config: TrajectoryConfig, cancel: &CancellationToken) -> Result<Trajectory, GenerationError> {
+        debug_assert_eq!(config.reversed, self.reversed);
+        cancel.check()?;
+        if self.segments.is_empty() {
+            return Ok(Trajectory::do_nothing());
+        }
+        let trajectory = trajectory_parameterizer::time_parameterize_trajectory(
+            &self.points(), config.constraints, config.start_velocity,
+            config.end_velocity, config.max_velocity, config.max_acceleration,
+            config.reversed)?;
+        Ok(trajectory)
+    }
+
+    fn parameterize_segment(&self, index: usize) -> Result<Vec<PoseWithCurvature>, MalformedSplineError> {
+        let flip = Transform2d::new(Translation2d::default(), Rotation2d::from_degrees(180.));
+        let mut p0 = self.waypoints[index];
+        let mut p1 = self.waypoints[index + 1];
+        if self.reversed {
+            p0 = p0.transform_by(&flip);
+            p1 = p1.transform_by(&flip);
+        }
+
+        let mut points = spline_parameterizer::parameterize(&quintic_spline_between(&p0, &p1), None, None)?;
+
+        // Flip theta back so it's relative to the field. Also fix curvature.
+        if self.reversed {
+            for point in points.iter_mut() {
+                *point = (point.0.transform_by(&flip), -point.1);
+            }
+        }
+        Ok(points)
+    }
+}
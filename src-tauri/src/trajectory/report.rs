@@ -0,0 +1,177 @@
+use serde::Serialize;
+
+use crate::geometry::Pose2d;
+
+use super::{Trajectory, TrajectoryConfig, TrajectoryState};
+
+/**
+ * How close in m/s or m/s² a state has to be to a limit to count as limited
+ * by it. The parameterizer only hits its limits up to floating point error,
+ * and the acceleration between states is averaged over a short distance.
+ */
+const TOLERANCE: f64 = 1E-3;
+/**
+ * How far in m/s or m/s² a state may go over a limit and still be feasible.
+ * Looser than TOLERANCE since constraints are only enforced at the states and
+ * not in between them.
+ */
+const FEASIBILITY_TOLERANCE: f64 = 0.05;
+
+/**
+ * What keeps the robot from going faster at a state of a trajectory.
+ */
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum Limit {
+    /** The state is the start of the trajectory, which has a set velocity */
+    StartVelocity,
+    /** The state is the end of the trajectory, which has a set velocity */
+    EndVelocity,
+    /** The configured max velocity */
+    MaxVelocity,
+    /** The configured max acceleration, while speeding up into the state */
+    MaxAcceleration,
+    /** The configured max acceleration, while slowing down after the state */
+    MaxDeceleration,
+    /** One of the constraints, by name */
+    Constraint { name: String }
+}
+
+/**
+ * The sharpest turn along a trajectory.
+ */
+#[derive(Clone, Copy, Debug, Serialize)]
+pub struct Turn {
+    /** Time since the start of the trajectory in seconds */
+    pub t: f64,
+    pub pose: Pose2d,
+    /** Curvature in rad/m, positive when turning left */
+    pub curvature: f64,
+    /** Radius of the turn in meters */
+    pub radius: f64
+}
+
+/**
+ * Statistics about a generated trajectory, along with what limits it at
+ * every state, so it can be checked without eyeballing the path.
+ */
+#[derive(Clone, Debug, Serialize)]
+pub struct TrajectoryReport {
+    /** Distance along the path in meters */
+    pub total_distance: f64,
+    /** Duration in seconds */
+    pub total_time: f64,
+    /** Fastest speed in m/s */
+    pub max_velocity: f64,
+    /** Largest magnitude of acceleration in m/s² */
+    pub max_acceleration: f64,
+    /** Largest magnitude of curvature in rad/m */
+    pub max_curvature: f64,
+    /** How long the robot drives at the configured max velocity, in seconds */
+    pub time_at_max_velocity: f64,
+    /** The sharpest turn, or None if the path is straight */
+    pub tightest_turn: Option<Turn>,
+    /** What keeps the robot from going faster at every state, if anything does */
+    pub limits: Vec<Option<Limit>>,
+    /**
+     * Whether every state stays within the configured velocity and
+     * acceleration and the constraints. A trajectory straight from the
+     * generator always does, unless the constraints contradict each other.
+     */
+    pub feasible: bool
+}
+impl TrajectoryReport {
+    /**
+     * @param trajectory The trajectory to report on.
+     * @param config     The config the trajectory was generated with.
+     */
+    pub fn new(trajectory: &Trajectory, config: &TrajectoryConfig) -> Self {
+        let states = trajectory.states();
+        let mut total_distance = 0.;
+        let mut time_at_max_velocity = 0.;
+        for pair in states.windows(2) {
+            total_distance += pair[0].pose.translation().distance_to(pair[1].pose.translation());
+            if pair.iter().all(|state| state.velocity.abs() >= config.max_velocity - TOLERANCE) {
+                time_at_max_velocity += pair[1].t - pair[0].t;
+            }
+        }
+        let tightest = states.iter()
+            .filter(|state| state.curvature.abs() > 1E-9)
+            .max_by(|a, b| a.curvature.abs().total_cmp(&b.curvature.abs()));
+
+        Self {
+            total_distance,
+            total_time: *trajectory.total_time(),
+            max_velocity: states.iter().fold(0., |max, state| state.velocity.abs().max(max)),
+            max_acceleration: states.iter().fold(0., |max, state| state.acceleration.abs().max(max)),
+            max_curvature: states.iter().fold(0., |max, state| state.curvature.abs().max(max)),
+            time_at_max_velocity,
+            tightest_turn: tightest.map(|state| Turn {
+                t: state.t,
+                pose: state.pose,
+                curvature: state.curvature,
+                radius: 1. / state.curvature.abs()
+            }),
+            limits: (0..states.len()).map(|i| limit_at(states, i, config)).collect(),
+            feasible: states.iter().all(|state| is_feasible(state, config))
+        }
+    }
+}
+
+/**
+ * Works out what limits the velocity of the state at the index. The velocity
+ * caps are checked first, then the acceleration into the state and the
+ * deceleration out of it.
+ */
+fn limit_at(states: &[TrajectoryState], i: usize, config: &TrajectoryConfig) -> Option<Limit> {
+    let state = &states[i];
+    if i == 0 {
+        return Some(Limit::StartVelocity);
+    }
+    if i == states.len() - 1 {
+        return Some(Limit::EndVelocity);
+    }
+    if state.velocity.abs() >= config.max_velocity - TOLERANCE {
+        return Some(Limit::MaxVelocity);
+    }
+    // Like the parameterizer, ask each constraint how fast the robot may go
+    // at the configured max velocity. Only a constraint that caps it below
+    // that can be what the state is at.
+    if let Some(constraint) = config.constraints.iter().find(|constraint| {
+        let cap = constraint.max_velocity(&state.pose, state.curvature, config.max_velocity).abs();
+        cap < config.max_velocity - TOLERANCE && state.velocity.abs() >= cap - TOLERANCE
+    }) {
+        return Some(Limit::Constraint { name: constraint.name().to_string() });
+    }
+
+    // The acceleration of a state holds until the next state. When reversed,
+    // speeding up is a negative acceleration.
+    let direction = if config.reversed { -1. } else { 1. };
+    let previous = &states[i - 1];
+    if previous.acceleration * direction >= config.max_acceleration - TOLERANCE {
+        return Some(Limit::MaxAcceleration);
+    }
+    if state.acceleration * direction <= -config.max_acceleration + TOLERANCE {
+        return Some(Limit::MaxDeceleration);
+    }
+    config.constraints.iter().find(|constraint| {
+        let into = constraint.min_max_acceleration(&previous.pose, previous.curvature, previous.velocity);
+        let out_of = constraint.min_max_acceleration(&state.pose, state.curvature, state.velocity);
+        let speeding_up = if config.reversed { previous.acceleration <= into.min_acceleration + TOLERANCE }
+            else { previous.acceleration >= into.max_acceleration - TOLERANCE };
+        let slowing_down = if config.reversed { state.acceleration >= out_of.max_acceleration - TOLERANCE }
+            else { state.acceleration <= out_of.min_acceleration + TOLERANCE };
+        speeding_up || slowing_down
+    }).map(|constraint| Limit::Constraint { name: constraint.name().to_string() })
+}
+
+fn is_feasible(state: &TrajectoryState, config: &TrajectoryConfig) -> bool {
+    state.velocity.abs() <= config.max_velocity + FEASIBILITY_TOLERANCE
+        && state.acceleration.abs() <= config.max_acceleration + FEASIBILITY_TOLERANCE
+        && config.constraints.iter().all(|constraint| {
+            let limits = constraint.min_max_acceleration(&state.pose, state.curvature, state.velocity);
+            state.velocity.abs() <= constraint.max_velocity(&state.pose, state.curvature, state.velocity).abs() + FEASIBILITY_TOLERANCE
+                && state.acceleration >= limits.min_acceleration - FEASIBILITY_TOLERANCE
+                && state.acceleration <= limits.max_acceleration + FEASIBILITY_TOLERANCE
+        })
+}
@@ -3,6 +3,8 @@ use std::fmt;
 use crate::geometry::Pose2d;
 
 use super::Spline;
+use super::cancellation::CancellationToken;
+use super::trajectory_generator::GenerationError;
 
 pub type PoseWithCurvature = (Pose2d, f64);
 
@@ -38,6 +40,21 @@ impl fmt::Display for MalformedSplineError {
  * the spline.
  */
 pub fn parameterize<const DIM: usize>(spline: &Spline<DIM>, t_0: Option<f64>, t_1: Option<f64>) -> Result<Vec<PoseWithCurvature>, MalformedSplineError> {
+    // A token that is never cancelled can only fail with a malformed spline.
+    try_parameterize(spline, t_0, t_1, &CancellationToken::new()).map_err(|_| MalformedSplineError)
+}
+
+/**
+ * Parameterizes the spline like parameterize, checking the cancellation token
+ * every time an arc is split so that a spline that takes thousands of
+ * subdivisions can still be stopped.
+ *
+ * @param spline The spline to parameterize.
+ * @param t0     Starting internal spline parameter.
+ * @param t1     Ending internal spline parameter.
+ * @param cancel Token checked on every iteration.
+ */
+pub fn try_parameterize<const DIM: usize>(spline: &Spline<DIM>, t_0: Option<f64>, t_1: Option<f64>, cancel: &CancellationToken) -> Result<Vec<PoseWithCurvature>, GenerationError> {
     let t0: f64 = t_0.unwrap_or(0.0);
     let t1: f64 = t_1.unwrap_or(1.0);
 
@@ -46,6 +63,12 @@ pub fn parameterize<const DIM: usize>(spline: &Spline<DIM>, t_0: Option<f64>, t_
     // The parameterization does not add the initial point. Let's add that.
     spline_points.push(spline.get_point(t0));
 
+    // A spline that stays in one place, like one between two coincident
+    // waypoints, has no direction to follow and so no defined curvature.
+    if !spline_points[0].1.is_finite() {
+        return Err(MalformedSplineError.into());
+    }
+
     // We use an "explicit stack" to simulate recursion, instead of a recursive
     // function call This give us greater control, instead of a stack overflow
     // std::stack<StackContents> stack;
@@ -59,6 +82,7 @@ pub fn parameterize<const DIM: usize>(spline: &Spline<DIM>, t_0: Option<f64>, t_
     let mut iterations = 0;
 
     while !stack.is_empty() {
+      cancel.check()?;
       current = stack.pop().unwrap();
       start = spline.get_point(current.t0);
       end = spline.get_point(current.t1);
@@ -71,12 +95,15 @@ pub fn parameterize<const DIM: usize>(spline: &Spline<DIM>, t_0: Option<f64>, t_
         stack.push(StackContents{t0: (current.t0 + current.t1) / 2., t1: current.t1});
         stack.push(StackContents{t0: current.t0, t1: (current.t0 + current.t1) / 2.});
       } else {
-        spline_points.push(spline.get_point(current.t1));
+        if !end.1.is_finite() {
+          return Err(MalformedSplineError.into());
+        }
+        spline_points.push(end);
       }
       
       iterations += 1;
       if iterations >= MAX_ITERATIONS {
-        return Err(MalformedSplineError);
+        return Err(MalformedSplineError.into());
       }
     }
 
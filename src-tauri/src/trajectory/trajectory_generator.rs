@@ -1,11 +1,50 @@
+use std::fmt;
+
 use crate::geometry::{Pose2d, Translation2d, Rotation2d, Transform2d};
 use crate::trajectory::TrajectoryConfig;
 
 use super::{Spline, Trajectory, trajectory_parameterizer};
+use super::cancellation::{CancellationToken, CancelledError};
 use super::spline_helper::quintic_splines_from_waypoints;
 use super::spline_parameterizer::{PoseWithCurvature, self, MalformedSplineError};
+use super::trajectory_parameterizer::ParameterizerError;
 
-pub fn spline_points_from_splines<const DEGREE: usize>(splines: &Vec<Spline<DEGREE>>) -> Result<Vec<PoseWithCurvature>, MalformedSplineError> {
+#[derive(Debug)]
+pub enum GenerationError {
+    MalformedSpline(MalformedSplineError),
+    Parameterizer(ParameterizerError),
+    Cancelled(CancelledError)
+}
+impl fmt::Display for GenerationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GenerationError::MalformedSpline(err) => err.fmt(f),
+            GenerationError::Parameterizer(err) => err.fmt(f),
+            GenerationError::Cancelled(err) => err.fmt(f)
+        }
+    }
+}
+impl From<MalformedSplineError> for GenerationError {
+    fn from(err: MalformedSplineError) -> Self {
+        GenerationError::MalformedSpline(err)
+    }
+}
+impl From<ParameterizerError> for GenerationError {
+    fn from(err: ParameterizerError) -> Self {
+        GenerationError::Parameterizer(err)
+    }
+}
+impl From<CancelledError> for GenerationError {
+    fn from(err: CancelledError) -> Self {
+        GenerationError::Cancelled(err)
+    }
+}
+
+/**
+ * Parameterizes every spline, checking the cancellation token while each
+ * spline is split up so that a stale request stops as soon as possible.
+ */
+pub fn spline_points_from_splines<const DEGREE: usize>(splines: &Vec<Spline<DEGREE>>, cancel: &CancellationToken) -> Result<Vec<PoseWithCurvature>, GenerationError> {
     // Create the vector of spline points.
     let mut spline_points = Vec::<PoseWithCurvature>::new();
 
@@ -15,19 +54,12 @@ pub fn spline_points_from_splines<const DEGREE: usize>(splines: &Vec<Spline<DEGR
     // Iterate through the vector and parameterize each spline, adding the
     // parameterized points to the final vector.
     for spline in splines {
-        let points = spline_parameterizer::parameterize(&spline, None, None);
+        let mut points = spline_parameterizer::try_parameterize(spline, None, None, cancel)?;
 
         // Append the array of poses to the vector. We are removing the first
         // point because it's a duplicate of the last point from the previous
         // spline.
-        if points.is_ok() {
-            let mut points_without_first = points.unwrap().split_off(1);
-            spline_points.append(&mut points_without_first);
-        } else {
-            let err = points.unwrap_err();
-            println!("{}", err);
-            return Err(err);
-        }
+        spline_points.append(&mut points.split_off(1));
     }
 
     Ok(spline_points)
@@ -43,7 +75,31 @@ pub fn spline_points_from_splines<const DEGREE: usize>(splines: &Vec<Spline<DEGR
  * @return The generated trajectory.
  */
 pub fn generate_trajectory(waypoints: Vec<Pose2d>, config: TrajectoryConfig) -> Trajectory {
-    
+    match try_generate_trajectory(waypoints, config, &CancellationToken::new()) {
+        Ok(trajectory) => trajectory,
+        Err(err) => {
+            println!("{}", err);
+            println!("Returning do nothing trajectory.");
+            Trajectory::do_nothing()
+        }
+    }
+}
+
+/**
+ * Generates a trajectory from the given waypoints and config, stopping early
+ * if the given token is cancelled.
+ *
+ * @param waypoints List of waypoints.
+ * @param config    The configuration for the trajectory.
+ * @param cancel    Token checked between the generation steps.
+ * @return The generated trajectory, or why it could not be generated.
+ */
+pub fn try_generate_trajectory(waypoints: Vec<Pose2d>, config: TrajectoryConfig, cancel: &CancellationToken) -> Result<Trajectory, GenerationError> {
+    // There is nothing to spline between fewer than two waypoints.
+    if waypoints.len() < 2 {
+        return Ok(Trajectory::do_nothing());
+    }
+
     let mut new_waypoints = waypoints.to_vec();
     // auto newWaypoints = waypoints;
     let flip = Transform2d::new(Translation2d::default(), Rotation2d::from_degrees(180.));
@@ -52,13 +108,7 @@ pub fn generate_trajectory(waypoints: Vec<Pose2d>, config: TrajectoryConfig) ->
             new_waypoints[i] = new_waypoints[i].transform_by(&flip);
         }
     }
-    let points_result = spline_points_from_splines::<5>(&quintic_splines_from_waypoints(new_waypoints));
-    if points_result.is_err() {
-        println!("{}", points_result.unwrap_err());
-        println!("Returning do nothing trajectory.");
-        return Trajectory::do_nothing();
-    }
-    let mut points = points_result.unwrap();
+    let mut points = spline_points_from_splines::<5>(&quintic_splines_from_waypoints(new_waypoints), cancel)?;
   
     // After trajectory generation, flip theta back so it's relative to the
     // field. Also fix curvature.
@@ -67,15 +117,11 @@ pub fn generate_trajectory(waypoints: Vec<Pose2d>, config: TrajectoryConfig) ->
             points[i] = (points[i].0.transform_by(&flip), -points[i].1)
         }
     }
+    cancel.check()?;
   
-    let result = trajectory_parameterizer::time_parameterize_trajectory(
+    let trajectory = trajectory_parameterizer::time_parameterize_trajectory(
         &points, config.constraints, config.start_velocity,
         config.end_velocity, config.max_velocity, config.max_acceleration,
-        config.reversed);
-    if result.is_err() {
-        println!("{}", result.unwrap_err());
-        println!("Returning do nothing trajectory.");
-        return Trajectory::do_nothing();
-    }
-    result.unwrap()
+        config.reversed)?;
+    Ok(trajectory)
 }
\ No newline at end of file
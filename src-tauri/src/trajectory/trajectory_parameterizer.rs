@@ -166,7 +166,7 @@ pub fn time_parameterize_trajectory(
                 cur_state.max_velocity = cur_state.max_velocity.min(
                     constraint.max_velocity(&cur_state.pose.0,
                                             cur_state.pose.1,
-                                            max_velocity));
+                                            cur_state.max_velocity));
             }
     
             // Now enforce all acceleration limits.
@@ -0,0 +1,151 @@
+use std::f64::consts::PI;
+
+use serde::{Serialize, Deserialize};
+
+use crate::auto::Headings;
+use crate::field::AprilTag;
+use crate::geometry::{Pose2d, Rotation2d, Translation2d};
+use crate::robot::{DrivetrainType, RobotConfig};
+use crate::simulation::controllers::angle_modulus;
+use crate::trajectory::Trajectory;
+
+/**
+ * How far off of head on in degrees a tag can be seen from before it's too
+ * skewed to be detected reliably.
+ */
+const MAX_VIEWING_ANGLE: f64 = 70.;
+/**
+ * The most times the heading that faces a tag is refined. The camera moves
+ * when the robot turns unless it's mounted in the center, so the heading is
+ * solved for iteratively. It settles quickly unless the tag is barely further
+ * from the robot than the camera.
+ */
+const MAX_AIM_ITERATIONS: usize = 20;
+
+/**
+ * A camera mounted on the robot. Positions are in meters relative to the
+ * center of the robot on the carpet, with x towards the front and y to the
+ * left. Angles are in degrees.
+ */
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Camera {
+    pub x: f64,
+    pub y: f64,
+    pub height: f64,
+    /** Which way the camera faces, counterclockwise from the front of the robot */
+    pub yaw: f64,
+    /** How far the camera is tilted up from level */
+    pub pitch: f64,
+    pub horizontal_fov: f64,
+    pub vertical_fov: f64,
+    /** The furthest away in meters a tag can be detected */
+    pub max_distance: f64
+}
+impl Camera {
+    /**
+     * Where the camera is on the field when the robot is at the given pose.
+     */
+    pub fn position(&self, robot_pose: &Pose2d) -> Translation2d {
+        *robot_pose.translation() + Translation2d::new(self.x, self.y).rotate_by(*robot_pose.rotation())
+    }
+
+    /**
+     * Whether the camera can see the tag when the robot is at the given pose.
+     * The tag has to be within range and the field of view, and facing the
+     * camera closely enough to be detected. Field elements blocking the line
+     * of sight aren't accounted for.
+     */
+    pub fn sees(&self, robot_pose: &Pose2d, tag: &AprilTag) -> bool {
+        let camera = self.position(robot_pose);
+        let to_tag = *tag.pose.translation() - camera;
+        let distance = camera.distance_to(tag.pose.translation());
+        if distance > self.max_distance || distance < 1E-9 {
+            return false;
+        }
+
+        let direction = to_tag.y().atan2(*to_tag.x());
+        let yaw = robot_pose.rotation().radians() + self.yaw.to_radians();
+        if angle_modulus(direction - yaw).abs() > self.horizontal_fov.to_radians() / 2. {
+            return false;
+        }
+        let elevation = (tag.height - self.height).atan2(distance);
+        if (elevation - self.pitch.to_radians()).abs() > self.vertical_fov.to_radians() / 2. {
+            return false;
+        }
+        // The tag faces back towards the camera when it's seen head on.
+        let viewing_angle = angle_modulus(direction + PI - tag.pose.rotation().radians());
+        viewing_angle.abs() <= MAX_VIEWING_ANGLE.to_radians()
+    }
+
+    /**
+     * The heading the robot needs at a point for the center of the camera's
+     * view to point at the tag.
+     */
+    pub fn heading_facing(&self, translation: &Translation2d, tag: &AprilTag) -> Rotation2d {
+        let direction_from = |from: Translation2d| {
+            let to_tag = *tag.pose.translation() - from;
+            to_tag.y().atan2(*to_tag.x())
+        };
+        let mut heading = direction_from(*translation) - self.yaw.to_radians();
+        for _ in 0..MAX_AIM_ITERATIONS {
+            let camera = self.position(&Pose2d::new(*translation, Rotation2d::from_radians(heading)));
+            let refined = direction_from(camera) - self.yaw.to_radians();
+            let change = angle_modulus(refined - heading);
+            heading = refined;
+            if change.abs() < 1E-9 {
+                break;
+            }
+        }
+        Rotation2d::from_radians(angle_modulus(heading))
+    }
+}
+
+/**
+ * The tags the camera can see at a state of a trajectory.
+ */
+#[derive(Clone, Debug, Serialize)]
+pub struct TagVisibility {
+    /** Time since the start of the trajectory in seconds */
+    pub t: f64,
+    /** Heading of the robot, which faces the target tag if there is one */
+    pub heading: Rotation2d,
+    /** Ids of the visible tags */
+    pub tags: Vec<u32>
+}
+
+/**
+ * Works out which tags the robot's camera can see at every state of the
+ * trajectory.
+ *
+ * @param trajectory The trajectory driven by the robot.
+ * @param robot      The robot. Nothing is visible if it has no camera.
+ * @param tags       The tags on the field.
+ * @param target     The id of a tag for a swerve drive to keep facing its
+ * camera towards along the whole trajectory, instead of following its
+ * headings. A differential drive always faces the direction of travel.
+ * @param headings   Which way a swerve drive faces along the trajectory when
+ * it isn't facing a tag.
+ * @return What's visible at every state of the trajectory.
+ */
+pub fn tag_visibility(trajectory: &Trajectory, robot: &RobotConfig, tags: &[AprilTag], target: Option<u32>, headings: &Headings) -> Vec<TagVisibility> {
+    let camera = match robot.camera {
+        Some(camera) if trajectory.num_states() > 0 => camera,
+        _ => return Vec::new()
+    };
+    let target = match robot.drivetrain {
+        DrivetrainType::Swerve => target.and_then(|id| tags.iter().find(|tag| tag.id == id)),
+        DrivetrainType::Differential { .. } => None
+    };
+    trajectory.states().iter().map(|state| {
+        let pose = match target {
+            Some(tag) => Pose2d::new(*state.pose.translation(), camera.heading_facing(state.pose.translation(), tag)),
+            None => robot.pose_at(state, headings.at(state.t))
+        };
+        TagVisibility {
+            t: state.t,
+            heading: *pose.rotation(),
+            tags: tags.iter().filter(|tag| camera.sees(&pose, tag)).map(|tag| tag.id).collect()
+        }
+    }).collect()
+}
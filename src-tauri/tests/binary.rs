@@ -0,0 +1,68 @@
+//! Tests for the binary trajectory format.
+
+use clairvoyance::export::{AngleUnit, DistanceUnit};
+use clairvoyance::export::binary::{crc32, decode, encode, BinaryError, HEADER_SIZE, STATE_SIZE};
+use clairvoyance::geometry::{Pose2d, Rotation2d, Translation2d};
+use clairvoyance::trajectory::{Trajectory, TrajectoryConfig};
+use clairvoyance::trajectory::trajectory_generator::generate_trajectory;
+
+const TOLERANCE: f64 = 1E-9;
+
+fn s_curve() -> Trajectory {
+    generate_trajectory(
+        vec![
+            Pose2d::new(Translation2d::new(1., 1.), Rotation2d::from_degrees(0.)),
+            Pose2d::new(Translation2d::new(3., 2.), Rotation2d::from_degrees(45.)),
+            Pose2d::new(Translation2d::new(5., 2.), Rotation2d::from_degrees(0.))
+        ],
+        TrajectoryConfig::new(3., 2.)
+    )
+}
+
+#[test]
+fn round_trips_in_any_units() {
+    let trajectory = s_curve();
+    for (distance_unit, angle_unit) in [(DistanceUnit::Meters, AngleUnit::Radians), (DistanceUnit::Inches, AngleUnit::Degrees)] {
+        let bytes = encode(&trajectory, distance_unit, angle_unit);
+        assert_eq!(bytes.len(), HEADER_SIZE + trajectory.num_states() * STATE_SIZE);
+        assert_eq!(&bytes[..4], b"CLVT");
+
+        let decoded = decode(&bytes).unwrap();
+        assert_eq!(decoded.num_states(), trajectory.num_states());
+        assert!((decoded.total_time() - trajectory.total_time()).abs() < TOLERANCE);
+        for (expected, actual) in trajectory.states().iter().zip(decoded.states()) {
+            assert!((expected.velocity - actual.velocity).abs() < TOLERANCE);
+            assert!((expected.pose.translation().x() - actual.pose.translation().x()).abs() < TOLERANCE);
+            assert!((expected.pose.rotation().radians() - actual.pose.rotation().radians()).abs() < TOLERANCE);
+            assert!((expected.curvature - actual.curvature).abs() < TOLERANCE);
+        }
+    }
+}
+
+#[test]
+fn header_is_little_endian_with_units_and_count() {
+    let trajectory = s_curve();
+    let bytes = encode(&trajectory, DistanceUnit::Feet, AngleUnit::Degrees);
+    assert_eq!(u16::from_le_bytes([bytes[4], bytes[5]]), 1);
+    assert_eq!((bytes[6], bytes[7]), (1, 1));
+    assert_eq!(u32::from_le_bytes([bytes[8], bytes[9], bytes[10], bytes[11]]) as usize, trajectory.num_states());
+    assert_eq!(u32::from_le_bytes([bytes[24], bytes[25], bytes[26], bytes[27]]), crc32(&bytes[HEADER_SIZE..]));
+    // The standard check value of CRC-32, which java.util.zip.CRC32 also gives.
+    assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+}
+
+#[test]
+fn rejects_corrupted_data() {
+    let bytes = encode(&s_curve(), DistanceUnit::Meters, AngleUnit::Radians);
+
+    let mut flipped = bytes.clone();
+    flipped[HEADER_SIZE + 10] ^= 0x40;
+    assert!(matches!(decode(&flipped), Err(BinaryError::ChecksumMismatch { .. })));
+    assert_eq!(decode(&bytes[..bytes.len() - 1]).unwrap_err(), BinaryError::Truncated);
+    assert_eq!(decode(&bytes[..2]).unwrap_err(), BinaryError::Truncated);
+    assert_eq!(decode(b"[{\"time\": 0}]").unwrap_err(), BinaryError::NotATrajectory);
+
+    let mut newer = bytes;
+    newer[4] = 2;
+    assert_eq!(decode(&newer).unwrap_err(), BinaryError::UnsupportedVersion(2));
+}
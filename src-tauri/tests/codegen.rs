@@ -0,0 +1,157 @@
+//! Tests for generating robot code from autos.
+
+use clairvoyance::auto::{Auto, AutoConfig, AutoPath, PathConfig};
+use clairvoyance::codegen::{generate_code, CodegenOptions, Language};
+use clairvoyance::robot::{DrivetrainType, RobotConfig, SimpleMotorFeedforward};
+
+mod common;
+
+use common::waypoint;
+
+fn two_piece(robot: RobotConfig) -> Auto {
+    Auto {
+        title: "Two Piece".to_string(),
+        paths: vec![
+            AutoPath {
+                waypoints: vec![waypoint(1., 1., 90., Some(180.), false), waypoint(2., 1.5, 90., None, true), waypoint(3., 1., 90., Some(90.), false)],
+                config: PathConfig { end_velocity: Some(0.5), ..PathConfig::default() },
+                hidden: false
+            },
+            AutoPath {
+                waypoints: vec![waypoint(3., 1., 90., None, false), waypoint(5., 2., 90., None, false)],
+                config: PathConfig { max_velocity: Some(1.5), reversed: Some(true), ..PathConfig::default() },
+                hidden: false
+            }
+        ],
+        config: AutoConfig { max_velocity: 3., max_acceleration: 2., reversed: false },
+        robot
+    }
+}
+
+fn differential() -> RobotConfig {
+    RobotConfig {
+        track_width: 0.6,
+        drivetrain: DrivetrainType::Differential {
+            max_wheel_speed: 3.5,
+            feedforward: Some(SimpleMotorFeedforward::new(0.2, 2.5, 0.3)),
+            max_voltage: 10.
+        },
+        ..RobotConfig::default()
+    }
+}
+
+#[test]
+fn generates_java_with_waypoints_stops_and_constraints() {
+    let options = CodegenOptions { package: Some("frc.robot.autos".to_string()), ..CodegenOptions::default() };
+    let generated = generate_code(&two_piece(differential()), &options);
+    assert_eq!(generated.file_name, "TwoPiece.java");
+
+    let code = &generated.code;
+    assert!(code.contains("package frc.robot.autos;"));
+    assert!(code.contains("public final class TwoPiece {"));
+    assert!(code.contains("public static final double MAX_VELOCITY = 3.0;"));
+    assert!(code.contains("new Pose2d(2.0, 1.5, Rotation2d.fromDegrees(90.0))"));
+    assert!(code.contains("PATH_1_STOPS = List.of(1);"));
+    assert!(code.contains("PATH_2_STOPS = List.of();"));
+    // The second waypoint keeps facing the way the first one does.
+    assert!(code.contains("Rotation2d.fromDegrees(180.0),\n        Rotation2d.fromDegrees(180.0),\n        Rotation2d.fromDegrees(90.0)"));
+    assert!(code.contains("public static TrajectoryConfig path1Config() {\n        return new TrajectoryConfig(3.0, 2.0)"));
+    assert!(code.contains(".setEndVelocity(0.5)"));
+    assert!(code.contains("return new TrajectoryConfig(1.5, 2.0)\n            .setStartVelocity(0.0)\n            .setEndVelocity(0.0)\n            .setReversed(true)"));
+    assert!(code.contains(".addConstraint(new DifferentialDriveKinematicsConstraint(new DifferentialDriveKinematics(0.6), 3.5))"));
+    assert!(code.contains("new SimpleMotorFeedforward(0.2, 2.5, 0.3), new DifferentialDriveKinematics(0.6), 10.0)"));
+}
+
+#[test]
+fn names_code_the_way_each_language_does() {
+    let auto = two_piece(RobotConfig::default());
+    let cpp = generate_code(&auto, &CodegenOptions { language: Language::Cpp, ..CodegenOptions::default() });
+    assert_eq!(cpp.file_name, "TwoPiece.h");
+    assert!(cpp.code.contains("namespace TwoPiece {"));
+    assert!(cpp.code.contains("inline const std::vector<size_t> kPath1Stops{1};"));
+    assert!(cpp.code.contains("inline frc::TrajectoryConfig Path2Config() {"));
+    assert!(cpp.code.contains("config.SetReversed(true);"));
+
+    let python = generate_code(&auto, &CodegenOptions { language: Language::Python, ..CodegenOptions::default() });
+    assert_eq!(python.file_name, "two_piece.py");
+    assert!(python.code.contains("class TwoPiece:"));
+    assert!(python.code.contains("    PATH_1_STOPS = [1]"));
+    assert!(python.code.contains("    def path_2_config() -> TrajectoryConfig:"));
+    assert!(python.code.contains("config.setReversed(True)"));
+    // Swerve configs only get kinematics when they're given.
+    assert!(!python.code.contains("setKinematics"));
+}
+
+#[test]
+fn uses_the_configured_names() {
+    let options = CodegenOptions {
+        class_name: Some("blue alliance autos".to_string()),
+        path_names: vec!["scoreFirst".to_string(), " ".to_string()],
+        kinematics: Some("Constants.kSwerveKinematics".to_string()),
+        ..CodegenOptions::default()
+    };
+    let generated = generate_code(&two_piece(RobotConfig::default()), &options);
+    assert_eq!(generated.file_name, "BlueAllianceAutos.java");
+    assert!(generated.code.contains("public final class BlueAllianceAutos {"));
+    assert!(generated.code.contains("SCORE_FIRST_WAYPOINTS"));
+    assert!(generated.code.contains("scoreFirstConfig()"));
+    // A blank name falls back to the path's number.
+    assert!(generated.code.contains("PATH_2_WAYPOINTS"));
+    assert!(generated.code.contains(".setKinematics(Constants.kSwerveKinematics)"));
+    assert!(!generated.code.contains("package"));
+}
+
+#[test]
+fn numbers_names_that_come_out_the_same() {
+    let auto = two_piece(RobotConfig::default());
+    for (first, second) in [("Score", "score"), ("Path 1", "path-1")] {
+        let options = CodegenOptions { path_names: vec![first.to_string(), second.to_string()], ..CodegenOptions::default() };
+        let java = generate_code(&auto, &options).code;
+        let constant = first.to_ascii_uppercase().replace(' ', "_");
+        let function = first.to_ascii_lowercase().replace(' ', "");
+        assert_eq!(java.matches(&format!(" {}_WAYPOINTS ", constant)).count(), 1, "{}", java);
+        assert!(java.contains(&format!(" {}_2_WAYPOINTS ", constant)));
+        assert_eq!(java.matches(&format!(" {}Config()", function)).count(), 1);
+        assert!(java.contains(&format!(" {}2Config()", function)));
+    }
+}
+
+#[test]
+fn keeps_reserved_words_out_of_names() {
+    let mut auto = two_piece(RobotConfig::default());
+    auto.title = "class".to_string();
+    let options = CodegenOptions { path_names: vec!["def".to_string(), "new".to_string()], ..CodegenOptions::default() };
+
+    let python = generate_code(&auto, &CodegenOptions { language: Language::Python, ..options.clone() });
+    assert_eq!(python.file_name, "auto_class.py");
+    assert!(python.code.contains("    def path_def_config() -> TrajectoryConfig:"));
+    assert!(python.code.contains("    def new_config() -> TrajectoryConfig:"));
+    let java = generate_code(&auto, &options).code;
+    assert!(java.contains("public static TrajectoryConfig defConfig() {"));
+    assert!(java.contains("public static TrajectoryConfig pathNewConfig() {"));
+
+    auto.title = "None".to_string();
+    let python = generate_code(&auto, &CodegenOptions { language: Language::Python, ..options });
+    assert!(python.code.contains("class AutoNone:"));
+}
+
+#[test]
+fn names_cannot_break_out_of_comments_or_expand_placeholders() {
+    let mut auto = two_piece(RobotConfig::default());
+    auto.title = "Sneaky\" */ ${class}\nSystem.exit(0); \\".to_string();
+    let options = CodegenOptions {
+        path_names: vec!["score */ ${paths}".to_string()],
+        package: Some("frc.robot;\nimport evil".to_string()),
+        ..CodegenOptions::default()
+    };
+    for language in [Language::Java, Language::Cpp, Language::Python] {
+        let code = generate_code(&auto, &CodegenOptions { language, ..options.clone() }).code;
+        let header = code.lines().next().unwrap();
+        assert!(header.ends_with("from \"Sneaky * / ${class} System.exit(0); \". Changes will be overwritten."), "{}", header);
+        assert!(code.contains("Waypoints of score * / ${paths}, with"));
+        assert_eq!(code.matches("*/").count(), code.matches("/**").count());
+        assert!(!code.lines().any(|line| line.starts_with("System.exit")));
+    }
+    let java = generate_code(&auto, &options).code;
+    assert!(java.contains("package frc.robotimportevil;\n"));
+}
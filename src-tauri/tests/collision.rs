@@ -0,0 +1,111 @@
+//! Tests for the shape intersection tests and the collision checker that
+//! sweeps the robot's footprint along a trajectory.
+
+use clairvoyance::auto::Headings;
+use clairvoyance::collision::{check_collisions, Obstacle};
+use clairvoyance::field::Field;
+use clairvoyance::geometry::{Rotation2d, Translation2d};
+use clairvoyance::geometry::shapes::{OrientedRectangle, Polygon};
+use clairvoyance::robot::RobotConfig;
+use clairvoyance::trajectory::TrajectoryConfig;
+use clairvoyance::trajectory::trajectory_generator::generate_trajectory;
+
+mod common;
+
+use common::{path, pose};
+
+#[test]
+fn separated_rectangles_do_not_intersect() {
+    let a = Polygon::rectangle(0., 0., 1., 1.);
+    assert!(!a.intersects(&Polygon::rectangle(2., 0., 3., 1.)));
+    assert!(!a.intersects(&Polygon::rectangle(0., 1.5, 1., 2.5)));
+}
+
+#[test]
+fn touching_rectangles_do_not_intersect() {
+    let a = Polygon::rectangle(0., 0., 1., 1.);
+    assert!(!a.intersects(&Polygon::rectangle(1., 0., 2., 1.)));
+}
+
+#[test]
+fn overlapping_rectangles_intersect() {
+    let a = Polygon::rectangle(0., 0., 1., 1.);
+    assert!(a.intersects(&Polygon::rectangle(0.5, 0.5, 1.5, 1.5)));
+    // Fully containing the other polygon counts too.
+    assert!(a.intersects(&Polygon::rectangle(0.25, 0.25, 0.75, 0.75)));
+}
+
+#[test]
+fn rotated_rectangle_reaches_further_along_its_diagonal() {
+    let obstacle = Polygon::rectangle(1.25, -0.1, 2., 0.1);
+    // A 2 m square centered on the origin reaches 1 m along the x axis, but
+    // rotated by 45 degrees its corner reaches about 1.41 m.
+    let square = OrientedRectangle::new(pose(0., 0., 0.), 2., 2.);
+    let diamond = OrientedRectangle::new(pose(0., 0., 45.), 2., 2.);
+    assert!(!obstacle.intersects(&square.to_polygon()));
+    assert!(obstacle.intersects(&diamond.to_polygon()));
+}
+
+#[test]
+fn contains_points_inside_and_on_edges() {
+    let triangle = Polygon::new(vec![Translation2d::new(0., 0.), Translation2d::new(2., 0.), Translation2d::new(0., 2.)]);
+    assert!(triangle.contains(&Translation2d::new(0.5, 0.5)));
+    assert!(triangle.contains(&Translation2d::new(1., 1.)));
+    assert!(!triangle.contains(&Translation2d::new(1.5, 1.5)));
+}
+
+#[test]
+fn reports_first_obstacle_hit() {
+    let field = Field {
+        obstacles: vec![
+            Obstacle::new("Near", Polygon::rectangle(4., 1., 4.5, 3.)),
+            Obstacle::new("Far", Polygon::rectangle(6., 1., 6.5, 3.))
+        ],
+        ..Field::default()
+    };
+    let trajectory = generate_trajectory(vec![pose(2., 2., 0.), pose(8., 2., 0.)], TrajectoryConfig::new(3., 3.));
+    let robot = RobotConfig::default();
+
+    let collision = check_collisions(&trajectory, &robot, &Headings::holding(Rotation2d::default()), &field).expect("drives straight through both obstacles");
+    assert_eq!(collision.obstacle, "Near");
+    // The front of the robot reaches the obstacle when its center is half a
+    // robot length away, give or take a sweep step.
+    let expected_x = 4. - robot.length / 2.;
+    assert!((collision.pose.translation().x() - expected_x).abs() < 0.03);
+    assert!(collision.t > 0. && collision.t < *trajectory.total_time());
+}
+
+#[test]
+fn clear_path_has_no_collision() {
+    let trajectory = generate_trajectory(
+        vec![pose(2., 4.4, 0.), pose(5.5, 4.6, 0.)], TrajectoryConfig::new(3., 3.));
+    assert!(check_collisions(&trajectory, &RobotConfig::default(), &Headings::holding(Rotation2d::default()), &Field::default()).is_none());
+}
+
+#[test]
+fn leaving_the_field_is_a_collision() {
+    let trajectory = generate_trajectory(
+        vec![pose(14., 7.5, 0.), pose(16.2, 7.6, 0.)], TrajectoryConfig::new(3., 3.));
+    let collision = check_collisions(&trajectory, &RobotConfig::default(), &Headings::holding(Rotation2d::default()), &Field::default()).expect("drives off the field");
+    assert_eq!(collision.obstacle, "Field perimeter");
+}
+
+#[test]
+fn swerve_footprint_turns_with_the_headings() {
+    // An obstacle just clear of the robot's side, which its corner swings
+    // into as it turns through 45 degrees halfway along.
+    let field = Field {
+        obstacles: vec![Obstacle::new("Beside", Polygon::rectangle(3.65, 4.95, 3.85, 5.2))],
+        ..Field::default()
+    };
+    let trajectory = generate_trajectory(vec![pose(2., 4.5, 0.), pose(5.5, 4.5, 0.)], TrajectoryConfig::new(3., 3.));
+    let robot = RobotConfig::default();
+    let mut turning = path(&[(2., 4.5), (5.5, 4.5)]);
+    turning.waypoints[1].psi = Some(90.);
+
+    assert!(check_collisions(&trajectory, &robot, &Headings::holding(Rotation2d::default()), &field).is_none());
+    let collision = check_collisions(&trajectory, &robot, &Headings::new(&turning, &trajectory), &field)
+        .expect("turns into the obstacle");
+    assert_eq!(collision.obstacle, "Beside");
+    assert!(*collision.pose.rotation().radians() > 0.);
+}
@@ -0,0 +1,54 @@
+// Not every test uses every helper.
+#![allow(dead_code)]
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use clairvoyance::auto::{Auto, AutoConfig, AutoPath, PathConfig, Waypoint};
+use clairvoyance::geometry::{Pose2d, Rotation2d, Translation2d};
+use clairvoyance::robot::RobotConfig;
+
+static DIRECTORIES: AtomicUsize = AtomicUsize::new(0);
+
+/** A fresh directory for a test to work in */
+pub fn temp_dir() -> PathBuf {
+    let path = std::env::temp_dir().join(format!(
+        "clairvoyance-test-{}-{}",
+        std::process::id(),
+        DIRECTORIES.fetch_add(1, Ordering::SeqCst)
+    ));
+    let _ = fs::remove_dir_all(&path);
+    fs::create_dir_all(&path).unwrap();
+    path
+}
+
+pub fn pose(x: f64, y: f64, degrees: f64) -> Pose2d {
+    Pose2d::new(Translation2d::new(x, y), Rotation2d::from_degrees(degrees))
+}
+
+/** A waypoint heading the path along th, both in degrees */
+pub fn waypoint(x: f64, y: f64, th: f64, psi: Option<f64>, stop: bool) -> Waypoint {
+    Waypoint { x, y, th: Some(th), psi, stop, hidden: false }
+}
+
+/** A path through the points, heading and facing along the x axis at each */
+pub fn path(points: &[(f64, f64)]) -> AutoPath {
+    AutoPath {
+        waypoints: points.iter()
+            .map(|&(x, y)| Waypoint { x, y, th: Some(0.), psi: Some(0.), stop: false, hidden: false })
+            .collect(),
+        config: PathConfig::default(),
+        hidden: false
+    }
+}
+
+/** An auto with the paths, at 3 m/s and 2 m/s² on the default robot */
+pub fn auto(title: &str, paths: Vec<AutoPath>) -> Auto {
+    Auto {
+        title: title.to_string(),
+        paths,
+        config: AutoConfig { max_velocity: 3., max_acceleration: 2., reversed: false },
+        robot: RobotConfig::default()
+    }
+}
@@ -0,0 +1,81 @@
+//! Tests for exporting the trajectories of an auto as CSV.
+
+use clairvoyance::export::{AngleUnit, DistanceUnit};
+use clairvoyance::export::csv::{auto_to_csv, CsvOptions};
+
+mod common;
+
+use common::{auto, path};
+
+/** The rows of a CSV after the header, as numbers */
+fn rows(csv: &str, delimiter: char) -> Vec<Vec<f64>> {
+    csv.lines().skip(1)
+        .map(|line| line.split(delimiter).map(|value| value.parse().unwrap()).collect())
+        .collect()
+}
+
+#[test]
+fn time_and_distance_carry_on_across_paths() {
+    let two_paths = auto("Two Piece", vec![path(&[(1., 1.), (3., 1.)]), path(&[(3., 1.), (5., 1.)])]);
+    let csv = auto_to_csv(&two_paths, &CsvOptions::default()).unwrap();
+
+    assert_eq!(
+        csv.lines().next().unwrap(),
+        "time (s),x (m),y (m),heading (deg),rotation (deg),velocity (m/s),acceleration (m/s^2),curvature (deg/m),distance (m)"
+    );
+    let rows = rows(&csv, ',');
+    assert!(rows.iter().all(|row| row.len() == 9));
+    assert!(rows.windows(2).all(|pair| pair[1][0] >= pair[0][0] && pair[1][8] >= pair[0][8] - 1E-9));
+    let last = rows.last().unwrap();
+    assert!((last[1] - 5.).abs() < 1E-6);
+    assert!((last[8] - 4.).abs() < 1E-6, "drove {} m instead of 4 m", last[8]);
+}
+
+#[test]
+fn resamples_at_a_fixed_period_in_other_units() {
+    let options = CsvOptions {
+        delimiter: '\t',
+        distance_unit: DistanceUnit::Feet,
+        angle_unit: AngleUnit::Radians,
+        sample_period: Some(0.02)
+    };
+    let csv = auto_to_csv(&auto("Two Piece", vec![path(&[(1., 1.), (3., 2.)])]), &options).unwrap();
+
+    assert!(csv.lines().next().unwrap().starts_with("time (s)\tx (ft)\ty (ft)\theading (rad)"));
+    let rows = rows(&csv, '\t');
+    for (i, row) in rows.iter().enumerate() {
+        assert!((row[0] - i as f64 * 0.02).abs() < 1E-9);
+    }
+    assert!((rows[0][1] - 1. / 0.3048).abs() < 1E-9);
+    let last = rows.last().unwrap();
+    assert!((last[1] - 3. / 0.3048).abs() < 0.1, "ended at x = {} ft", last[1]);
+}
+
+#[test]
+fn rejects_delimiters_that_are_part_of_numbers() {
+    let options = CsvOptions { delimiter: '.', ..CsvOptions::default() };
+    assert!(auto_to_csv(&auto("Two Piece", vec![path(&[(1., 1.), (3., 1.)])]), &options).is_err());
+    let options = CsvOptions { sample_period: Some(0.), ..CsvOptions::default() };
+    assert!(auto_to_csv(&auto("Two Piece", vec![path(&[(1., 1.), (3., 1.)])]), &options).is_err());
+}
+
+#[test]
+fn swerve_rotation_turns_between_waypoint_orientations() {
+    let mut turning = auto("Two Piece", vec![path(&[(1., 1.), (3., 1.), (5., 1.)])]);
+    let waypoints = &mut turning.paths[0].waypoints;
+    waypoints[0].psi = Some(170.);
+    waypoints[1].psi = Some(-150.);
+    waypoints[2].psi = None;
+    let csv = auto_to_csv(&turning, &CsvOptions::default()).unwrap();
+    let rows = rows(&csv, ',');
+
+    // Turns the short way around through 180 degrees, then holds.
+    let rotation = |row: &Vec<f64>| row[4].rem_euclid(360.);
+    assert!((rotation(&rows[0]) - 170.).abs() < 1E-9);
+    let middle = rows.iter().find(|row| (row[1] - 3.).abs() < 1E-6).unwrap();
+    assert!((rotation(middle) - 210.).abs() < 1E-6, "facing {} at the middle waypoint", rotation(middle));
+    assert!(rows.windows(2).all(|pair| rotation(&pair[1]) >= rotation(&pair[0]) - 1E-9));
+    assert!((rotation(rows.last().unwrap()) - 210.).abs() < 1E-9);
+    // Headings still follow the path.
+    assert!(rows.iter().all(|row| row[3].abs() < 1E-6));
+}
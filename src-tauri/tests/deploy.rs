@@ -0,0 +1,90 @@
+//! Tests for syncing a project's autos into the robot code.
+
+use std::fs;
+
+use clairvoyance::export::binary::decode;
+use clairvoyance::project::{Project, ProjectError};
+use clairvoyance::project::deploy::ExportFormat;
+
+mod common;
+
+use common::{auto, path, temp_dir};
+
+#[test]
+fn writes_a_wpilib_trajectory_for_every_path() {
+    let project = Project::open(&temp_dir()).unwrap();
+    let two_paths = auto("Two Piece", vec![path(&[(1., 1.), (3., 1.)]), path(&[(3., 1.), (5., 2.)])]);
+    project.save_auto(None, &two_paths).unwrap();
+
+    let manifest = project.sync_deploy(ExportFormat::WpilibJson, None).unwrap();
+    assert_eq!(manifest.autos["Two Piece.json"], ["Two Piece-1.wpilib.json", "Two Piece-2.wpilib.json"]);
+    let deployed = project.deploy_directory();
+    assert!(deployed.ends_with("src/main/deploy/paths"));
+    let states: serde_json::Value = serde_json::from_str(&fs::read_to_string(deployed.join("Two Piece-2.wpilib.json")).unwrap()).unwrap();
+    let states = states.as_array().unwrap();
+    assert_eq!(states[0]["time"], 0.);
+    assert_eq!(states[0]["pose"]["translation"]["x"], 3.);
+    assert!(states.last().unwrap()["pose"]["rotation"]["radians"].is_number());
+
+    assert_eq!(project.deploy_manifest().unwrap().format, ExportFormat::WpilibJson);
+}
+
+#[test]
+fn writes_a_binary_trajectory_for_every_path() {
+    let project = Project::open(&temp_dir()).unwrap();
+    project.save_auto(None, &auto("Two Piece", vec![path(&[(1., 1.), (3., 1.)]), path(&[(3., 1.), (5., 2.)])])).unwrap();
+
+    let manifest = project.sync_deploy(ExportFormat::Binary, None).unwrap();
+    assert_eq!(manifest.autos["Two Piece.json"], ["Two Piece-1.bin", "Two Piece-2.bin"]);
+    let trajectory = decode(&fs::read(project.deploy_directory().join("Two Piece-2.bin")).unwrap()).unwrap();
+    assert_eq!(*trajectory.init_pose().translation().x(), 3.);
+}
+
+#[test]
+fn cleans_up_only_its_own_stale_files() {
+    let project = Project::open(&temp_dir()).unwrap();
+    project.save_auto(Some("left.json"), &auto("Left", vec![path(&[(1., 1.), (3., 1.)])])).unwrap();
+    project.save_auto(Some("right.json"), &auto("Right", vec![path(&[(1., 7.), (3., 7.)])])).unwrap();
+    project.sync_deploy(ExportFormat::WpilibJson, None).unwrap();
+    let deployed = project.deploy_directory();
+    fs::write(deployed.join("handwritten.wpilib.json"), "[]").unwrap();
+
+    fs::remove_file(project.root().join("autos").join("right.json")).unwrap();
+    let manifest = project.sync_deploy(ExportFormat::JavaConstants, None).unwrap();
+    assert_eq!(manifest.removed, ["left-1.wpilib.json", "right-1.wpilib.json"]);
+    assert!(deployed.join("handwritten.wpilib.json").is_file());
+    assert!(!deployed.join("Left.java").exists());
+}
+
+#[test]
+fn writes_java_classes_to_the_robot_code_sources() {
+    let project = Project::open(&temp_dir()).unwrap();
+    project.save_auto(Some("left.json"), &auto("Left", vec![path(&[(1., 1.), (3., 1.)])])).unwrap();
+
+    let manifest = project.sync_deploy(ExportFormat::JavaConstants, None).unwrap();
+    assert_eq!(manifest.package.as_deref(), Some("frc.robot.autos"));
+    assert_eq!(manifest.autos["left.json"], ["Left.java"]);
+    let sources = project.root().join("src/main/java");
+    let java = fs::read_to_string(sources.join("frc/robot/autos/Left.java")).unwrap();
+    assert!(java.contains("package frc.robot.autos;"));
+    assert!(java.contains("public final class Left {"));
+    assert!(java.contains("new Pose2d(3.0, 1.0, Rotation2d.fromDegrees(0.0))"));
+    assert_eq!(project.deploy_manifest().unwrap().package.as_deref(), Some("frc.robot.autos"));
+
+    // Moving the classes to another package cleans up the old ones.
+    let manifest = project.sync_deploy(ExportFormat::JavaConstants, Some("frc.robot.paths")).unwrap();
+    assert_eq!(manifest.removed, ["Left.java"]);
+    assert!(!sources.join("frc/robot/autos/Left.java").exists());
+    assert!(sources.join("frc/robot/paths/Left.java").is_file());
+    let manifest = project.sync_deploy(ExportFormat::JavaConstants, Some("../..")).unwrap();
+    assert_eq!(manifest.package.as_deref(), Some("frc.robot.autos"));
+}
+
+#[test]
+fn refuses_to_deploy_two_autos_to_the_same_file() {
+    let project = Project::open(&temp_dir()).unwrap();
+    project.save_auto(Some("two piece.json"), &auto("A", vec![path(&[(1., 1.), (3., 1.)])])).unwrap();
+    project.save_auto(Some("two-piece.json"), &auto("B", vec![path(&[(1., 1.), (3., 1.)])])).unwrap();
+    assert!(matches!(project.sync_deploy(ExportFormat::JavaConstants, None), Err(ProjectError::DeployConflict(_))));
+    assert!(project.sync_deploy(ExportFormat::WpilibJson, None).is_ok());
+}
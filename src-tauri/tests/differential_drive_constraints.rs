@@ -0,0 +1,82 @@
+//! Checks that trajectories generated with the differential drive constraints
+//! never ask more of either side of the drivetrain than it can give. Ported
+//! from WPILib's DifferentialDriveKinematicsConstraintTest and
+//! DifferentialDriveVoltageConstraintTest.
+
+use clairvoyance::kinematics::{ChassisSpeeds, DifferentialDriveKinematics};
+use clairvoyance::robot::SimpleMotorFeedforward;
+use clairvoyance::trajectory::{Trajectory, TrajectoryConfig, TrajectoryConstraint};
+use clairvoyance::trajectory::cancellation::CancellationToken;
+use clairvoyance::trajectory::constraints::{DifferentialDriveKinematicsConstraint, DifferentialDriveVoltageConstraint};
+use clairvoyance::trajectory::trajectory_generator::try_generate_trajectory;
+
+mod common;
+
+use common::pose;
+
+const TOLERANCE: f64 = 0.05;
+
+/** A winding path with tight turns, so the outer wheel goes well over the chassis speed. */
+fn generate(constraint: Box<dyn TrajectoryConstraint>) -> Trajectory {
+    let waypoints = vec![pose(1., 1., 0.), pose(4., 1., 0.), pose(5., 2.5, 90.), pose(3., 4., 180.), pose(1., 3., 270.)];
+    let mut config = TrajectoryConfig::new(3.6576, 3.6576);
+    config.constraints.push(constraint);
+    try_generate_trajectory(waypoints, config, &CancellationToken::new()).unwrap()
+}
+
+#[test]
+fn kinematics_constraint_limits_wheel_speeds() {
+    let max_speed = 2.;
+    let kinematics = DifferentialDriveKinematics::new(0.6858);
+    let trajectory = generate(Box::new(DifferentialDriveKinematicsConstraint::new(kinematics, max_speed)));
+
+    for state in trajectory.states() {
+        let wheel_speeds = kinematics.to_wheel_speeds(
+            &ChassisSpeeds::new(state.velocity, 0., state.velocity * state.curvature));
+        assert!(wheel_speeds.left.abs() <= max_speed + TOLERANCE,
+            "left wheel at {} m/s at t={}", wheel_speeds.left, state.t);
+        assert!(wheel_speeds.right.abs() <= max_speed + TOLERANCE,
+            "right wheel at {} m/s at t={}", wheel_speeds.right, state.t);
+    }
+}
+
+#[test]
+fn voltage_constraint_limits_voltage() {
+    let feedforward = SimpleMotorFeedforward::new(1., 1., 3.);
+    let kinematics = DifferentialDriveKinematics::new(0.5);
+    let max_voltage = 10.;
+    let trajectory = generate(Box::new(DifferentialDriveVoltageConstraint::new(feedforward, kinematics, max_voltage)));
+
+    for state in trajectory.states() {
+        let wheel_speeds = kinematics.to_wheel_speeds(
+            &ChassisSpeeds::new(state.velocity, 0., state.velocity * state.curvature));
+
+        // Not really a strictly-correct test as we're using the chassis
+        // acceleration instead of the wheel acceleration, but much easier than
+        // doing it "properly" and a reasonable check anyway.
+        for wheel_speed in [wheel_speeds.left, wheel_speeds.right] {
+            let voltage = feedforward.calculate(wheel_speed, state.acceleration);
+            assert!(voltage.abs() <= max_voltage + TOLERANCE, "needs {} V at t={}", voltage, state.t);
+        }
+    }
+}
+
+#[test]
+fn voltage_constraint_handles_high_curvature_endpoints() {
+    let feedforward = SimpleMotorFeedforward::new(1., 1., 3.);
+    // Large track width, to turn with a radius of curvature less than half of
+    // it.
+    let kinematics = DifferentialDriveKinematics::new(3.);
+    let max_voltage = 10.;
+
+    // Roughly a quarter circle with a radius of 1 meter, driven forwards and
+    // then backwards.
+    let mut config = TrajectoryConfig::new(12., 12.);
+    config.constraints.push(Box::new(DifferentialDriveVoltageConstraint::new(feedforward, kinematics, max_voltage)));
+    assert!(try_generate_trajectory(vec![pose(1., 0., 90.), pose(0., 1., 180.)], config, &CancellationToken::new()).is_ok());
+
+    let mut config = TrajectoryConfig::new(12., 12.);
+    config.reversed = true;
+    config.constraints.push(Box::new(DifferentialDriveVoltageConstraint::new(feedforward, kinematics, max_voltage)));
+    assert!(try_generate_trajectory(vec![pose(1., 0., 270.), pose(0., 1., 0.)], config, &CancellationToken::new()).is_ok());
+}
@@ -0,0 +1,69 @@
+//! Tests for loading the season field models.
+
+use clairvoyance::field::{Field, FieldError, DEFAULT_SEASON, FIELD_VERSION};
+use clairvoyance::geometry::{Pose2d, Rotation2d, Translation2d};
+
+#[test]
+fn built_in_fields_load() {
+    for season in Field::seasons() {
+        let field = Field::for_season(season).unwrap();
+        assert_eq!(field.season, season);
+        assert!(field.version <= FIELD_VERSION);
+        // Everything on the field has to be inside the perimeter.
+        let perimeter = field.perimeter();
+        for obstacle in field.obstacles.iter().chain(&field.slow_zones) {
+            assert!(obstacle.shape.vertices().iter().all(|vertex| perimeter.contains(vertex)), "{} leaves the field", obstacle.name);
+        }
+        for tag in &field.april_tags {
+            assert!(perimeter.contains(tag.pose.translation()), "tag {} is off the field", tag.id);
+        }
+    }
+}
+
+#[test]
+fn the_current_field_is_only_parsed_once() {
+    assert!(std::ptr::eq(Field::current(), Field::current()));
+    assert_eq!(Field::current().season, DEFAULT_SEASON);
+    assert_eq!(Field::default().april_tags.len(), Field::current().april_tags.len());
+}
+
+#[test]
+fn matches_the_frontend_field_size() {
+    let frontend: serde_json::Value = serde_json::from_str(include_str!("../../src/lib/assets/field-data.json")).unwrap();
+    let field = Field::default();
+    assert_eq!(frontend["fieldLengthMeters"].as_f64(), Some(field.length));
+    assert_eq!(frontend["fieldWidthMeters"].as_f64(), Some(field.width));
+}
+
+#[test]
+fn newer_versions_are_rejected() {
+    let json = format!(r#"{{ "version": {}, "something_new": true }}"#, FIELD_VERSION + 1);
+    assert!(matches!(Field::from_json(&json), Err(FieldError::UnsupportedVersion(_))));
+    assert!(matches!(Field::for_season(1992), Err(FieldError::UnknownSeason(1992))));
+}
+
+#[test]
+fn mirroring_swaps_alliances() {
+    let field = Field::default();
+    let blue = Pose2d::new(Translation2d::new(2., 3.), Rotation2d::from_degrees(30.));
+    let red = field.mirror_pose(&blue);
+    assert!((red.translation().x() - (field.length - 2.)).abs() < 1E-9);
+    assert_eq!(*red.translation().y(), 3.);
+    assert!((red.rotation().radians() - 150_f64.to_radians()).abs() < 1E-9);
+
+    // Every blue tag has a red twin in the mirrored spot, give or take the
+    // millimeters the real field is off from symmetric.
+    for tag in &field.april_tags {
+        let mirrored = field.mirror_translation(tag.pose.translation());
+        assert!(field.april_tags.iter().any(|other| other.pose.translation().distance_to(&mirrored) < 0.01));
+    }
+}
+
+#[test]
+fn snaps_to_the_nearest_landmark() {
+    let field = Field::default();
+    let column = &field.scoring_locations[0];
+    let nearby = *column.pose.translation() + Translation2d::new(0.05, 0.05);
+    assert_eq!(field.nearest_landmark(&nearby, 0.15).unwrap().name, column.name);
+    assert!(field.nearest_landmark(&Translation2d::new(8., 6.), 0.15).is_none());
+}
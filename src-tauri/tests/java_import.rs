@@ -0,0 +1,145 @@
+//! Tests for reading paths out of Java source.
+
+use clairvoyance::auto::{Auto, AutoConfig, AutoPath, PathConfig};
+use clairvoyance::codegen::{generate_code, CodegenOptions};
+use clairvoyance::codegen::import::{import_java, ImportError};
+use clairvoyance::field::{Field, DEFAULT_SEASON};
+use clairvoyance::robot::{DrivetrainType, RobotConfig, SimpleMotorFeedforward};
+
+mod common;
+
+use common::waypoint;
+
+fn field() -> Field {
+    Field::for_season(DEFAULT_SEASON).unwrap()
+}
+
+fn assert_close(actual: f64, expected: f64) {
+    assert!((actual - expected).abs() < 1e-9, "{} isn't {}", actual, expected);
+}
+
+#[test]
+fn reads_generated_code_back_without_losing_anything() {
+    let auto = Auto {
+        title: "Two Piece".to_string(),
+        paths: vec![
+            AutoPath {
+                waypoints: vec![waypoint(1., 1., 0., Some(180.), false), waypoint(2.5, 1.25, 45., Some(180.), true), waypoint(4., 2., 90., Some(90.), false)],
+                config: PathConfig { end_velocity: Some(0.5), ..PathConfig::default() },
+                hidden: false
+            },
+            AutoPath {
+                waypoints: vec![waypoint(4., 2., -90., Some(90.), false), waypoint(1., 1., 180., Some(0.), false)],
+                config: PathConfig { max_velocity: Some(1.5), reversed: Some(true), ..PathConfig::default() },
+                hidden: false
+            }
+        ],
+        config: AutoConfig { max_velocity: 3., max_acceleration: 2., reversed: false },
+        robot: RobotConfig {
+            track_width: 0.6,
+            drivetrain: DrivetrainType::Differential {
+                max_wheel_speed: 3.5,
+                feedforward: Some(SimpleMotorFeedforward::new(0.2, 2.5, 0.3)),
+                max_voltage: 10.
+            },
+            ..RobotConfig::default()
+        }
+    };
+    let options = CodegenOptions { package: Some("frc.robot.autos".to_string()), ..CodegenOptions::default() };
+    let code = generate_code(&auto, &options).code;
+
+    let imported = import_java(&code, &field()).unwrap();
+    assert!(imported.warnings.is_empty(), "{:?}", imported.warnings);
+    assert_eq!(imported.autos.len(), 1);
+    assert_eq!(serde_json::to_value(&imported.autos[0]).unwrap(), serde_json::to_value(&auto).unwrap());
+}
+
+#[test]
+fn works_out_arithmetic_on_constants_in_waypoint_arrays() {
+    let code = r#"
+        public final class Constants {
+            public static final double kFieldMid = fieldWidth / 2;
+            private static final double kInset = Units.inchesToMeters(12);
+        }
+
+        public static final TrajectoryConfig scoreTwoConfig = new TrajectoryConfig(
+            3.0, 2.5
+        ).setKinematics(Constants.SwerveConstants.SwerveKinematics)
+        .setReversed(true);
+        public static final SwerveTrajectoryWaypoint[] scoreTwo = new SwerveTrajectoryWaypoint[] {
+            new SwerveTrajectoryWaypoint(
+                new Translation2d(1.5 + Constants.kInset, Constants.kFieldMid),
+                Rotation2d.fromDegrees(180),
+                new Rotation2d(Math.PI / 2)),
+            null,
+            new SwerveTrajectoryWaypoint(new Translation2d(Math.max(2, 3), 1), Rotation2d.fromDegrees(-90), Rotation2d.fromDegrees(0)),
+        };
+    "#;
+    let imported = import_java(code, &field()).unwrap();
+    assert_eq!(imported.autos.len(), 1);
+    let auto = &imported.autos[0];
+    assert_eq!(auto.title, "Score Two");
+    assert_eq!((auto.config.max_velocity, auto.config.max_acceleration, auto.config.reversed), (3., 2.5, true));
+    assert!(matches!(auto.robot.drivetrain, DrivetrainType::Swerve));
+
+    let waypoints = &auto.paths[0].waypoints;
+    assert_eq!(waypoints.len(), 2);
+    assert_close(waypoints[0].x, 1.5 + 0.3048);
+    assert_close(waypoints[0].y, field().width / 2.);
+    assert_eq!(waypoints[0].psi, Some(180.));
+    assert_close(waypoints[0].th.unwrap(), 90.);
+    assert!(waypoints[0].stop);
+    assert_eq!((waypoints[1].x, waypoints[1].psi, waypoints[1].stop), (3., Some(-90.), false));
+    assert!(auto.paths[0].config.max_velocity.is_none());
+}
+
+#[test]
+fn reads_trajectories_generated_in_methods() {
+    let code = r#"
+        package frc.robot.commands;
+
+        import edu.wpi.first.math.geometry.Pose2d;
+
+        /** Drives forward and back. */
+        public class DriveForward extends SequentialCommandGroup {
+            private static final double DISTANCE = 2 * 1.5;
+
+            public DriveForward(Drivetrain drive) {
+                TrajectoryConfig config = new TrajectoryConfig(2, 1);
+                config.setKinematics(new DifferentialDriveKinematics(0.7));
+                config.setEndVelocity(0.5);
+                List<Translation2d> interior = new ArrayList<>();
+                interior.add(new Translation2d(DISTANCE / 2, 0.25));
+                addCommands(
+                    new RamseteCommand(
+                        TrajectoryGenerator.generateTrajectory(new Pose2d(), interior, new Pose2d(DISTANCE, 0, new Rotation2d()), config),
+                        drive::getPose,
+                        drive::tankDriveVolts,
+                        drive));
+                Pose2d heading = new Pose2d(1, 2, drive.getHeading());
+            }
+        }
+    "#;
+    let imported = import_java(code, &field()).unwrap();
+    let auto = &imported.autos[0];
+    assert_eq!(auto.title, "Drive Forward");
+    assert_eq!(auto.paths.len(), 1);
+    let positions: Vec<_> = auto.paths[0].waypoints.iter().map(|waypoint| (waypoint.x, waypoint.y, waypoint.th)).collect();
+    assert_eq!(positions, [(0., 0., Some(0.)), (1.5, 0.25, None), (3., 0., Some(0.))]);
+    assert_eq!((auto.config.max_velocity, auto.config.max_acceleration), (2., 1.));
+    assert_eq!(auto.paths[0].config.end_velocity, Some(0.5));
+    assert_eq!(auto.robot.track_width, 0.7);
+    assert!(matches!(auto.robot.drivetrain, DrivetrainType::Differential { feedforward: None, .. }));
+
+    assert_eq!(imported.warnings.len(), 1);
+    assert_eq!(imported.warnings[0].line, Some(22));
+    assert!(imported.warnings[0].message.contains("drive isn't defined"), "{}", imported.warnings[0].message);
+}
+
+#[test]
+fn reports_code_without_paths() {
+    assert!(matches!(import_java("class A { /* never closed", &field()), Err(ImportError::Syntax { line: 1, .. })));
+    let error = import_java("class A { Pose2d start = new Pose2d(1, 2, kMissing); }", &field()).unwrap_err();
+    assert!(matches!(error, ImportError::NoPaths(_)));
+    assert!(error.to_string().contains("kMissing isn't defined"), "{}", error);
+}
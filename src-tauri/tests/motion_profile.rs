@@ -0,0 +1,123 @@
+//! Tests for turning autos into motion profiles for motor controllers.
+
+use std::f64::consts::PI;
+
+use clairvoyance::auto::{Auto, AutoPath};
+use clairvoyance::export::motion_profile::{motion_profile_files, motion_profiles, MotionProfileFormat, MotionProfileOptions, PhoenixVersion};
+use clairvoyance::robot::{DrivetrainType, RobotConfig};
+
+mod common;
+
+use common::{auto, path};
+
+fn two_piece(paths: Vec<AutoPath>, robot: RobotConfig) -> Auto {
+    Auto { robot, ..auto("Two Piece", paths) }
+}
+
+fn differential() -> RobotConfig {
+    RobotConfig {
+        drivetrain: DrivetrainType::Differential { max_wheel_speed: 4., feedforward: None, max_voltage: 10. },
+        wheel_diameter: 0.1524,
+        gear_ratio: 10.71,
+        ..RobotConfig::default()
+    }
+}
+
+#[test]
+fn straight_paths_turn_both_sides_by_the_distance_driven() {
+    let two_paths = two_piece(vec![path(&[(1., 1.), (3., 1.)]), path(&[(3., 1.), (5., 1.)])], differential());
+    let options = MotionProfileOptions { phoenix_version: PhoenixVersion::Phoenix6, ..MotionProfileOptions::default() };
+    let profiles = motion_profiles(&two_paths, &options).unwrap();
+
+    assert_eq!(profiles.iter().map(|profile| profile.wheel.as_str()).collect::<Vec<_>>(), ["left", "right"]);
+    let expected = 4. / (PI * 0.1524) * 10.71;
+    for profile in &profiles {
+        let last = profile.points.last().unwrap();
+        assert!((last.position - expected).abs() < 0.05 * expected, "{} turned {} rotations instead of {}", profile.wheel, last.position, expected);
+        assert!(last.velocity.abs() < 1E-6);
+        assert!(profile.points.iter().all(|point| point.duration_ms == 10 && point.angle.is_none()));
+        assert!(profile.points.windows(2).all(|pair| pair[1].position >= pair[0].position));
+    }
+}
+
+#[test]
+fn swerve_profiles_have_a_file_per_module_with_steering_angles() {
+    let options = MotionProfileOptions { format: MotionProfileFormat::Rev, period_ms: 20, ..MotionProfileOptions::default() };
+    let files = motion_profile_files("Two Piece", &two_piece(vec![path(&[(1., 1.), (3., 2.)])], RobotConfig::default()), &options).unwrap();
+
+    let names: Vec<&str> = files.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(names, ["Two Piece-front-left.rev.csv", "Two Piece-front-right.rev.csv", "Two Piece-back-left.rev.csv", "Two Piece-back-right.rev.csv"]);
+    let mut lines = files[0].1.lines();
+    assert_eq!(lines.next(), Some("time (s),position (rotations),velocity (RPM),angle (deg)"));
+    for (i, line) in lines.enumerate() {
+        let values: Vec<f64> = line.split(',').map(|value| value.parse().unwrap()).collect();
+        assert_eq!(values.len(), 4);
+        assert!((values[0] - i as f64 * 0.02).abs() < 1E-9);
+    }
+}
+
+#[test]
+fn ctre_profiles_keep_points_short_enough_for_the_buffer() {
+    let straight = two_piece(vec![path(&[(1., 1.), (3., 1.)])], differential());
+    let files = motion_profile_files("Straight", &straight, &MotionProfileOptions::default()).unwrap();
+    assert_eq!(files[0].0, "Straight-left.ctre.csv");
+    assert!(files[0].1.lines().all(|line| line.split(',').count() == 3 && line.ends_with(",10")));
+
+    let too_long = MotionProfileOptions { period_ms: 200, ..MotionProfileOptions::default() };
+    assert!(motion_profiles(&straight, &too_long).is_err());
+    let no_wheels = two_piece(vec![path(&[(1., 1.), (3., 1.)])], RobotConfig { wheel_diameter: 0., ..differential() });
+    assert!(motion_profiles(&no_wheels, &MotionProfileOptions::default()).is_err());
+}
+
+#[test]
+fn rev_profiles_take_any_period_of_at_least_a_millisecond() {
+    let straight = two_piece(vec![path(&[(1., 1.), (3., 1.)])], differential());
+    let rev = |period_ms| MotionProfileOptions { format: MotionProfileFormat::Rev, period_ms, ..MotionProfileOptions::default() };
+    assert!(motion_profiles(&straight, &rev(200)).is_ok());
+    let err = motion_profiles(&straight, &rev(0)).unwrap_err().to_string();
+    assert!(err.contains("at least 1 ms"), "{}", err);
+}
+
+#[test]
+fn swerve_modules_steer_apart_while_the_robot_turns() {
+    let straight = path(&[(1., 1.), (4., 1.)]);
+    let mut turning = straight.clone();
+    turning.waypoints[1].psi = Some(90.);
+    let angles_halfway = |path: AutoPath| {
+        let profiles = motion_profiles(&two_piece(vec![path], RobotConfig::default()), &MotionProfileOptions::default()).unwrap();
+        let halfway = profiles[0].points.len() / 2;
+        profiles.iter().map(|profile| profile.points[halfway].angle.unwrap()).collect::<Vec<_>>()
+    };
+
+    // Driving straight every module points the same way, but turning at
+    // the same time each one has to sweep around the center differently.
+    let holding = angles_halfway(straight);
+    assert!(holding.iter().all(|angle| (angle - holding[0]).abs() < 1E-6));
+    let turning = angles_halfway(turning);
+    assert!(turning.iter().any(|angle| (angle - turning[0]).abs() > 1.), "{:?}", turning);
+}
+
+#[test]
+fn ctre_profiles_are_in_the_units_of_the_phoenix_version() {
+    let straight = two_piece(vec![path(&[(1., 1.), (3., 1.)])], differential());
+    let rev = motion_profiles(&straight, &MotionProfileOptions { format: MotionProfileFormat::Rev, ..MotionProfileOptions::default() }).unwrap();
+    let phoenix6 = MotionProfileOptions { phoenix_version: PhoenixVersion::Phoenix6, ..MotionProfileOptions::default() };
+    let phoenix6 = motion_profiles(&straight, &phoenix6).unwrap();
+    let phoenix5 = MotionProfileOptions { counts_per_rev: 4096., ..MotionProfileOptions::default() };
+    let phoenix5 = motion_profiles(&straight, &phoenix5).unwrap();
+
+    let fastest = rev[0].points.iter().map(|point| point.velocity).fold(0., f64::max);
+    assert!(fastest > 100.);
+    for ((rev, phoenix6), phoenix5) in rev[0].points.iter().zip(&phoenix6[0].points).zip(&phoenix5[0].points) {
+        // RPM are rotations per second times 60, and sensor units per 100 ms a tenth of counts per second.
+        assert!((phoenix6.position - rev.position).abs() < 1E-9);
+        assert!((phoenix6.velocity - rev.velocity / 60.).abs() < 1E-9);
+        assert!((phoenix5.position - rev.position * 4096.).abs() < 1E-6);
+        assert!((phoenix5.velocity - rev.velocity / 60. * 409.6).abs() < 1E-6);
+    }
+
+    let no_encoder = MotionProfileOptions { counts_per_rev: 0., ..MotionProfileOptions::default() };
+    assert!(motion_profiles(&straight, &no_encoder).is_err());
+    let rev_ignores_the_encoder = MotionProfileOptions { format: MotionProfileFormat::Rev, ..no_encoder };
+    assert!(motion_profiles(&straight, &rev_ignores_the_encoder).is_ok());
+}
@@ -0,0 +1,64 @@
+//! Checks that regenerating a path incrementally after moving a waypoint
+//! gives the same trajectory as generating it from scratch.
+
+use clairvoyance::trajectory::{Trajectory, TrajectoryConfig};
+use clairvoyance::trajectory::cancellation::CancellationToken;
+use clairvoyance::trajectory::path_model::PathModel;
+use clairvoyance::trajectory::trajectory_generator::try_generate_trajectory;
+
+mod common;
+
+use common::pose;
+
+const TOLERANCE: f64 = 1E-9;
+
+fn config(reversed: bool) -> TrajectoryConfig {
+    let mut config = TrajectoryConfig::new(3., 2.5);
+    config.reversed = reversed;
+    config
+}
+
+fn assert_same(incremental: &Trajectory, full: &Trajectory) {
+    assert_eq!(incremental.num_states(), full.num_states());
+    assert!((incremental.total_time() - full.total_time()).abs() < TOLERANCE);
+    for (a, b) in incremental.states().iter().zip(full.states()) {
+        assert!((a.t - b.t).abs() < TOLERANCE);
+        assert!((a.velocity - b.velocity).abs() < TOLERANCE);
+        assert!((a.curvature - b.curvature).abs() < TOLERANCE);
+        assert!((a.pose.translation().x() - b.pose.translation().x()).abs() < TOLERANCE);
+        assert!((a.pose.translation().y() - b.pose.translation().y()).abs() < TOLERANCE);
+        assert!((a.pose.rotation().radians() - b.pose.rotation().radians()).abs() < TOLERANCE);
+    }
+}
+
+#[test]
+fn moving_any_waypoint_matches_full_generation() {
+    let waypoints = vec![pose(1., 1., 0.), pose(3., 2., 45.), pose(5., 2., 0.), pose(7., 3., 30.)];
+    let moves = [(0, pose(1.5, 0.5, 20.)), (1, pose(3.2, 2.6, 60.)), (3, pose(7.5, 2., -15.))];
+    for reversed in [false, true] {
+        for &(index, moved) in &moves {
+            let mut model = PathModel::new(waypoints.clone(), reversed).unwrap();
+            let mut moved_waypoints = waypoints.clone();
+            moved_waypoints[index] = moved;
+            model.update(&moved_waypoints, reversed).unwrap();
+            assert_eq!(model.waypoints(), &moved_waypoints);
+
+            let incremental = model.trajectory(config(reversed), &CancellationToken::new()).unwrap();
+            let full = try_generate_trajectory(moved_waypoints, config(reversed), &CancellationToken::new()).unwrap();
+            assert_same(&incremental, &full);
+        }
+    }
+}
+
+#[test]
+fn moving_waypoints_one_after_another_matches_full_generation() {
+    let mut waypoints = vec![pose(1., 1., 0.), pose(3., 2., 45.), pose(5., 2., 0.)];
+    let mut model = PathModel::new(waypoints.clone(), false).unwrap();
+    for (index, moved) in [(1, pose(3., 3., 90.)), (2, pose(6., 1., -30.)), (1, pose(2.5, 2., 10.))] {
+        model.move_waypoint(index, moved).unwrap();
+        waypoints[index] = moved;
+    }
+    let incremental = model.trajectory(config(false), &CancellationToken::new()).unwrap();
+    let full = try_generate_trajectory(waypoints, config(false), &CancellationToken::new()).unwrap();
+    assert_same(&incremental, &full);
+}
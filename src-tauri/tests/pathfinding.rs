@@ -0,0 +1,118 @@
+//! Tests for finding paths around field elements.
+
+use clairvoyance::auto::Headings;
+use clairvoyance::collision::{check_collisions, Obstacle};
+use clairvoyance::field::Field;
+use clairvoyance::geometry::{Rotation2d, Translation2d};
+use clairvoyance::geometry::shapes::Polygon;
+use clairvoyance::pathfinding::{find_path, find_trajectory, PathfindingError};
+use clairvoyance::robot::{DrivetrainType, RobotConfig};
+use clairvoyance::trajectory::TrajectoryConfigNoConstraints;
+
+mod common;
+
+use common::pose;
+
+/** The field with the slow zones counted as obstacles, like path finding does */
+fn avoided() -> Field {
+    let field = Field::default();
+    Field { obstacles: field.avoided_obstacles(), slow_zones: Vec::new(), ..field }
+}
+
+fn config() -> TrajectoryConfigNoConstraints {
+    TrajectoryConfigNoConstraints {
+        max_velocity: 3.,
+        max_acceleration: 3.,
+        start_velocity: 0.,
+        end_velocity: 0.,
+        reversed: false
+    }
+}
+
+fn differential() -> RobotConfig {
+    RobotConfig {
+        drivetrain: DrivetrainType::Differential { max_wheel_speed: 4., feedforward: None, max_voltage: 10. },
+        ..RobotConfig::default()
+    }
+}
+
+#[test]
+fn segments_along_edges_do_not_cross() {
+    let square = Polygon::rectangle(0., 0., 1., 1.);
+    assert!(square.crosses(&Translation2d::new(-1., 0.5), &Translation2d::new(2., 0.5)));
+    assert!(!square.crosses(&Translation2d::new(-1., 0.), &Translation2d::new(2., 0.)));
+    assert!(!square.crosses(&Translation2d::new(-1., 2.), &Translation2d::new(2., 1.5)));
+    assert!(!square.crosses(&Translation2d::new(0., 1.), &Translation2d::new(1., 2.)));
+}
+
+#[test]
+fn minkowski_sum_of_rectangles_adds_their_sizes() {
+    let sum = Polygon::rectangle(0., 0., 2., 1.).minkowski_sum(&Polygon::rectangle(-0.5, -0.5, 0.5, 0.5));
+    assert_eq!(sum.vertices().len(), 4);
+    assert!(sum.contains(&Translation2d::new(-0.5, -0.5)));
+    assert!(sum.contains(&Translation2d::new(2.5, 1.5)));
+    assert!(!sum.contains(&Translation2d::new(2.6, 1.5)));
+}
+
+#[test]
+fn drives_straight_when_nothing_is_in_the_way() {
+    let waypoints = find_path(&pose(6., 2., 0.), &pose(10., 3., 0.), &RobotConfig::default(), &Field::default()).unwrap();
+    assert_eq!(waypoints.len(), 2);
+}
+
+#[test]
+fn goes_around_the_charge_station() {
+    let start = pose(1.8, 2.7, 0.);
+    let goal = pose(6., 2.7, 0.);
+    for robot in [RobotConfig::default(), differential()] {
+        let (waypoints, trajectory) = find_trajectory(&start, &goal, &robot, &Field::default(), config()).unwrap();
+        assert!(waypoints.len() > 2);
+        assert_eq!(waypoints[0], start);
+        assert_eq!(*waypoints.last().unwrap(), goal);
+        assert!(check_collisions(&trajectory, &robot, &Headings::holding(Rotation2d::default()), &avoided()).is_none());
+    }
+}
+
+#[test]
+fn refines_waypoints_when_the_spline_swings_wide() {
+    // The spline straight to the corner of the charge station dips out of
+    // the field, so the path needs refining.
+    let robot = RobotConfig::default();
+    let (_, trajectory) = find_trajectory(&pose(1.8, 0.5, 0.), &pose(6.5, 4.6, 90.), &robot, &Field::default(), config()).unwrap();
+    assert!(check_collisions(&trajectory, &robot, &Headings::holding(Rotation2d::default()), &avoided()).is_none());
+}
+
+#[test]
+fn can_start_against_the_grid() {
+    // Pushed up against the grid, closer than the clearance kept elsewhere.
+    let start = pose(1.38 + RobotConfig::default().length / 2., 2., 0.);
+    for robot in [RobotConfig::default(), differential()] {
+        assert!(find_path(&start, &pose(6., 0.7, 0.), &robot, &Field::default()).is_ok());
+    }
+}
+
+#[test]
+fn blocked_start_and_goal_are_errors() {
+    let robot = RobotConfig::default();
+    let inside_charge_station = pose(3.9, 2.7, 0.);
+    assert!(matches!(
+        find_path(&inside_charge_station, &pose(6., 2., 0.), &robot, &Field::default()),
+        Err(PathfindingError::StartBlocked)));
+    assert!(matches!(
+        find_path(&pose(6., 2., 0.), &inside_charge_station, &robot, &Field::default()),
+        Err(PathfindingError::GoalBlocked)));
+    assert!(matches!(
+        find_path(&pose(6., 2., 0.), &pose(16.5, 2., 0.), &robot, &Field::default()),
+        Err(PathfindingError::GoalBlocked)));
+}
+
+#[test]
+fn unreachable_goal_is_an_error() {
+    let walls = Field {
+        obstacles: vec![Obstacle::new("Wall", Polygon::rectangle(8., 0., 8.5, 8.0137))],
+        ..Field::default()
+    };
+    assert!(matches!(
+        find_path(&pose(6., 2., 0.), &pose(10., 2., 0.), &RobotConfig::default(), &walls),
+        Err(PathfindingError::NoPath)));
+}
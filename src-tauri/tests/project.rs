@@ -0,0 +1,97 @@
+//! Tests for keeping autos in a project directory.
+
+use std::fs;
+
+use clairvoyance::auto::Auto;
+use clairvoyance::auto::schema::{SchemaError, AUTO_SCHEMA_VERSION};
+use clairvoyance::project::{Project, ProjectError, RecentProjects};
+
+mod common;
+
+use common::{path, temp_dir};
+
+/** An auto with one path that faces a tag, so every part of the config is saved */
+fn auto(title: &str) -> Auto {
+    let mut auto = common::auto(title, vec![path(&[(1., 1.), (3., 2.)])]);
+    auto.paths[0].config.face_tag = Some(7);
+    auto
+}
+
+#[test]
+fn saved_autos_load_back() {
+    let project = Project::open(&temp_dir()).unwrap();
+    let file_name = project.save_auto(None, &auto("Two Piece: Left")).unwrap();
+    assert_eq!(file_name, "Two Piece_ Left.json");
+
+    // Saving again replaces the file without leaving anything behind.
+    let mut renamed = auto("Two Piece");
+    renamed.paths[0].waypoints.extend(path(&[(5., 2.)]).waypoints);
+    assert_eq!(project.save_auto(Some(&file_name), &renamed).unwrap(), file_name);
+    let loaded = project.load_auto(&file_name).unwrap();
+    assert_eq!(loaded.title, "Two Piece");
+    assert_eq!(loaded.paths[0].waypoints.len(), 3);
+    assert_eq!(loaded.paths[0].config.face_tag, Some(7));
+    assert_eq!(fs::read_dir(project.root().join("autos")).unwrap().count(), 1);
+}
+
+#[test]
+fn only_opens_existing_folders() {
+    let directory = temp_dir();
+    let missing = directory.join("typo");
+    assert!(matches!(Project::open(&missing), Err(ProjectError::NotADirectory(_))));
+    assert!(!missing.exists());
+    fs::write(directory.join("file.txt"), "").unwrap();
+    assert!(matches!(Project::open(&directory.join("file.txt")), Err(ProjectError::NotADirectory(_))));
+
+    let project = Project::open(&directory).unwrap();
+    assert!(project.root().join("autos").is_dir());
+}
+
+#[test]
+fn lists_autos_including_broken_ones() {
+    let project = Project::open(&temp_dir()).unwrap();
+    project.save_auto(Some("b.json"), &auto("Second")).unwrap();
+    project.save_auto(Some("a.json"), &auto("First")).unwrap();
+    fs::write(project.root().join("autos").join("broken.json"), "{").unwrap();
+    fs::write(project.root().join("autos").join("notes.txt"), "not an auto").unwrap();
+
+    let entries = project.list_autos().unwrap();
+    let names: Vec<_> = entries.iter().map(|entry| entry.file_name.as_str()).collect();
+    assert_eq!(names, ["a.json", "b.json", "broken.json"]);
+    assert_eq!(entries[0].title.as_deref(), Some("First"));
+    assert!(entries[2].title.is_none() && entries[2].error.is_some());
+}
+
+#[test]
+fn file_names_stay_inside_the_project() {
+    let project = Project::open(&temp_dir()).unwrap();
+    for name in ["../escape.json", "nested/auto.json", "auto.txt", ""] {
+        assert!(matches!(project.save_auto(Some(name), &auto("Auto")), Err(ProjectError::InvalidFileName(_))), "{}", name);
+    }
+}
+
+#[test]
+fn newer_versions_are_rejected() {
+    let project = Project::open(&temp_dir()).unwrap();
+    let json = format!(r#"{{ "version": {}, "something_new": true }}"#, AUTO_SCHEMA_VERSION + 1);
+    fs::write(project.root().join("autos").join("future.json"), json).unwrap();
+    assert!(matches!(project.load_auto("future.json"), Err(ProjectError::Schema(SchemaError::UnsupportedVersion(_)))));
+}
+
+#[test]
+fn recent_projects_are_newest_first() {
+    let directory = temp_dir();
+    let path = directory.join("config").join("recent-projects.json");
+    assert!(RecentProjects::load(&path).projects().is_empty());
+
+    let (first, second) = (temp_dir(), temp_dir());
+    let mut recent = RecentProjects::load(&path);
+    recent.add(&first);
+    recent.add(&second);
+    recent.add(&first);
+    recent.add(&directory.join("deleted"));
+    recent.save(&path).unwrap();
+
+    // Projects that no longer exist are left out.
+    assert_eq!(RecentProjects::load(&path).projects(), [first, second]);
+}
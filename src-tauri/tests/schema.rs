@@ -0,0 +1,79 @@
+//! Tests for reading auto files of every version.
+
+use clairvoyance::auto::schema::{parse_auto, to_json, SchemaError, ValidationError, AUTO_SCHEMA_VERSION};
+
+/** What the web app exported before files were versioned */
+const UNVERSIONED: &str = r#"{
+    "title": "Two Piece",
+    "paths": [
+        {
+            "waypoints": [
+                { "x": 1.5, "y": 1, "th": 0, "psi": 0, "hidden": false },
+                { "x": 4, "y": 1.5, "th": 0, "psi": 180, "stop": true, "hidden": false }
+            ],
+            "path": { "states": [], "totalTimeSeconds": 0 },
+            "config": { "maxVelocity": 2 },
+            "hidden": false
+        }
+    ],
+    "config": { "maxVelocity": 4.5, "maxAcceleration": 3.5, "reversed": false }
+}"#;
+
+fn locations(err: SchemaError) -> Vec<String> {
+    match err {
+        SchemaError::Invalid(errors) => errors.into_iter().map(|ValidationError { location, .. }| location).collect(),
+        err => panic!("expected validation errors, got {}", err)
+    }
+}
+
+#[test]
+fn unversioned_exports_are_migrated() {
+    let auto = parse_auto(UNVERSIONED).unwrap();
+    assert_eq!(auto.title, "Two Piece");
+    assert_eq!(auto.paths[0].waypoints.len(), 2);
+    assert!(auto.paths[0].waypoints[1].stop);
+    assert_eq!(auto.paths[0].config.max_velocity, Some(2.));
+
+    // Saving writes the current version, which reads back the same.
+    let json = to_json(&auto).unwrap();
+    let saved: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(saved["version"], AUTO_SCHEMA_VERSION);
+    assert!(saved["paths"][0].get("path").is_none());
+    assert_eq!(parse_auto(&json).unwrap().paths[0].waypoints[1].psi, Some(180.));
+}
+
+#[test]
+fn malformed_pieces_are_reported_with_where_they_are() {
+    let json = UNVERSIONED
+        .replace(r#""x": 4,"#, r#""x": "four","#)
+        .replace(r#""maxAcceleration": 3.5,"#, "");
+    assert_eq!(locations(parse_auto(&json).unwrap_err()), ["config", "paths[0].waypoints[1]"]);
+
+    assert_eq!(locations(parse_auto("[]").unwrap_err()), ["auto"]);
+    assert!(matches!(parse_auto("{"), Err(SchemaError::Parse(_))));
+    let json = format!(r#"{{ "version": {}, "something_new": true }}"#, AUTO_SCHEMA_VERSION + 1);
+    assert!(matches!(parse_auto(&json), Err(SchemaError::UnsupportedVersion(_))));
+}
+
+#[test]
+fn impossible_values_are_rejected() {
+    let json = UNVERSIONED
+        .replace(r#""title": "Two Piece""#, r#""title": " ""#)
+        .replace(r#""maxVelocity": 2"#, r#""maxVelocity": -2, "endVelocity": -1"#);
+    assert_eq!(
+        locations(parse_auto(&json).unwrap_err()),
+        ["title", "paths[0].config.maxVelocity", "paths[0].config.endVelocity"]
+    );
+}
+
+#[test]
+fn voltage_constraints_need_an_acceleration_gain() {
+    let robot = r#""robot": {
+        "width": 0.8, "length": 0.9, "wheelbase": 0.6, "trackWidth": 0.6,
+        "drivetrain": { "type": "differential", "maxWheelSpeed": 4, "maxVoltage": 10, "feedforward": { "ks": 0.2, "kv": 2, "ka": 0 } }
+    },
+    "title""#;
+    let json = UNVERSIONED.replacen(r#""title""#, robot, 1);
+    assert_eq!(locations(parse_auto(&json).unwrap_err()), ["robot.drivetrain.feedforward.ka"]);
+    assert!(parse_auto(&json.replace(r#""ka": 0"#, r#""ka": 0.2"#)).is_ok());
+}
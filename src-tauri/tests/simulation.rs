@@ -0,0 +1,80 @@
+//! Tests for simulating a robot following a trajectory.
+
+use clairvoyance::auto::Headings;
+use clairvoyance::geometry::Rotation2d;
+use clairvoyance::simulation::{simulate, simulate_from, Controller, SimulationConfig, SimulationError};
+use clairvoyance::trajectory::{Trajectory, TrajectoryConfig};
+use clairvoyance::trajectory::trajectory_generator::generate_trajectory;
+
+mod common;
+
+use common::{path, pose};
+
+fn trajectory() -> Trajectory {
+    generate_trajectory(vec![pose(1., 1., 0.), pose(3., 2., 45.), pose(5., 3., 0.)], TrajectoryConfig::new(2., 2.))
+}
+
+fn holding() -> Headings {
+    Headings::holding(Rotation2d::default())
+}
+
+/** A drivetrain that follows every command instantly and never saturates */
+fn perfect(controller: Controller) -> SimulationConfig {
+    SimulationConfig { controller, max_wheel_speed: 100., time_constant: 0., dt: 0.005, ..SimulationConfig::default() }
+}
+
+const CONTROLLERS: [Controller; 2] = [
+    Controller::Holonomic { translation_kp: 2., rotation_kp: 2. },
+    Controller::Ramsete { b: 2., zeta: 0.7 }
+];
+
+#[test]
+fn perfect_drivetrains_track_the_trajectory() {
+    let trajectory = trajectory();
+    for controller in CONTROLLERS {
+        let result = simulate(&trajectory, &holding(), &perfect(controller)).unwrap();
+        assert!(*result.max_error() < 5E-3, "{:?} strayed {} m", controller, result.max_error());
+        let last = result.states().last().unwrap();
+        assert!((last.t - trajectory.total_time()).abs() < 1E-9);
+    }
+}
+
+#[test]
+fn robots_placed_off_the_path_converge_to_it() {
+    let trajectory = trajectory();
+    let start = pose(1.2, 0.8, 0.);
+    for controller in CONTROLLERS {
+        let result = simulate_from(&trajectory, &holding(), &perfect(controller), start).unwrap();
+        let first = result.states()[0].error;
+        let last = result.states().last().unwrap().error;
+        assert!(first > 0.25);
+        assert!(last < 0.05, "{:?} ended {} m away", controller, last);
+    }
+}
+
+#[test]
+fn holonomic_drives_turn_to_the_waypoint_orientations() {
+    let trajectory = generate_trajectory(vec![pose(1., 1., 0.), pose(5., 1., 0.)], TrajectoryConfig::new(2., 2.));
+    let mut turning = path(&[(1., 1.), (5., 1.)]);
+    turning.waypoints[1].psi = Some(90.);
+    let headings = Headings::new(&turning, &trajectory);
+
+    let result = simulate(&trajectory, &headings, &perfect(CONTROLLERS[0])).unwrap();
+    assert!(*result.max_error() < 5E-3);
+    let halfway = result.states()[result.states().len() / 2].pose.rotation().radians().to_degrees();
+    assert!(halfway > 20. && halfway < 70., "faced {} degrees halfway", halfway);
+    let last = result.states().last().unwrap().pose.rotation().radians().to_degrees();
+    assert!((last - 90.).abs() < 20., "ended facing {} degrees", last);
+}
+
+#[test]
+fn impossible_configs_are_rejected() {
+    let trajectory = trajectory();
+    for dt in [0., -0.02, f64::NAN, f64::INFINITY] {
+        let config = SimulationConfig { dt, ..SimulationConfig::default() };
+        assert!(matches!(simulate(&trajectory, &holding(), &config), Err(SimulationError::InvalidTimestep(_))));
+    }
+    let config = SimulationConfig { time_constant: -0.1, ..SimulationConfig::default() };
+    assert_eq!(simulate(&trajectory, &holding(), &config).unwrap_err(), SimulationError::InvalidTimeConstant(-0.1));
+    assert!(simulate(&Trajectory::do_nothing(), &holding(), &SimulationConfig::default()).unwrap().states().is_empty());
+}
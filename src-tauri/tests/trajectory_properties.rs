@@ -0,0 +1,156 @@
+//! Randomized tests asserting invariants every generated trajectory should
+//! hold, whatever the waypoints and config.
+
+use proptest::prelude::*;
+
+use clairvoyance::geometry::{Pose2d, Rotation2d, Translation2d};
+use clairvoyance::trajectory::{Trajectory, TrajectoryConfig};
+use clairvoyance::trajectory::cancellation::CancellationToken;
+use clairvoyance::trajectory::spline_helper::quintic_spline_between;
+use clairvoyance::trajectory::spline_parameterizer::try_parameterize;
+use clairvoyance::trajectory::trajectory_generator::{try_generate_trajectory, GenerationError};
+
+const TOLERANCE: f64 = 1E-6;
+/**
+ * The time parameterizer allows its acceleration limits to be exceeded by
+ * 1E-6 before it tightens them, which can push the resulting acceleration
+ * slightly over the limit on very short paths. This allows for 0.1% of the max
+ * acceleration.
+ */
+const RELATIVE_ACCELERATION_TOLERANCE: f64 = 1E-3;
+
+#[derive(Clone, Copy, Debug)]
+struct Config {
+    max_velocity: f64,
+    max_acceleration: f64,
+    start_velocity: f64,
+    end_velocity: f64,
+    reversed: bool
+}
+impl Config {
+    fn to_trajectory_config(self) -> TrajectoryConfig {
+        let mut config = TrajectoryConfig::new(self.max_velocity, self.max_acceleration);
+        config.start_velocity = self.start_velocity;
+        config.end_velocity = self.end_velocity;
+        config.reversed = self.reversed;
+        config
+    }
+}
+
+fn waypoint() -> impl Strategy<Value = Pose2d> {
+    (0.0..16.5, 0.0..8.0, -180.0..180.0).prop_map(|(x, y, degrees)|
+        Pose2d::new(Translation2d::new(x, y), Rotation2d::from_degrees(degrees)))
+}
+
+/** Waypoints anywhere on the field. */
+fn waypoints() -> impl Strategy<Value = Vec<Pose2d>> {
+    prop::collection::vec(waypoint(), 2..8)
+}
+
+/**
+ * Pairs of waypoints almost or exactly on top of each other, facing roughly
+ * opposite ways.
+ */
+fn near_coincident_waypoints() -> impl Strategy<Value = Vec<Pose2d>> {
+    let offset = prop_oneof![Just(0.0), -0.05..0.05];
+    (waypoint(), offset.clone(), offset, 150.0..210.0).prop_map(|(start, dx, dy, turn)| {
+        let end = Pose2d::new(
+            Translation2d::new(start.translation().x() + dx, start.translation().y() + dy),
+            Rotation2d::from_degrees(start.rotation().radians().to_degrees() + turn));
+        vec![start, end]
+    })
+}
+
+fn config() -> impl Strategy<Value = Config> {
+    (0.5..6.0, 0.5..6.0, 0.0..1.0, 0.0..1.0, any::<bool>()).prop_map(
+        |(max_velocity, max_acceleration, start, end, reversed)| Config {
+            max_velocity,
+            max_acceleration,
+            // Keep start and end velocities reachable within max velocity.
+            start_velocity: start * max_velocity,
+            end_velocity: end * max_velocity,
+            reversed
+        })
+}
+
+fn generate(waypoints: Vec<Pose2d>, config: Config) -> Result<Trajectory, GenerationError> {
+    try_generate_trajectory(waypoints, config.to_trajectory_config(), &CancellationToken::new())
+}
+
+fn check_invariants(trajectory: &Trajectory, config: Config) -> Result<(), TestCaseError> {
+    let states = trajectory.states();
+    prop_assert!(!states.is_empty());
+    prop_assert!(trajectory.total_time().is_finite());
+
+    for (i, state) in states.iter().enumerate() {
+        prop_assert!(state.t.is_finite(), "time at state {} is {}", i, state.t);
+        prop_assert!(state.velocity.is_finite(), "velocity at state {} is {}", i, state.velocity);
+        prop_assert!(state.acceleration.is_finite(), "acceleration at state {} is {}", i, state.acceleration);
+        prop_assert!(state.curvature.is_finite(), "curvature at state {} is {}", i, state.curvature);
+        prop_assert!(state.pose.translation().x().is_finite() && state.pose.translation().y().is_finite());
+
+        prop_assert!(state.velocity.abs() <= config.max_velocity + TOLERANCE,
+            "velocity {} at state {} exceeds max velocity {}", state.velocity, i, config.max_velocity);
+        prop_assert!(state.acceleration.abs() <= config.max_acceleration * (1. + RELATIVE_ACCELERATION_TOLERANCE),
+            "acceleration {} at state {} exceeds max acceleration {}", state.acceleration, i, config.max_acceleration);
+        if config.reversed {
+            prop_assert!(state.velocity <= TOLERANCE, "reversed trajectory drives forwards at state {}", i);
+        } else {
+            prop_assert!(state.velocity >= -TOLERANCE, "trajectory drives backwards at state {}", i);
+        }
+    }
+
+    for pair in states.windows(2) {
+        prop_assert!(pair[1].t >= pair[0].t, "time goes backwards from {} to {}", pair[0].t, pair[1].t);
+    }
+
+    let end_velocity = states.last().unwrap().velocity.abs();
+    prop_assert!(end_velocity <= config.end_velocity + TOLERANCE,
+        "ends at {} m/s instead of {} m/s", end_velocity, config.end_velocity);
+    Ok(())
+}
+
+proptest! {
+    #[test]
+    fn generated_trajectories_respect_config(waypoints in waypoints(), config in config()) {
+        match generate(waypoints, config) {
+            Ok(trajectory) => check_invariants(&trajectory, config)?,
+            // Randomly placed waypoints can legitimately describe a spline
+            // that can't be parameterized.
+            Err(GenerationError::MalformedSpline(_)) => {},
+            Err(err) => prop_assert!(false, "{}", err)
+        }
+    }
+
+    #[test]
+    fn near_coincident_waypoints_fail_cleanly(waypoints in near_coincident_waypoints(), config in config()) {
+        match generate(waypoints, config) {
+            Ok(trajectory) => check_invariants(&trajectory, config)?,
+            Err(GenerationError::MalformedSpline(_)) => {},
+            Err(err) => prop_assert!(false, "{}", err)
+        }
+    }
+
+    #[test]
+    fn sampling_stays_within_trajectory(waypoints in waypoints(), config in config(), fraction in 0.0..1.0) {
+        if let Ok(trajectory) = generate(waypoints, config) {
+            let sample = trajectory.sample(fraction * trajectory.total_time());
+            prop_assert!(sample.t >= 0. && sample.t <= *trajectory.total_time());
+            prop_assert!(sample.velocity.is_finite());
+            prop_assert!(sample.velocity.abs() <= config.max_velocity + TOLERANCE);
+        }
+    }
+}
+
+#[test]
+fn cancellation_stops_a_single_spline() {
+    let start = Pose2d::new(Translation2d::new(0., 0.), Rotation2d::from_degrees(0.));
+    let end = Pose2d::new(Translation2d::new(8., 4.), Rotation2d::from_degrees(90.));
+    let spline = quintic_spline_between(&start, &end);
+    assert!(try_parameterize(&spline, None, None, &CancellationToken::new()).is_ok());
+
+    // Cancelled before the first arc is split, so it stops without finishing the spline.
+    let cancel = CancellationToken::new();
+    cancel.cancel();
+    assert!(matches!(try_parameterize(&spline, None, None, &cancel), Err(GenerationError::Cancelled(_))));
+}
@@ -0,0 +1,68 @@
+//! Tests for the statistics reported about generated trajectories.
+
+use clairvoyance::kinematics::DifferentialDriveKinematics;
+use clairvoyance::trajectory::{Trajectory, TrajectoryConfig};
+use clairvoyance::trajectory::constraints::DifferentialDriveKinematicsConstraint;
+use clairvoyance::trajectory::report::{Limit, TrajectoryReport};
+use clairvoyance::trajectory::trajectory_generator::generate_trajectory;
+
+mod common;
+
+use common::pose;
+
+#[test]
+fn straight_line_is_a_trapezoid() {
+    let trajectory = generate_trajectory(vec![pose(0., 0., 0.), pose(10., 0., 0.)], TrajectoryConfig::new(3., 3.));
+    let report = TrajectoryReport::new(&trajectory, &TrajectoryConfig::new(3., 3.));
+
+    assert!((report.total_distance - 10.).abs() < 1E-6);
+    assert!((report.max_velocity - 3.).abs() < 1E-6);
+    assert!((report.max_acceleration - 3.).abs() < 1E-6);
+    // 1.5 m to get up to speed and 1.5 m to stop leaves 7 m at 3 m/s.
+    assert!((report.time_at_max_velocity - 7. / 3.).abs() < 0.05);
+    assert!(report.tightest_turn.is_none());
+    assert!(report.feasible);
+
+    assert_eq!(report.limits.len(), trajectory.num_states());
+    assert_eq!(report.limits[0], Some(Limit::StartVelocity));
+    assert_eq!(*report.limits.last().unwrap(), Some(Limit::EndVelocity));
+    // It speeds up for the first second, cruises, then slows down for the last.
+    let total_time = *trajectory.total_time();
+    assert_eq!(limit_at(&trajectory, &report, 0.5), Some(Limit::MaxAcceleration));
+    assert_eq!(limit_at(&trajectory, &report, total_time / 2.), Some(Limit::MaxVelocity));
+    assert_eq!(limit_at(&trajectory, &report, total_time - 0.5), Some(Limit::MaxDeceleration));
+}
+
+#[test]
+fn reports_limiting_constraints_and_the_tightest_turn() {
+    let waypoints = vec![pose(1., 1., 0.), pose(4., 1., 0.), pose(5., 2.5, 90.), pose(3., 4., 180.)];
+    let config = || {
+        let mut config = TrajectoryConfig::new(3., 3.);
+        config.constraints.push(Box::new(DifferentialDriveKinematicsConstraint::new(DifferentialDriveKinematics::new(0.6), 3.)));
+        config
+    };
+    let trajectory = generate_trajectory(waypoints, config());
+    let report = TrajectoryReport::new(&trajectory, &config());
+
+    assert!(report.feasible);
+    // The wheels only cap the speed in the turns, so the straight start is
+    // limited by the acceleration.
+    assert_eq!(limit_at(&trajectory, &report, 0.25), Some(Limit::MaxAcceleration));
+    let turn = report.tightest_turn.unwrap();
+    assert_eq!(limit_at(&trajectory, &report, turn.t),
+        Some(Limit::Constraint { name: "Differential drive kinematics".to_string() }));
+    assert!((turn.curvature.abs() - report.max_curvature).abs() < 1E-9);
+    assert!((turn.radius * report.max_curvature - 1.).abs() < 1E-9);
+}
+
+#[test]
+fn flags_trajectories_that_break_the_config() {
+    let trajectory = generate_trajectory(vec![pose(0., 0., 0.), pose(5., 0., 0.)], TrajectoryConfig::new(3., 3.));
+    assert!(!TrajectoryReport::new(&trajectory, &TrajectoryConfig::new(2., 3.)).feasible);
+}
+
+/** What the report says limits the first state at or after the time */
+fn limit_at(trajectory: &Trajectory, report: &TrajectoryReport, t: f64) -> Option<Limit> {
+    let i = trajectory.states().iter().position(|state| state.t >= t).unwrap();
+    report.limits[i].clone()
+}
@@ -0,0 +1,148 @@
+//! Tests for working out which AprilTags the robot's camera can see.
+
+use std::fs;
+
+use clairvoyance::auto::{generate_auto, try_generate_auto_on, Auto, Headings};
+use clairvoyance::field::{AprilTag, Field, FieldError, FieldSettings};
+use clairvoyance::geometry::{Pose2d, Rotation2d, Translation2d};
+use clairvoyance::robot::RobotConfig;
+use clairvoyance::trajectory::TrajectoryConfig;
+use clairvoyance::trajectory::cancellation::CancellationToken;
+use clairvoyance::trajectory::trajectory_generator::generate_trajectory;
+use clairvoyance::vision::{tag_visibility, Camera};
+
+mod common;
+
+use common::{auto, path, pose, temp_dir};
+
+fn camera() -> Camera {
+    Camera {
+        x: 0.3,
+        y: 0.,
+        height: 0.5,
+        yaw: 0.,
+        pitch: 0.,
+        horizontal_fov: 70.,
+        vertical_fov: 50.,
+        max_distance: 5.
+    }
+}
+
+/** A tag at the origin facing along the x axis, at the height of the camera */
+fn tag() -> AprilTag {
+    AprilTag { id: 1, pose: pose(0., 0., 0.), height: 0.5 }
+}
+
+#[test]
+fn sees_tags_in_front_and_in_range() {
+    let camera = camera();
+    assert!(camera.sees(&pose(3., 0.5, 180.), &tag()));
+    // Behind the robot, out of range and outside the field of view.
+    assert!(!camera.sees(&pose(3., 0.5, 0.), &tag()));
+    assert!(!camera.sees(&pose(6., 0., 180.), &tag()));
+    assert!(!camera.sees(&pose(3., 0., 130.), &tag()));
+    // Too far above the camera for how little it tilts up.
+    assert!(!camera.sees(&pose(1., 0., 180.), &AprilTag { height: 2., ..tag() }));
+}
+
+#[test]
+fn does_not_see_tags_from_behind_or_edge_on() {
+    let camera = camera();
+    assert!(!camera.sees(&pose(-3., 0., 0.), &tag()));
+    assert!(!camera.sees(&pose(0.5, 3., -90.), &tag()));
+}
+
+#[test]
+fn heading_points_an_offset_camera_at_the_tag() {
+    // A camera on the back left corner facing backwards.
+    let camera = Camera { x: -0.35, y: 0.3, yaw: 180., ..camera() };
+    let translation = Translation2d::new(2., 1.5);
+    let heading = camera.heading_facing(&translation, &tag());
+    let robot_pose = Pose2d::new(translation, heading);
+    assert!(camera.sees(&robot_pose, &tag()));
+
+    let from_camera = *tag().pose.translation() - camera.position(&robot_pose);
+    let direction = from_camera.y().atan2(*from_camera.x());
+    let camera_yaw = heading.radians() + 180_f64.to_radians();
+    assert!((direction - camera_yaw).sin().abs() < 1E-6);
+}
+
+#[test]
+fn reads_wpilib_layouts() {
+    let layout = r#"{
+        "tags": [
+            {
+                "ID": 7,
+                "pose": {
+                    "translation": { "x": 1.0, "y": 2.0, "z": 0.5 },
+                    "rotation": { "quaternion": { "W": 0.0, "X": 0.0, "Y": 0.0, "Z": 1.0 } }
+                }
+            }
+        ],
+        "field": { "length": 16.54175, "width": 8.0137 }
+    }"#;
+    let field = Field::default().with_april_tag_layout(layout).unwrap();
+    assert_eq!(field.april_tags.len(), 1);
+    let tag = field.april_tag(7).unwrap();
+    assert_eq!(*tag.pose.translation(), Translation2d::new(1., 2.));
+    assert_eq!(tag.height, 0.5);
+    assert!((tag.pose.rotation().radians().abs() - 180_f64.to_radians()).abs() < 1E-9);
+}
+
+#[test]
+fn facing_a_tag_keeps_it_in_view() {
+    let field = Field::default();
+    let robot = RobotConfig { camera: Some(camera()), ..RobotConfig::default() };
+    // Drives away from the blue grid facing forwards, so the tags are behind
+    // the robot unless it turns to face them.
+    let trajectory = generate_trajectory(vec![pose(2.5, 1., 0.), pose(4.5, 1., 0.)], TrajectoryConfig::new(2., 2.));
+
+    let forwards = Headings::holding(Rotation2d::default());
+    let holding = tag_visibility(&trajectory, &robot, &field.april_tags, None, &forwards);
+    assert_eq!(holding.len(), trajectory.num_states());
+    assert!(holding.iter().all(|visibility| visibility.tags.is_empty()));
+
+    let facing = tag_visibility(&trajectory, &robot, &field.april_tags, Some(8), &forwards);
+    assert!(facing.iter().all(|visibility| visibility.tags.contains(&8)));
+
+    // Turning around along the path brings the tag into view too.
+    let backwards = Headings::holding(Rotation2d::from_degrees(180.));
+    let turned = tag_visibility(&trajectory, &robot, &field.april_tags, None, &backwards);
+    assert!(turned.iter().all(|visibility| visibility.tags.contains(&8)));
+
+    let blind = tag_visibility(&trajectory, &RobotConfig::default(), &field.april_tags, Some(8), &forwards);
+    assert!(blind.is_empty());
+}
+
+#[test]
+fn checks_autos_against_a_saved_layout() {
+    let directory = temp_dir();
+    let layout = directory.join("layout.json");
+    fs::write(&layout, r#"{
+        "tags": [
+            {
+                "ID": 20,
+                "pose": {
+                    "translation": { "x": 1.0, "y": 1.0, "z": 0.5 },
+                    "rotation": { "quaternion": { "W": 1.0, "X": 0.0, "Y": 0.0, "Z": 0.0 } }
+                }
+            }
+        ]
+    }"#).unwrap();
+    let settings_path = directory.join("config").join("field-settings.json");
+    FieldSettings { april_tag_layout: Some(layout) }.save(&settings_path).unwrap();
+    let field = FieldSettings::load(&settings_path).field().unwrap();
+    assert_eq!(field.april_tags.len(), 1);
+
+    let mut facing = path(&[(2.5, 1.), (4.5, 1.)]);
+    facing.config.face_tag = Some(20);
+    let facing = Auto { robot: RobotConfig { camera: Some(camera()), ..RobotConfig::default() }, ..auto("Facing", vec![facing]) };
+    let generated = try_generate_auto_on(&facing, &field, &CancellationToken::new()).unwrap();
+    assert!(generated.tag_visibility()[0].iter().all(|visibility| visibility.tags == [20]));
+    // The built in field doesn't have the tag, so the robot keeps facing away from the grid.
+    assert!(generate_auto(&facing).tag_visibility()[0].iter().all(|visibility| visibility.tags.is_empty()));
+
+    assert!(FieldSettings::load(&directory.join("missing.json")).field().unwrap().april_tags.len() > 1);
+    let missing = FieldSettings { april_tag_layout: Some(directory.join("missing.json")) };
+    assert!(matches!(missing.field(), Err(FieldError::Io(_))));
+}
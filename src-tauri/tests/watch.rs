@@ -0,0 +1,89 @@
+//! Tests for noticing auto files changed outside of the app.
+
+use std::fs;
+
+use clairvoyance::auto::Auto;
+use clairvoyance::field::Field;
+use clairvoyance::project::Project;
+use clairvoyance::project::watch::{ChangeKind, ProjectWatcher};
+
+mod common;
+
+use common::{path, temp_dir};
+
+fn auto(title: &str) -> Auto {
+    common::auto(title, vec![path(&[(1., 1.), (3., 1.)])])
+}
+
+fn kinds(watcher: &ProjectWatcher) -> Vec<(String, ChangeKind)> {
+    let (_, changes) = watcher.poll().unwrap();
+    changes.into_iter().map(|change| (change.file_name, change.kind)).collect()
+}
+
+#[test]
+fn reports_changes_made_outside_of_the_app() {
+    let project = Project::open(&temp_dir()).unwrap();
+    project.save_auto(Some("left.json"), &auto("Left")).unwrap();
+    project.save_auto(Some("right.json"), &auto("Right")).unwrap();
+    let watcher = ProjectWatcher::default();
+    assert!(watcher.poll().is_none());
+    watcher.watch(project.clone());
+    assert!(kinds(&watcher).is_empty());
+
+    // Like a git pull that edits one auto, adds another and deletes a third.
+    let other = Project::open(project.root()).unwrap();
+    other.save_auto(Some("left.json"), &auto("Left, but better")).unwrap();
+    other.save_auto(Some("middle.json"), &auto("Middle")).unwrap();
+    fs::remove_file(project.root().join("autos").join("right.json")).unwrap();
+    let (_, changes) = watcher.poll().unwrap();
+    assert_eq!(changes.iter().map(|change| change.kind).collect::<Vec<_>>(), [ChangeKind::Modified, ChangeKind::Created, ChangeKind::Removed]);
+    assert_eq!(changes[0].auto.as_ref().unwrap().title, "Left, but better");
+    assert!(kinds(&watcher).is_empty());
+
+    // Half written files are reported with why they can't be read.
+    fs::write(project.root().join("autos").join("left.json"), "{").unwrap();
+    let (_, changes) = watcher.poll().unwrap();
+    assert!(changes[0].auto.is_none() && changes[0].error.is_some());
+}
+
+#[test]
+fn ignores_the_apps_own_saves() {
+    let project = Project::open(&temp_dir()).unwrap();
+    let watcher = ProjectWatcher::default();
+    watcher.watch(project.clone());
+
+    let file_name = watcher.save_auto(&project, None, &auto("Mine")).unwrap();
+    assert!(kinds(&watcher).is_empty());
+    let other_file = project.save_auto(Some("other.json"), &auto("Other")).unwrap();
+    watcher.record(project.root(), &other_file);
+    assert!(kinds(&watcher).is_empty());
+
+    // Saves to a project that isn't being watched are ignored.
+    let elsewhere = Project::open(&temp_dir()).unwrap();
+    watcher.save_auto(&elsewhere, Some(&file_name), &auto("Elsewhere")).unwrap();
+    watcher.record(elsewhere.root(), &file_name);
+    project.save_auto(Some(&file_name), &auto("Theirs")).unwrap();
+    assert_eq!(kinds(&watcher), [(file_name, ChangeKind::Modified)]);
+}
+
+#[test]
+fn generates_the_trajectories_of_changed_autos() {
+    let project = Project::open(&temp_dir()).unwrap();
+    project.save_auto(Some("gone.json"), &auto("Gone")).unwrap();
+    let watcher = ProjectWatcher::default();
+    watcher.watch(project.clone());
+
+    project.save_auto(Some("new.json"), &auto("New")).unwrap();
+    fs::remove_file(project.root().join("autos").join("gone.json")).unwrap();
+    let (_, mut changes) = watcher.poll().unwrap();
+    assert!(changes.iter().all(|change| change.trajectory.is_none()));
+    for change in &mut changes {
+        change.generate_on(&Field::default());
+    }
+
+    assert_eq!(changes[0].kind, ChangeKind::Removed);
+    assert!(changes[0].trajectory.is_none());
+    let trajectory = changes[1].trajectory.as_ref().expect("generated the new auto");
+    assert_eq!(trajectory.paths().len(), 1);
+    assert!((trajectory.paths()[0].states().last().unwrap().pose.translation().x() - 3.).abs() < 1E-6);
+}
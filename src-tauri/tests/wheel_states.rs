@@ -0,0 +1,106 @@
+//! Checks the wheel states worked out along a trajectory against what the
+//! robot's kinematics say they should be.
+
+use clairvoyance::auto::{generate_auto, Auto, AutoConfig, AutoPath, Headings, PathConfig, Waypoint};
+use clairvoyance::geometry::Rotation2d;
+use clairvoyance::kinematics::WheelStates;
+use clairvoyance::robot::{DrivetrainType, RobotConfig};
+use clairvoyance::trajectory::{Trajectory, TrajectoryConfig};
+use clairvoyance::trajectory::trajectory_generator::generate_trajectory;
+
+mod common;
+
+use common::{auto, path, pose};
+
+const TOLERANCE: f64 = 1E-9;
+
+fn s_curve() -> Trajectory {
+    generate_trajectory(vec![pose(1., 1., 0.), pose(3., 2., 45.), pose(5., 3., 0.)], TrajectoryConfig::new(3., 3.))
+}
+
+#[test]
+fn swerve_modules_point_along_the_path() {
+    let robot = RobotConfig::default();
+    let trajectory = s_curve();
+    let wheel_states = robot.wheel_states(&trajectory, &Headings::holding(Rotation2d::default()));
+    assert_eq!(wheel_states.len(), trajectory.states().len());
+
+    for (state, wheels) in trajectory.states().iter().zip(&wheel_states) {
+        let WheelStates::Swerve { modules } = wheels else { panic!("expected swerve module states") };
+        for module in modules {
+            // Without rotation every module drives the robot's velocity in the
+            // direction of travel, relative to the heading of 0.
+            assert!((module.speed - state.velocity.abs()).abs() < TOLERANCE);
+            if state.velocity > TOLERANCE {
+                let difference = module.angle.radians() - state.pose.rotation().radians();
+                assert!(difference.sin().abs() < 1E-6 && difference.cos() > 0.);
+            }
+        }
+    }
+}
+
+#[test]
+fn swerve_modules_turn_the_robot_between_waypoint_orientations() {
+    let mut turning = path(&[(1., 1.), (4., 1.)]);
+    turning.waypoints[1].psi = Some(90.);
+    let robot = RobotConfig::default();
+    let generated = generate_auto(&auto("Turning", vec![turning]));
+    let (trajectory, headings) = (&generated.paths()[0], &generated.headings()[0]);
+    let omega = 90_f64.to_radians() / *trajectory.total_time();
+    // Front left, front right, back left and back right, relative to the center.
+    let (x, y) = (robot.wheelbase / 2., robot.track_width / 2.);
+    let positions = [(x, y), (x, -y), (-x, y), (-x, -y)];
+
+    let mut speeds_differ = false;
+    for (state, wheels) in trajectory.states().iter().zip(&generated.wheel_states()[0]) {
+        let WheelStates::Swerve { modules } = wheels else { panic!("expected swerve module states") };
+        if state.t < *trajectory.total_time() {
+            assert!((headings.angular_velocity(state.t) - omega).abs() < TOLERANCE);
+        }
+        // Every module drives the robot's velocity relative to where it faces,
+        // plus its part of the turn.
+        let heading = headings.at(state.t);
+        let (vx, vy) = (state.velocity * heading.cos(), -state.velocity * heading.sin());
+        let turn = headings.angular_velocity(state.t);
+        for (module, (x, y)) in modules.iter().zip(positions) {
+            let (expected_x, expected_y) = (vx - turn * y, vy + turn * x);
+            assert!((module.speed * module.angle.cos() - expected_x).abs() < 1E-6);
+            assert!((module.speed * module.angle.sin() - expected_y).abs() < 1E-6);
+        }
+        speeds_differ |= (modules[0].speed - modules[3].speed).abs() > 0.1;
+    }
+    assert!(speeds_differ);
+}
+
+#[test]
+fn differential_wheels_average_to_chassis_speed() {
+    let robot = RobotConfig {
+        drivetrain: DrivetrainType::Differential { max_wheel_speed: 4., feedforward: None, max_voltage: 10. },
+        ..RobotConfig::default()
+    };
+    let trajectory = s_curve();
+    let wheel_states = robot.wheel_states(&trajectory, &Headings::holding(Rotation2d::default()));
+
+    for (state, wheels) in trajectory.states().iter().zip(&wheel_states) {
+        let WheelStates::Differential { left, right } = *wheels else { panic!("expected differential wheel speeds") };
+        assert!(((left + right) / 2. - state.velocity).abs() < TOLERANCE);
+        assert!(((right - left) / robot.track_width - state.velocity * state.curvature).abs() < TOLERANCE);
+    }
+}
+
+#[test]
+fn paths_that_do_nothing_have_no_wheel_states() {
+    assert!(RobotConfig::default().wheel_states(&Trajectory::do_nothing(), &Headings::holding(Rotation2d::default())).is_empty());
+
+    // A path with a single waypoint, like the one a new auto starts with, doesn't go anywhere.
+    let waypoint = Waypoint { x: 1., y: 1., th: Some(0.), psi: Some(0.), stop: false, hidden: false };
+    let auto = Auto {
+        title: "New Auto".to_string(),
+        paths: vec![AutoPath { waypoints: vec![waypoint], config: PathConfig::default(), hidden: false }],
+        config: AutoConfig { max_velocity: 3., max_acceleration: 3., reversed: false },
+        robot: RobotConfig::default()
+    };
+    let generated = generate_auto(&auto);
+    assert_eq!(generated.paths()[0].num_states(), 0);
+    assert!(generated.wheel_states()[0].is_empty());
+}
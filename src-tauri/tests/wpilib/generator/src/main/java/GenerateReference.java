@@ -0,0 +1,52 @@
+import com.fasterxml.jackson.databind.JsonNode;
+import com.fasterxml.jackson.databind.ObjectMapper;
+import edu.wpi.first.math.geometry.Pose2d;
+import edu.wpi.first.math.geometry.Rotation2d;
+import edu.wpi.first.math.trajectory.Trajectory;
+import edu.wpi.first.math.trajectory.TrajectoryConfig;
+import edu.wpi.first.math.trajectory.TrajectoryGenerator;
+import edu.wpi.first.math.trajectory.TrajectoryUtil;
+import java.io.File;
+import java.nio.file.Files;
+import java.util.ArrayList;
+import java.util.List;
+
+/**
+ * Writes the trajectory WPILib generates for every case in cases.json to
+ * reference/[name].json, in the format of TrajectoryUtil.serializeTrajectory.
+ */
+public class GenerateReference {
+  public static void main(String[] args) throws Exception {
+    File casesFile = new File(args[0]);
+    File referenceDir = new File(args[1]);
+    referenceDir.mkdirs();
+
+    JsonNode cases = new ObjectMapper().readTree(casesFile);
+    for (JsonNode testCase : cases) {
+      List<Pose2d> waypoints = new ArrayList<>();
+      for (JsonNode waypoint : testCase.get("waypoints")) {
+        waypoints.add(
+            new Pose2d(
+                waypoint.get("x").asDouble(),
+                waypoint.get("y").asDouble(),
+                Rotation2d.fromDegrees(waypoint.get("degrees").asDouble())));
+      }
+
+      JsonNode configNode = testCase.get("config");
+      TrajectoryConfig config =
+          new TrajectoryConfig(
+                  configNode.get("maxVelocity").asDouble(),
+                  configNode.get("maxAcceleration").asDouble())
+              .setStartVelocity(configNode.get("startVelocity").asDouble())
+              .setEndVelocity(configNode.get("endVelocity").asDouble())
+              .setReversed(configNode.get("reversed").asBoolean());
+
+      Trajectory trajectory = TrajectoryGenerator.generateTrajectory(waypoints, config);
+      String name = testCase.get("name").asText();
+      Files.writeString(
+          new File(referenceDir, name + ".json").toPath(),
+          TrajectoryUtil.serializeTrajectory(trajectory));
+      System.out.println("Wrote " + name + " with " + trajectory.getStates().size() + " states");
+    }
+  }
+}
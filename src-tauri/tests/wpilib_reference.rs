@@ -0,0 +1,160 @@
+//! Compares generated trajectories against the ones WPILib generates for the
+//! same waypoints and config. The reference trajectories live in
+//! tests/wpilib/reference and are written by the generator in
+//! tests/wpilib/generator. Generating them needs WPILib, so the comparison
+//! only runs with `cargo test -- --ignored`, which CI does right after
+//! running the generator.
+
+use std::f64::consts::PI;
+use std::fs;
+use std::path::PathBuf;
+
+use serde::Deserialize;
+
+use clairvoyance::geometry::{Pose2d, Rotation2d, Translation2d};
+use clairvoyance::trajectory::{Trajectory, TrajectoryConfig};
+use clairvoyance::trajectory::trajectory_generator::generate_trajectory;
+
+const POSITION_TOLERANCE: f64 = 1E-3;
+const ANGLE_TOLERANCE: f64 = 1E-3;
+const VELOCITY_TOLERANCE: f64 = 1E-3;
+const TIME_TOLERANCE: f64 = 1E-3;
+
+#[derive(Deserialize)]
+struct Waypoint {
+    x: f64,
+    y: f64,
+    degrees: f64
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Config {
+    max_velocity: f64,
+    max_acceleration: f64,
+    start_velocity: f64,
+    end_velocity: f64,
+    reversed: bool
+}
+
+#[derive(Deserialize)]
+struct Case {
+    name: String,
+    waypoints: Vec<Waypoint>,
+    config: Config
+}
+
+/** A trajectory state as serialized by WPILib's TrajectoryUtil. */
+#[derive(Deserialize)]
+struct ReferenceState {
+    time: f64,
+    velocity: f64,
+    pose: Pose2d
+}
+
+fn wpilib_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join("wpilib")
+}
+
+fn cases() -> Vec<Case> {
+    let contents = fs::read_to_string(wpilib_dir().join("cases.json")).unwrap();
+    serde_json::from_str(&contents).unwrap()
+}
+
+fn generate(case: &Case) -> Trajectory {
+    let waypoints = case.waypoints.iter()
+        .map(|waypoint| Pose2d::new(
+            Translation2d::new(waypoint.x, waypoint.y),
+            Rotation2d::from_degrees(waypoint.degrees)))
+        .collect();
+    let mut config = TrajectoryConfig::new(case.config.max_velocity, case.config.max_acceleration);
+    config.start_velocity = case.config.start_velocity;
+    config.end_velocity = case.config.end_velocity;
+    config.reversed = case.config.reversed;
+    generate_trajectory(waypoints, config)
+}
+
+fn angle_between(a: f64, b: f64) -> f64 {
+    let difference = (a - b).rem_euclid(2. * PI);
+    difference.min(2. * PI - difference)
+}
+
+fn reference_path(case: &Case) -> PathBuf {
+    wpilib_dir().join("reference").join(format!("{}.json", case.name))
+}
+
+#[test]
+#[ignore = "needs the WPILib reference trajectories, run the generator in tests/wpilib/generator first"]
+fn matches_wpilib_reference_trajectories() {
+    let cases = cases();
+    let missing: Vec<&str> = cases.iter()
+        .filter(|case| !reference_path(case).is_file())
+        .map(|case| case.name.as_str())
+        .collect();
+    assert!(missing.is_empty(),
+        "No WPILib reference for {:?}. Run `gradle run` in tests/wpilib/generator and commit the files it writes.",
+        missing);
+
+    for case in &cases {
+        let contents = fs::read_to_string(reference_path(case)).unwrap();
+        let reference: Vec<ReferenceState> = serde_json::from_str(&contents).unwrap();
+        let trajectory = generate(case);
+
+        let reference_total_time = reference.last().unwrap().time;
+        assert!(
+            (trajectory.total_time() - reference_total_time).abs() < TIME_TOLERANCE,
+            "{}: total time {} does not match WPILib's {}",
+            case.name, trajectory.total_time(), reference_total_time);
+
+        for expected in &reference {
+            let actual = trajectory.sample(expected.time);
+            let distance = actual.pose.translation().distance_to(expected.pose.translation());
+            assert!(distance < POSITION_TOLERANCE,
+                "{}: pose at t={} is {} m away from WPILib's", case.name, expected.time, distance);
+            let angle = angle_between(*actual.pose.rotation().radians(), *expected.pose.rotation().radians());
+            assert!(angle < ANGLE_TOLERANCE,
+                "{}: heading at t={} is {} rad off from WPILib's", case.name, expected.time, angle);
+            assert!((actual.velocity - expected.velocity).abs() < VELOCITY_TOLERANCE,
+                "{}: velocity at t={} is {} but WPILib's is {}",
+                case.name, expected.time, actual.velocity, expected.velocity);
+        }
+    }
+}
+
+/*
+ * The cases below don't need a reference file since the expected profile of a
+ * straight line can be worked out by hand.
+ */
+
+fn straight_line(length: f64, max_velocity: f64, max_acceleration: f64) -> Trajectory {
+    let waypoints = vec![
+        Pose2d::new(Translation2d::new(0., 0.), Rotation2d::from_degrees(0.)),
+        Pose2d::new(Translation2d::new(length, 0.), Rotation2d::from_degrees(0.))
+    ];
+    generate_trajectory(waypoints, TrajectoryConfig::new(max_velocity, max_acceleration))
+}
+
+#[test]
+fn straight_line_follows_trapezoidal_profile() {
+    let trajectory = straight_line(8., 3., 3.);
+
+    // One second each to accelerate and decelerate over 1.5 m, then cruise
+    // the remaining 5 m at 3 m/s.
+    assert!((trajectory.total_time() - (1. + 5. / 3. + 1.)).abs() < TIME_TOLERANCE);
+    let middle = trajectory.sample(trajectory.total_time() / 2.);
+    assert!((middle.velocity - 3.).abs() < VELOCITY_TOLERANCE);
+    assert!((*middle.pose.translation().x() - 4.).abs() < POSITION_TOLERANCE);
+}
+
+#[test]
+fn short_straight_line_follows_triangular_profile() {
+    let trajectory = straight_line(4., 4.5, 3.5);
+
+    // Never reaches max velocity: accelerate for half the distance, then
+    // decelerate for the other half.
+    let half_time = (4. / 3.5_f64).sqrt();
+    assert!((trajectory.total_time() - 2. * half_time).abs() < TIME_TOLERANCE);
+    let peak = trajectory.sample(half_time);
+    assert!((peak.velocity - 3.5 * half_time).abs() < VELOCITY_TOLERANCE);
+    assert!(trajectory.states().iter().all(|state| state.velocity <= 4.5));
+}
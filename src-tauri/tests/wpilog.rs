@@ -0,0 +1,190 @@
+//! Tests for reading WPILib data logs and lining them up with autos.
+
+use clairvoyance::auto::{generate_auto, Auto};
+use clairvoyance::geometry::Pose2d;
+use clairvoyance::replay::{replay, ReplayOptions};
+use clairvoyance::replay::wpilog::{WpiLog, WpiLogError};
+
+mod common;
+
+use common::{auto, path};
+
+/**
+ * Writes a data log the way WPILib's DataLog does, with the shortest
+ * integers that fit every record.
+ */
+struct LogWriter {
+    bytes: Vec<u8>
+}
+impl LogWriter {
+    fn new() -> Self {
+        let mut bytes = b"WPILOG".to_vec();
+        bytes.extend_from_slice(&0x0100u16.to_le_bytes());
+        let extra_header = b"team 1234";
+        bytes.extend_from_slice(&(extra_header.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(extra_header);
+        Self { bytes }
+    }
+
+    fn record(&mut self, id: u32, timestamp: u64, data: &[u8]) {
+        let length = |value: u64| (1..8).find(|bytes| value >> (8 * bytes) == 0).unwrap_or(8);
+        let (id_length, size_length, timestamp_length) = (length(u64::from(id)), length(data.len() as u64), length(timestamp));
+        self.bytes.push(((id_length - 1) | (size_length - 1) << 2 | (timestamp_length - 1) << 4) as u8);
+        self.bytes.extend_from_slice(&u64::from(id).to_le_bytes()[..id_length]);
+        self.bytes.extend_from_slice(&(data.len() as u64).to_le_bytes()[..size_length]);
+        self.bytes.extend_from_slice(&timestamp.to_le_bytes()[..timestamp_length]);
+        self.bytes.extend_from_slice(data);
+    }
+
+    fn start(&mut self, id: u32, name: &str, type_name: &str) {
+        let mut data = vec![0];
+        data.extend_from_slice(&id.to_le_bytes());
+        for text in [name, type_name, ""] {
+            data.extend_from_slice(&(text.len() as u32).to_le_bytes());
+            data.extend_from_slice(text.as_bytes());
+        }
+        self.record(0, 0, &data);
+    }
+
+    fn finish(&mut self, id: u32) {
+        let mut data = vec![1];
+        data.extend_from_slice(&id.to_le_bytes());
+        self.record(0, 0, &data);
+    }
+
+    fn doubles(&mut self, id: u32, seconds: f64, values: &[f64]) {
+        let data: Vec<u8> = values.iter().flat_map(|value| value.to_le_bytes()).collect();
+        self.record(id, (seconds * 1E6).round() as u64, &data);
+    }
+
+    fn boolean(&mut self, id: u32, seconds: f64, value: bool) {
+        self.record(id, (seconds * 1E6).round() as u64, &[u8::from(value)]);
+    }
+}
+
+fn two_piece() -> Auto {
+    auto("Two Piece", vec![path(&[(1., 1.), (3., 2.), (5., 2.)])])
+}
+
+/** Logs the robot following the auto perfectly, starting after it waits for the given time */
+fn log_auto(log: &mut LogWriter, id: u32, start_time: f64) {
+    let generated = generate_auto(&two_piece());
+    let trajectory = &generated.paths()[0];
+    let end_time = start_time + trajectory.total_time();
+    let mut time = 0.;
+    while time < end_time + 1. {
+        let pose: Pose2d = trajectory.sample(time - start_time).pose;
+        let translation = pose.translation();
+        log.doubles(id, time, &[*translation.x(), *translation.y(), *pose.rotation().radians()]);
+        time += 0.02;
+    }
+}
+
+#[test]
+fn reads_entries_and_records_with_reused_ids() {
+    let mut log = LogWriter::new();
+    log.start(1, "/Odometry/Pose", "struct:Pose2d");
+    log.doubles(1, 1., &[1., 2., std::f64::consts::FRAC_PI_2]);
+    log.finish(1);
+    log.doubles(1, 2., &[9., 9., 0.]);
+    log.start(1, "NT:/SmartDashboard/Field/Robot", "double[]");
+    log.doubles(1, 3., &[4., 5., 90.]);
+    log.start(2, "/Arm/Angle", "double");
+    log.doubles(2, 3., &[0.5]);
+
+    let parsed = WpiLog::parse(&log.bytes).unwrap();
+    assert_eq!(parsed.extra_header, "team 1234");
+    assert_eq!(parsed.entries.len(), 3);
+    assert_eq!(parsed.records.len(), 3);
+    let names: Vec<&str> = parsed.pose_entries().iter().map(|entry| entry.name.as_str()).collect();
+    assert_eq!(names, ["/Odometry/Pose", "NT:/SmartDashboard/Field/Robot"]);
+
+    let poses = parsed.poses("/Odometry/Pose", false);
+    assert_eq!(poses.len(), 1);
+    assert_eq!(poses[0].0, 1.);
+    assert!((poses[0].1.rotation().radians() - std::f64::consts::FRAC_PI_2).abs() < 1E-12);
+    let field = parsed.poses("NT:/SmartDashboard/Field/Robot", true);
+    assert!((field[0].1.rotation().radians() - std::f64::consts::FRAC_PI_2).abs() < 1E-12);
+}
+
+#[test]
+fn reads_up_to_a_cut_off_last_record() {
+    let mut log = LogWriter::new();
+    log.start(1, "/Odometry/Pose", "struct:Pose2d");
+    log.doubles(1, 1., &[1., 2., 0.]);
+    log.doubles(1, 2., &[3., 4., 0.]);
+    let length = log.bytes.len();
+
+    for cut in [1, 4, 25] {
+        let parsed = WpiLog::parse(&log.bytes[..length - cut]).unwrap();
+        assert_eq!(parsed.entries.len(), 1);
+        let poses = parsed.poses("/Odometry/Pose", false);
+        assert_eq!(poses.len(), 1, "cut {} bytes", cut);
+        assert_eq!(poses[0].0, 1.);
+    }
+}
+
+#[test]
+fn rejects_logs_it_cannot_read() {
+    assert_eq!(WpiLog::parse(b"[{\"time\": 0}]").unwrap_err(), WpiLogError::NotAWpiLog);
+    let mut log = LogWriter::new();
+    log.start(1, "/Odometry/Pose", "struct:Pose2d");
+    log.doubles(1, 1., &[1., 2., 0.]);
+    assert!(matches!(WpiLog::parse(&log.bytes[..10]), Err(WpiLogError::Truncated(_))));
+    let mut newer = log.bytes;
+    newer[7] = 2;
+    assert_eq!(WpiLog::parse(&newer).unwrap_err(), WpiLogError::UnsupportedVersion(0x0200));
+}
+
+#[test]
+fn lines_up_with_the_start_of_autonomous() {
+    let mut log = LogWriter::new();
+    log.start(1, "DS:enabled", "boolean");
+    log.start(2, "DS:autonomous", "boolean");
+    log.start(3, "/Odometry/Pose", "struct:Pose2d");
+    log.boolean(2, 0.5, true);
+    log.boolean(1, 2., true);
+    log_auto(&mut log, 3, 2.);
+
+    let parsed = WpiLog::parse(&log.bytes).unwrap();
+    let replayed = replay(&parsed, &two_piece(), &ReplayOptions::default()).unwrap();
+    assert_eq!(replayed.entry(), "/Odometry/Pose");
+    assert!((replayed.start_time() - 2.).abs() < 1E-9);
+    assert!(!replayed.states().is_empty());
+    assert!(replayed.states().iter().all(|state| state.t >= 0.));
+    assert!(*replayed.max_error() < 0.01, "max error {} m", replayed.max_error());
+}
+
+#[test]
+fn falls_back_to_when_the_robot_starts_moving() {
+    let mut log = LogWriter::new();
+    log.start(1, "NT:/SmartDashboard/Field/Robot", "double[]");
+    log_auto(&mut log, 1, 3.);
+
+    let parsed = WpiLog::parse(&log.bytes).unwrap();
+    let replayed = replay(&parsed, &two_piece(), &ReplayOptions::default()).unwrap();
+    assert!((replayed.start_time() - 3.).abs() < 0.15, "started at {} s", replayed.start_time());
+    assert!(*replayed.rms_error() < 0.3, "RMS error {} m", replayed.rms_error());
+
+    let options = ReplayOptions { start_time: Some(1.), ..ReplayOptions::default() };
+    let early = replay(&parsed, &two_piece(), &options).unwrap();
+    assert!(*early.max_error() > 1.);
+}
+
+#[test]
+fn plans_the_way_a_swerve_drive_turns() {
+    let mut log = LogWriter::new();
+    log.start(1, "/Odometry/Pose", "struct:Pose2d");
+    log_auto(&mut log, 1, 0.);
+
+    let mut turning = two_piece();
+    turning.paths[0].waypoints[2].psi = Some(90.);
+    let parsed = WpiLog::parse(&log.bytes).unwrap();
+    let options = ReplayOptions { start_time: Some(0.), ..ReplayOptions::default() };
+    let replayed = replay(&parsed, &turning, &options).unwrap();
+    let rotation = |i: usize| replayed.states()[i].planned.rotation().radians().to_degrees();
+    assert!(rotation(0).abs() < 1E-9);
+    // The last pose is logged up to a loop period before the end.
+    assert!((rotation(replayed.states().len() - 1) - 90.).abs() < 1.);
+    assert!(*replayed.max_error() < 0.01);
+}
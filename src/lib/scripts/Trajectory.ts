@@ -1,5 +1,6 @@
-import { degreesToRadians, radiansToDegrees, parseAndRound } from '$lib/scripts/math';
-import { camelCaseToTitleCase, toCamelCase } from '$lib/scripts/text-manipulation';
+import { degreesToRadians, radiansToDegrees } from '$lib/scripts/math';
+import { toCamelCase } from '$lib/scripts/text-manipulation';
+import { listen } from '@tauri-apps/api/event';
 import { invoke } from '@tauri-apps/api/tauri';
 
 enum DetailType {
@@ -36,6 +37,45 @@ type WaypointDetail = BaseDetail & {
 
 type Detail = RobotConfigDetail | AutoConfigDetail | PathConfigDetail | WaypointDetail;
 
+interface SimpleMotorFeedforward {
+	/** Static gain in volts */
+	ks: number;
+	/** Velocity gain in V/(m/s) */
+	kv: number;
+	/** Acceleration gain in V/(m/s^2) */
+	ka: number;
+}
+
+type DrivetrainType =
+	| { type: 'swerve' }
+	| {
+			type: 'differential';
+			/** Top speed of either side of the drivetrain in m/s */
+			maxWheelSpeed: number;
+			/** Limits paths to what the motors can do with maxVoltage if set */
+			feedforward: SimpleMotorFeedforward | null;
+			/** Voltage available to the drivetrain */
+			maxVoltage: number;
+	  };
+
+/** A camera on the robot, positioned relative to the center of the robot on the carpet */
+interface Camera {
+	/** Meters towards the front of the robot */
+	x: number;
+	/** Meters towards the left of the robot */
+	y: number;
+	/** Height of the lens in meters */
+	height: number;
+	/** Degrees counterclockwise from the front of the robot */
+	yaw: number;
+	/** Degrees tilted up from level */
+	pitch: number;
+	horizontalFov: number;
+	verticalFov: number;
+	/** The furthest away in meters a tag can be detected */
+	maxDistance: number;
+}
+
 interface RobotConfig {
 	/** Width of robot in meters */
 	width: number;
@@ -45,6 +85,13 @@ interface RobotConfig {
 	wheelbase: number;
 	/** Track width of robot in meters */
 	trackWidth: number;
+	drivetrain: DrivetrainType;
+	/** Diameter of the drive wheels in meters */
+	wheelDiameter: number;
+	/** Rotations of a drive motor for every rotation of its wheel */
+	gearRatio: number;
+	/** The camera used to see AprilTags, if the robot has one */
+	camera?: Camera | null;
 }
 
 /** Autonomous Routine */
@@ -55,6 +102,14 @@ interface Auto {
 		path: Path;
 		config: PathConfig;
 		hidden: boolean;
+		/** What the wheels do at every state of the path, only available on Tauri */
+		wheelStates?: WheelStates[];
+		/** Where the robot first hits a field element, only available on Tauri */
+		collision?: Collision | null;
+		/** The robot following the path in simulation, only available on Tauri */
+		simulated?: SimulationResult | null;
+		/** Which AprilTags the camera sees along the path, only available on Tauri */
+		tagVisibility?: TagVisibility[];
 	}[];
 	config: AutoConfig;
 }
@@ -71,6 +126,12 @@ interface PathConfig {
 	maxVelocity?: number;
 	maxAcceleration?: number;
 	reversed?: boolean;
+	/**
+	 * Id of an AprilTag a swerve drive keeps its camera facing along the path. Only the tag
+	 * visibility shown on the field turns towards it, exported and deployed paths still follow the
+	 * waypoints' orientations.
+	 */
+	faceTag?: number;
 }
 
 interface Waypoint {
@@ -125,8 +186,312 @@ interface Path {
 	initialPose: Pose;
 }
 
+interface PathTiming {
+	start_time: number;
+	end_time: number;
+}
+
+interface SwerveModuleState {
+	/** Speed of the wheel in m/s */
+	speed: number;
+	/** Angle of the module relative to the front of the robot */
+	angle: { radians: number };
+}
+
+/** Modules are in the order front left, front right, back left, back right */
+type WheelStates =
+	| { type: 'swerve'; modules: SwerveModuleState[] }
+	| { type: 'differential'; left: number; right: number };
+
+interface Collision {
+	/** Time since the start of the path in seconds */
+	t: number;
+	/** Name of the field element, or "Field perimeter" if the robot leaves the field */
+	obstacle: string;
+	pose: Pose;
+	/** Corners of the robot's footprint when it collides */
+	footprint: { x: number; y: number }[];
+}
+
+type Limit =
+	| { type: 'startVelocity' }
+	| { type: 'endVelocity' }
+	| { type: 'maxVelocity' }
+	| { type: 'maxAcceleration' }
+	| { type: 'maxDeceleration' }
+	| { type: 'constraint'; name: string };
+
+/** Statistics about a generated path, along with what limits it at every state */
+interface TrajectoryReport {
+	total_distance: number;
+	total_time: number;
+	max_velocity: number;
+	max_acceleration: number;
+	max_curvature: number;
+	/** How long the robot drives at the configured max velocity, in seconds */
+	time_at_max_velocity: number;
+	/** The sharpest turn, or null if the path is straight */
+	tightest_turn: { t: number; pose: Pose; curvature: number; radius: number } | null;
+	/** What keeps the robot from going faster at every state, if anything does */
+	limits: (Limit | null)[];
+	/** Whether every state stays within the config and constraints */
+	feasible: boolean;
+}
+
+interface TagVisibility {
+	/** Time since the start of the path in seconds */
+	t: number;
+	/** Heading of the robot, which faces the path's target tag if it has one */
+	heading: { radians: number };
+	/** Ids of the visible tags */
+	tags: number[];
+}
+
+interface Landmark {
+	name: string;
+	pose: Pose;
+}
+
+interface AprilTag {
+	id: number;
+	/** Where the tag is, facing the direction the tag faces */
+	pose: Pose;
+	/** Height of the center of the tag above the carpet in meters */
+	height: number;
+}
+
+interface Field {
+	version: number;
+	season: number;
+	name: string;
+	length: number;
+	width: number;
+	/** Field elements the robot can't drive through */
+	obstacles: { name: string; shape: { vertices: { x: number; y: number }[] } }[];
+	/** Field elements the robot can drive over slowly, avoided when finding paths */
+	slow_zones: { name: string; shape: { vertices: { x: number; y: number }[] } }[];
+	scoring_locations: Landmark[];
+	named_poses: Landmark[];
+	april_tags: AprilTag[];
+}
+
+interface AutoPaths {
+	paths: Path[];
+	/** What the wheels do at every state of every path, only available on Tauri */
+	wheel_states?: WheelStates[][];
+	/** Where the robot first hits a field element on every path, only available on Tauri */
+	collisions?: (Collision | null)[];
+	/** Which AprilTags the camera sees at every state of every path, only available on Tauri */
+	tag_visibility?: TagVisibility[][];
+	/** Statistics about every path, only available on Tauri */
+	reports?: TrajectoryReport[];
+	timeline: PathTiming[];
+	total_time: number;
+}
+
+/** An auto file in a project, only available on Tauri */
+interface AutoEntry {
+	file_name: string;
+	/** Title of the auto, or null if the file couldn't be read */
+	title: string | null;
+	/** Why the file couldn't be read, if it couldn't */
+	error: string | null;
+}
+
+/** An auto file changed outside of the app, like by a git pull or a text editor */
+interface AutoChange {
+	fileName: string;
+	kind: 'created' | 'modified' | 'removed';
+	/** The auto as it is now, or null if it was removed or can't be read yet */
+	loaded: ProjectAuto | null;
+}
+
+/** What gets written to a robot project for every auto */
+type ExportFormat = 'wpilibJson' | 'javaConstants' | 'binary';
+
+/** The files written by the last sync, only available on Tauri */
+interface DeployManifest {
+	version: number;
+	format: ExportFormat;
+	/** Package of the generated Java classes, only set for Java */
+	package?: string;
+	/** Files written for every auto by the auto's file name */
+	autos: Record<string, string[]>;
+	/** Stale files deleted by the sync */
+	removed: string[];
+}
+
+/** A language robot code can be generated in */
+type CodeLanguage = 'java' | 'cpp' | 'python';
+
+/** How to generate the code for an auto */
+interface CodegenOptions {
+	language: CodeLanguage;
+	/** Name of the generated class, or null to name it after the auto */
+	className: string | null;
+	/** Names of the paths to use for their constants, in order */
+	pathNames: string[];
+	/** Code for the kinematics set on the trajectory configs, like Constants.kSwerveKinematics */
+	kinematics: string | null;
+	/** Package of the generated class, only used for Java */
+	package: string | null;
+}
+
+/** How to write the trajectories of an auto as CSV */
+interface CsvOptions {
+	delimiter: string;
+	distanceUnit: 'meters' | 'feet' | 'inches';
+	angleUnit: 'degrees' | 'radians';
+	/** Seconds between rows, or null for a row at every state of the trajectory */
+	samplePeriod: number | null;
+}
+
+/** Vendor layout of a motion profile run on motor controllers */
+type MotionProfileFormat = 'ctre' | 'rev';
+
+/** Phoenix 5 takes encoder counts and counts per 100 ms, Phoenix 6 rotations and rotations per second */
+type PhoenixVersion = 'phoenix5' | 'phoenix6';
+
+/** How to turn an auto into a motion profile for every drive motor */
+interface MotionProfileOptions {
+	format: MotionProfileFormat;
+	/** Time between points in milliseconds */
+	periodMs: number;
+	/** Which Phoenix a CTRE profile is for */
+	phoenixVersion: PhoenixVersion;
+	/** Encoder counts per motor rotation, used for Phoenix 5 */
+	countsPerRev: number;
+}
+
+interface GeneratedCode {
+	file_name: string;
+	code: string;
+}
+
+/** Something in imported code that was skipped or may have been misread */
+interface ImportWarning {
+	line: number | null;
+	message: string;
+}
+
+/** Autos read from Java source, one for every class with paths in it */
+interface JavaImport {
+	autos: { auto: Auto; robot: RobotConfig }[];
+	warnings: ImportWarning[];
+}
+
+/** An auto loaded from a project along with the robot that drives it */
+interface ProjectAuto {
+	auto: Auto;
+	robot: RobotConfig;
+	fileName: string;
+}
+
+type Controller =
+	| { type: 'holonomic'; translationKp: number; rotationKp: number }
+	| { type: 'ramsete'; b: number; zeta: number };
+
+interface SimulationConfig {
+	controller: Controller;
+	robot: RobotConfig;
+	/** Top speed of a single wheel or module in m/s */
+	maxWheelSpeed: number;
+	/** Time in seconds the drivetrain takes to get about two thirds of the way to a commanded speed */
+	timeConstant: number;
+	/** Simulation timestep in seconds */
+	dt: number;
+}
+
+interface SimulatedState {
+	t: number;
+	pose: Pose;
+	/** Distance between the simulated and planned position in meters */
+	error: number;
+}
+
+interface SimulationResult {
+	states: SimulatedState[];
+	max_error: number;
+	rms_error: number;
+}
+
+/** An entry of a WPILib data log that the robot's poses can be read from */
+interface LogEntry {
+	name: string;
+	/** Type of the logged data, like struct:Pose2d or double[] */
+	type_name: string;
+	metadata: string;
+}
+
+/** How to line up a WPILib data log with an auto */
+interface ReplayOptions {
+	/** Entry the poses were logged to, or null for the first one in the log */
+	entry: string | null;
+	/**
+	 * When the auto started in seconds since the log started, or null for when the robot was
+	 * enabled in autonomous
+	 */
+	startTime: number | null;
+	/** Whether rotations in double arrays are in degrees, as Field2d logs them */
+	degrees: boolean;
+}
+
+interface ReplayedState {
+	/** Time since the start of the auto */
+	t: number;
+	pose: Pose;
+	planned: Pose;
+	/** Distance between the logged and planned position in meters */
+	error: number;
+}
+
+/** Poses logged while the robot drove an auto, lined up with its paths */
+interface Replay {
+	entry: string;
+	/** When the auto started in seconds since the log started */
+	start_time: number;
+	states: ReplayedState[];
+	max_error: number;
+	rms_error: number;
+}
+
+/** Version of the auto file layout, matching the backend's */
+const AUTO_SCHEMA_VERSION = 1;
+
 let ON_TAURI = false;
-let getPath = fetchPath;
+let getPath: (waypoints: Waypoint[], config: AutoConfig, pathId?: string) => Promise<Path> =
+	fetchPath;
+/** The robot's drivetrain constraints are only respected on Tauri */
+let getAutoPaths: (auto: Auto, robot: RobotConfig) => Promise<AutoPaths | null> = fetchAutoPaths;
+/**
+ * Like getPath, but reuses unchanged segments of the last path generated with the same id. The
+ * robot's drivetrain constraints are only respected on Tauri, like getAutoPaths.
+ */
+let getPathIncremental: (
+	waypoints: Waypoint[],
+	config: AutoConfig,
+	pathId: string,
+	robot: RobotConfig
+) => Promise<Path | null> = (waypoints, config, pathId) => getPath(waypoints, config, pathId);
+/**
+ * Simulates a robot following the path, turning between its waypoints' orientations, only possible
+ * on Tauri
+ */
+let simulatePath: (
+	path: Auto['paths'][number],
+	config: SimulationConfig
+) => Promise<SimulationResult | null> = () => Promise.resolve(null);
+/** Finds waypoints around the field elements from start to goal, only possible on Tauri */
+let findPath: (
+	start: Waypoint,
+	goal: Waypoint,
+	config: AutoConfig,
+	robot: RobotConfig
+) => Promise<Waypoint[] | null> = () => Promise.resolve(null);
+/** Gets the field for a season, or the current one, only possible on Tauri */
+let getField: (season?: number) => Promise<Field | null> = () => Promise.resolve(null);
+/** Increases with every generation request so the backend can cancel stale ones */
+let generationRequestId = 0;
 
 async function initTauriTrajectoryApi() {
 	ON_TAURI =
@@ -141,8 +506,10 @@ async function initTauriTrajectoryApi() {
 			: 'Was not able to invoke tauri function, will use TrajectoryAPI'
 	);
 	if (ON_TAURI) {
-		getPath = (waypoints, config) =>
-			invoke<Path>('generate_trajectory_tauri', {
+		getPath = (waypoints, config, pathId = 'default') =>
+			invoke<Path>('generate_trajectory_async', {
+				pathId,
+				requestId: ++generationRequestId,
 				waypoints: waypointsToPoses(waypoints),
 				config: {
 					max_acceleration: config.maxAcceleration,
@@ -151,6 +518,69 @@ async function initTauriTrajectoryApi() {
 					start_velocity: 0,
 					end_velocity: 0
 				}
+			}).catch((error) => {
+				console.debug(error);
+				return getDoNothingPath();
+			});
+		getPathIncremental = (waypoints, config, pathId, robot) =>
+			invoke<Path>('generate_path_incremental', {
+				pathId,
+				requestId: ++generationRequestId,
+				waypoints: waypointsToPoses(waypoints),
+				config: {
+					max_acceleration: config.maxAcceleration,
+					max_velocity: config.maxVelocity,
+					reversed: config.reversed,
+					start_velocity: 0,
+					end_velocity: 0
+				},
+				robot
+			}).catch((error) => {
+				// a newer request for this path is already being generated
+				console.debug(error);
+				return null;
+			});
+		getAutoPaths = (auto, robot) =>
+			invoke<AutoPaths>('generate_auto_async', {
+				autoId: auto.title,
+				requestId: ++generationRequestId,
+				auto: { ...strippedAuto(auto), robot }
+			}).catch((error) => {
+				// a newer request for this auto is already being generated
+				console.debug(error);
+				return null;
+			});
+		findPath = (start, goal, config, robot) =>
+			invoke<Pose[]>('find_path', {
+				start: waypointsToPoses([start])[0],
+				goal: waypointsToPoses([goal])[0],
+				robot,
+				config: {
+					max_acceleration: config.maxAcceleration,
+					max_velocity: config.maxVelocity,
+					reversed: config.reversed,
+					start_velocity: 0,
+					end_velocity: 0
+				}
+			})
+				.then((poses) => posesToWaypoints(poses))
+				.catch((error) => {
+					console.error(error);
+					return null;
+				});
+		getField = (season) =>
+			invoke<Field>('get_field', { season: season ?? null }).catch((error) => {
+				console.error(error);
+				return null;
+			});
+		simulatePath = (path, config) =>
+			invoke<SimulationResult>('simulate_trajectory', {
+				trajectory: path.path,
+				path: { waypoints: path.waypoints, config: path.config, hidden: path.hidden },
+				config
+			}).catch((error) => {
+				console.error(error);
+				return null;
 			});
 	}
 }
@@ -162,7 +592,50 @@ function getDefaultRobotConfig(): RobotConfig {
 		width: 0.762, // 30 in
 		length: 0.762, // 30 in
 		trackWidth: 0.6096, // 24 in
-		wheelbase: 0.6096 // 24 in
+		wheelbase: 0.6096, // 24 in
+		drivetrain: { type: 'swerve' },
+		wheelDiameter: 0.1016, // 4 in
+		gearRatio: 6.75
+	};
+}
+
+function getDefaultCamera(): Camera {
+	return {
+		x: 0,
+		y: 0,
+		height: 0.5,
+		yaw: 0,
+		pitch: 15,
+		horizontalFov: 70,
+		verticalFov: 50,
+		maxDistance: 5
+	};
+}
+
+function getDefaultDifferentialDrivetrain(): DrivetrainType {
+	return {
+		type: 'differential',
+		maxWheelSpeed: 4,
+		feedforward: null,
+		maxVoltage: 10
+	};
+}
+
+/** Gains of a typical kitbot drivetrain, from WPILib's examples, to start tuning from */
+function getDefaultFeedforward(): SimpleMotorFeedforward {
+	return { ks: 0.22, kv: 1.98, ka: 0.2 };
+}
+
+function getDefaultSimulationConfig(robot = getDefaultRobotConfig()): SimulationConfig {
+	const differential = robot.drivetrain.type === 'differential';
+	return {
+		controller: differential
+			? { type: 'ramsete', b: 2, zeta: 0.7 }
+			: { type: 'holonomic', translationKp: 1, rotationKp: 1 },
+		robot,
+		maxWheelSpeed: robot.drivetrain.type === 'differential' ? robot.drivetrain.maxWheelSpeed : 4.5,
+		timeConstant: 0.1,
+		dt: 0.02
 	};
 }
 
@@ -226,6 +699,34 @@ function waypointsToPoses(waypoints: Waypoint[]) {
 	});
 }
 
+/**
+ * The scoring location or named pose closest to a point, if any is within the distance.
+ * Used to snap waypoints onto the field's landmarks.
+ */
+function nearestLandmark(field: Field, x: number, y: number, maxDistance: number) {
+	let nearest: Landmark | null = null;
+	let nearestDistance = maxDistance;
+	for (const landmark of [...field.scoring_locations, ...field.named_poses]) {
+		const { translation } = landmark.pose;
+		const distance = Math.hypot(translation.x - x, translation.y - y);
+		if (distance <= nearestDistance) {
+			nearest = landmark;
+			nearestDistance = distance;
+		}
+	}
+	return nearest;
+}
+
+function posesToWaypoints(poses: Pose[]): Waypoint[] {
+	return poses.map((pose) => ({
+		x: pose.translation.x,
+		y: pose.translation.y,
+		th: radiansToDegrees(pose.rotation.radians),
+		psi: 0,
+		hidden: false
+	}));
+}
+
 async function fetchPath(waypoints: Waypoint[], config: AutoConfig): Promise<Path> {
 	if (waypoints.length < 2) {
 		return new Promise((resolve) => resolve(getDoNothingPath()));
@@ -266,6 +767,378 @@ async function fetchPath(waypoints: Waypoint[], config: AutoConfig): Promise<Pat
 	}
 }
 
+async function fetchAutoPaths(auto: Auto): Promise<AutoPaths> {
+	const paths = await Promise.all(
+		auto.paths.map((path) => getPath(path.waypoints, { ...auto.config, ...path.config }))
+	);
+	const timeline: PathTiming[] = [];
+	let t = 0;
+	for (const path of paths) {
+		timeline.push({ start_time: t, end_time: t + path.totalTimeSeconds });
+		t += path.totalTimeSeconds;
+	}
+	return { paths, timeline, total_time: t };
+}
+
+/**
+ * Removes the generated paths from an auto so they aren't sent back to the backend
+ * @param auto
+ */
+function strippedAuto(auto: Auto) {
+	return {
+		...auto,
+		paths: auto.paths.map(({ waypoints, config, hidden }) => ({ waypoints, config, hidden }))
+	};
+}
+
+/**
+ * Opens the project in an existing folder, setting it up if it's new, and lists its autos. Only
+ * possible on Tauri, and rejects with the reason if the project can't be opened.
+ * @param root
+ */
+function openProject(root: string): Promise<AutoEntry[]> {
+	return invoke<AutoEntry[]>('open_project', { root });
+}
+
+/** Gets the WPILib AprilTag layout file used instead of the field's own tags, if one was loaded */
+function getAprilTagLayout(): Promise<string | null> {
+	return invoke<string | null>('april_tag_layout').catch((error) => {
+		console.error(error);
+		return null;
+	});
+}
+
+/**
+ * Uses the tags in a WPILib AprilTag layout file instead of the field's own, or the field's own
+ * tags again if no file is given. The choice is remembered, and it's only possible on Tauri.
+ * Rejects with the reason if the file can't be read.
+ * @param path
+ * @returns the field with the new tags
+ */
+function loadAprilTagLayout(path: string | null): Promise<Field> {
+	return invoke<Field>('load_april_tag_layout', { path });
+}
+
+/** Gets the recently opened projects, newest first, only possible on Tauri */
+function getRecentProjects(): Promise<string[]> {
+	return invoke<string[]>('recent_projects').catch((error) => {
+		console.error(error);
+		return [];
+	});
+}
+
+/** An auto as the backend reads and writes it, with the robot and without generated paths */
+type SavedAuto = ReturnType<typeof strippedAuto> & { robot: RobotConfig };
+
+/**
+ * Turns an auto read by the backend back into one the editor can show
+ * @param saved
+ */
+function fromSavedAuto({ robot, ...saved }: SavedAuto): { auto: Auto; robot: RobotConfig } {
+	return {
+		auto: {
+			...saved,
+			paths: saved.paths.map((path) => ({
+				...path,
+				// unset options are sent as null, which would override the auto config
+				config: Object.fromEntries(
+					Object.entries(path.config).filter(([, value]) => value != null)
+				) as PathConfig,
+				waypoints: path.waypoints.map((waypoint) => ({
+					...waypoint,
+					th: waypoint.th ?? 0,
+					psi: waypoint.psi ?? 0
+				})),
+				path: getDoNothingPath()
+			}))
+		},
+		robot
+	};
+}
+
+/**
+ * Fills in the paths of a loaded auto with the trajectories the backend generated for it, so it
+ * can be shown before it's generated again
+ * @param loaded
+ * @param generated
+ */
+function withAutoPaths(
+	loaded: { auto: Auto; robot: RobotConfig },
+	generated: AutoPaths | null
+): { auto: Auto; robot: RobotConfig } {
+	generated?.paths.forEach((path, i) => {
+		const autoPath = loaded.auto.paths[i];
+		if (!autoPath) return;
+		autoPath.path = path;
+		autoPath.wheelStates = generated.wheel_states?.[i];
+		autoPath.collision = generated.collisions?.[i];
+		autoPath.tagVisibility = generated.tag_visibility?.[i];
+	});
+	return loaded;
+}
+
+/**
+ * Loads an auto from a project, only possible on Tauri
+ * @param root
+ * @param fileName
+ */
+async function loadProjectAuto(root: string, fileName: string): Promise<ProjectAuto> {
+	const saved = await invoke<SavedAuto>('load_auto', { root, fileName });
+	return { ...fromSavedAuto(saved), fileName };
+}
+
+/**
+ * Reads an exported auto file of any version. On Tauri the backend upgrades and validates it,
+ * otherwise only its shape is checked. Rejects with what's wrong with the file.
+ * @param json
+ * @returns the auto, along with the robot if the file has one
+ */
+async function importAuto(json: string): Promise<{ auto: Auto; robot: RobotConfig | null }> {
+	if (ON_TAURI) {
+		try {
+			return fromSavedAuto(await invoke<SavedAuto>('import_auto', { json }));
+		} catch (error) {
+			throw new Error(String(error));
+		}
+	}
+	const { robot, ...auto } = JSON.parse(json) as Auto & { robot?: RobotConfig };
+	checkAutoShape(auto);
+	// robots saved before a field was added get its default
+	return { auto, robot: robot ? { ...getDefaultRobotConfig(), ...robot } : null };
+}
+
+/**
+ * Reads the paths out of Java source, like generated code, SwerveTrajectoryWaypoint arrays or
+ * TrajectoryGenerator.generateTrajectory calls, with every class becoming an auto. Only possible
+ * on Tauri, and rejects with why no paths could be found.
+ * @param source
+ * @returns the autos, along with what in the code was skipped
+ */
+async function importJava(source: string): Promise<JavaImport> {
+	try {
+		const { autos, warnings } = await invoke<{ autos: SavedAuto[]; warnings: ImportWarning[] }>(
+			'import_java',
+			{ source }
+		);
+		return { autos: autos.map(fromSavedAuto), warnings };
+	} catch (error) {
+		throw new Error(String(error));
+	}
+}
+
+/**
+ * Checks an auto has everything the editor needs, for when the backend isn't available to
+ * validate it. Throws an error describing the first problem found.
+ * @param auto
+ */
+function checkAutoShape(auto: Auto) {
+	if (!auto.config) throw new Error('Auto has no config');
+	if (typeof auto.config !== 'object') throw new Error('Auto config is not an object');
+	if (typeof auto.config.maxAcceleration !== 'number')
+		throw new Error('Auto config maxAcceleration is not a number');
+	if (typeof auto.config.maxVelocity !== 'number')
+		throw new Error('Auto config maxVelocity is not a number');
+	if (typeof auto.config.reversed !== 'boolean')
+		throw new Error('Auto config reversed is not a boolean');
+
+	if (!Array.isArray(auto.paths)) throw new Error('Auto paths is not an array');
+	if (!auto.paths.length) throw new Error('Auto paths is empty');
+
+	auto.paths.forEach((path, i) => {
+		if (!Array.isArray(path.waypoints))
+			throw new Error(`Auto path at index ${i} has waypoints that are not an array`);
+		path.waypoints.forEach((waypoint, j) => {
+			if (typeof waypoint !== 'object')
+				throw new Error(
+					`Auto path at index ${i} has waypoint at index ${j} that is not an object`
+				);
+			if (typeof waypoint.x !== 'number' || typeof waypoint.y !== 'number')
+				throw new Error(
+					`Auto path at index ${i} has waypoint at index ${j} with non-numeric x or y`
+				);
+			if (typeof waypoint.th !== 'number') waypoint.th = 0;
+			if (typeof waypoint.psi !== 'number') waypoint.psi = 0;
+			waypoint.hidden = !!waypoint.hidden;
+		});
+		if (!path.config) path.config = {};
+		path.hidden = !!path.hidden;
+		// generated paths are always refetched after importing
+		path.path = getDoNothingPath();
+	});
+}
+
+/**
+ * Serializes an auto the way the backend saves it, so exported files can be imported anywhere
+ * @param auto
+ * @param robot
+ */
+function autoToJson(auto: Auto, robot: RobotConfig): string {
+	return JSON.stringify({ version: AUTO_SCHEMA_VERSION, ...strippedAuto(auto), robot }, null, 2);
+}
+
+/**
+ * Saves an auto into a project, replacing the file in one step. Only possible on Tauri.
+ * @param root
+ * @param fileName file to save to, or null to name it after the auto's title
+ * @returns the name of the file the auto was saved to
+ */
+function saveProjectAuto(
+	root: string,
+	fileName: string | null,
+	auto: Auto,
+	robot: RobotConfig
+): Promise<string> {
+	return invoke<string>('save_auto', { root, fileName, auto: { ...strippedAuto(auto), robot } });
+}
+
+/**
+ * Calls back whenever autos in the open project change outside of the app, with their paths already
+ * generated. Changes to files that can't be read yet are left out, since they're reported again
+ * once they're finished. Only possible on Tauri.
+ * @param callback given the project, what changed, and why the deploy folder couldn't be updated
+ * @returns a function to stop listening
+ */
+async function onAutosChanged(
+	callback: (root: string, changes: AutoChange[], deployError: string | null) => void
+): Promise<() => void> {
+	type Payload = {
+		root: string;
+		changes: {
+			file_name: string;
+			kind: AutoChange['kind'];
+			auto: SavedAuto | null;
+			error: string | null;
+			trajectory: AutoPaths | null;
+		}[];
+		deploy_error: string | null;
+	};
+	try {
+		return await listen<Payload>('autos-changed', ({ payload }) =>
+			callback(
+				payload.root,
+				payload.changes
+					.filter((change) => !change.error)
+					.map(({ file_name: fileName, kind, auto, trajectory }) => ({
+						fileName,
+						kind,
+						loaded: auto && { ...withAutoPaths(fromSavedAuto(auto), trajectory), fileName }
+					})),
+				payload.deploy_error
+			)
+		);
+	} catch (error) {
+		// not on Tauri, so there's nothing to listen to
+		console.debug(error);
+		return () => undefined;
+	}
+}
+
+/**
+ * Gets what the project's last sync wrote, including its format and Java package, or null if it
+ * was never synced. Only possible on Tauri.
+ * @param root
+ */
+function getDeployManifest(root: string): Promise<DeployManifest | null> {
+	return invoke<DeployManifest | null>('deploy_manifest', { root })
+		.catch((error) => {
+			console.error(error);
+			return null;
+		});
+}
+
+/**
+ * Writes every auto in the project to its robot code, removing files that are no longer
+ * generated. Java classes go in the package's folder under src/main/java, everything else in the
+ * deploy folder. Only possible on Tauri, and rejects with the reason if the sync fails.
+ * @param root
+ * @param format
+ * @param javaPackage Package of the generated Java classes, or null for frc.robot.autos
+ */
+function syncDeploy(
+	root: string,
+	format: ExportFormat,
+	javaPackage: string | null
+): Promise<DeployManifest> {
+	return invoke<DeployManifest>('sync_deploy', { root, format, package: javaPackage });
+}
+
+/**
+ * Generates Java, C++ or RobotPy code with the waypoints, stop points and trajectory configs of
+ * every path of an auto. Only possible on Tauri.
+ * @param auto
+ * @param robot
+ * @param options
+ */
+function generateCode(
+	auto: Auto,
+	robot: RobotConfig,
+	options: CodegenOptions
+): Promise<GeneratedCode> {
+	return invoke<GeneratedCode>('generate_code', { auto: { ...strippedAuto(auto), robot }, options });
+}
+
+/**
+ * Generates an auto and saves it as a CSV file with time, pose, rotation, velocity, acceleration,
+ * curvature and distance columns. Only possible on Tauri, and rejects with the reason if the file
+ * can't be written.
+ * @param path
+ * @param auto
+ * @param robot
+ * @param options
+ */
+function saveCsv(path: string, auto: Auto, robot: RobotConfig, options: CsvOptions): Promise<void> {
+	return invoke<void>('save_csv', { path, auto: { ...strippedAuto(auto), robot }, options });
+}
+
+/**
+ * Saves a motion profile for every drive motor of the robot into a directory, in the units of the
+ * motor controllers worked out with the robot's wheel diameter and gear ratio. Only possible on
+ * Tauri.
+ * @param directory
+ * @param auto
+ * @param robot
+ * @param options
+ * @returns the names of the files written
+ */
+function saveMotionProfiles(
+	directory: string,
+	auto: Auto,
+	robot: RobotConfig,
+	options: MotionProfileOptions
+): Promise<string[]> {
+	return invoke<string[]>('save_motion_profiles', {
+		directory,
+		auto: { ...strippedAuto(auto), robot },
+		options
+	});
+}
+
+/**
+ * Lists the entries of a WPILib data log that the robot's poses can be read from. Only possible on
+ * Tauri, and rejects with the reason if the log can't be read.
+ * @param path
+ */
+function getWpilogPoseEntries(path: string): Promise<LogEntry[]> {
+	return invoke<LogEntry[]>('wpilog_pose_entries', { path });
+}
+
+/**
+ * Reads the poses a WPILib data log has for the robot while it drove an auto, lined up in time with
+ * the auto's paths so they can be drawn over them. Only possible on Tauri.
+ * @param path
+ * @param auto
+ * @param robot
+ * @param options
+ */
+function replayWpilog(
+	path: string,
+	auto: Auto,
+	robot: RobotConfig,
+	options: ReplayOptions
+): Promise<Replay> {
+	return invoke<Replay>('replay_wpilog', { path, auto: { ...strippedAuto(auto), robot }, options });
+}
+
 /**
  * Creates a string representation of the path that is valid Java code.
  * @param points
@@ -295,135 +1168,86 @@ function pathToString(trajectory: Auto) {
 	return output.slice(0, -8) + '\n};\n';
 }
 
-/**
- * Given a WPILib angle string, returns the angle in degrees
- * @param angleStr a string containg an angle either in radians, or wrapped in a
- * WPILib rotation2d constructor such as new Rotation2d(angleRadians),
- * Rotation2d.fromDegrees(angleDegrees), or Rotation2d.fromRadians(angleRadians)
- * The string can contain the mathematical operations +, -, *, /, and parentheses,
- * as well as the constants Math.PI and fieldWidth
- */
-function parseWpilibAngle(angleStr: string) {
-	let angle: number;
-	if (/(?:new)?Rotation2d(?:\.fromRadians)?\(/g.test(angleStr)) {
-		angleStr = angleStr.slice(angleStr.indexOf('(') + 1, -1);
-		angle = radiansToDegrees(parseAndRound(angleStr));
-	} else if (/Rotation2d\.fromDegrees/g.test(angleStr)) {
-		angleStr = angleStr.slice(angleStr.indexOf('(') + 1, -1);
-		angle = parseAndRound(angleStr);
-	} else {
-		angle = radiansToDegrees(parseAndRound(angleStr));
-	}
-	return angle;
-}
-
-/**
- * Extracts trajectory waypoints from path code of the format generated by @function pathToString
- * @param pathCode
- */
-function stringToPaths(pathCode: string): Auto[] {
-	const pathCodeWithoutComments = pathCode.replace(/\/\/.*/g, '');
-	const paths = pathCodeWithoutComments.match(
-		/SwerveTrajectoryWaypoint\s*\[\s*\]\s*\w+\s*=\s*(?:new\s+SwerveTrajectoryWaypoint\[\]\s*)?\{(?:\s|\d|\w|[-+*(),./])+\};/g
-	);
-	console.debug(paths);
-
-	if (!paths) return [];
-
-	console.debug(`%cImporting ${paths.length} Path Groups`, 'color: cyan');
-
-	const points: Auto[] = [];
-	for (const path of paths) {
-		// remove comments
-
-		let title = path.match(/\s*\w+(?:\w|\d)?(?=\s*=)/g)?.[0].trim() ?? 'noTitleFound';
-		title = camelCaseToTitleCase(title);
-		const splitPath = path.split(/(?:\s*|,)?\s*new\s*SwerveTrajectoryWaypoint\(\s*/g);
-		console.debug(splitPath);
-		if (!splitPath) {
-			console.warn(`%cNo points found for path ${title}`, 'color: yellow');
-			continue;
-		}
-		const ptMatches = [];
-		for (const part of splitPath) {
-			if (part.includes('=')) continue;
-			let cleanPart = part.replace(/null|\s/g, '');
-			if (cleanPart.endsWith(')')) cleanPart = cleanPart.slice(0, -1);
-			else if (cleanPart.endsWith(')};')) cleanPart = cleanPart.slice(0, -3);
-			else if (cleanPart.endsWith('),')) cleanPart = cleanPart.slice(0, -2);
-			ptMatches.push(cleanPart.split(','));
-			if (part.includes('null')) {
-				ptMatches.push(['null']);
-			}
-		}
-		console.debug(ptMatches);
-		// const ptMatches = pathWithoutComments.matchAll(
-		// 	/(?:\s*new\s+SwerveTrajectoryWaypoint\s*\(\s*new\s+Translation2d\s*\((?<translationX>-?\d*\.\d+|\d+\.?)\s*,\s*(?<translationY>-?\d*\.\d+|\d+\.?)\s*\)\s*,\s*(?<orientation_constructor>Rotation2d\.fromDegrees|new\s+Rotation2d|Rotation2d\.fromRadians)\s*\((?<orientation>-?\d*\.\d+|\d+\.?)\s*\)\s*,\s*(?<heading_constructor>Rotation2d\.fromDegrees|new\s+Rotation2d|Rotation2d\.fromRadians)\s*\((?<heading>-?\d*\.\d+|\d+\.?)\s*\)\s*\)|null)+/g
-		// );
-		const waypoints: Waypoint[] = [];
-		let curPts = 0;
-		for (const point of ptMatches) {
-			console.debug(point);
-			if (point[0] === 'null') {
-				console.debug(`%cGot a subpath with ${curPts} points`, 'color: white');
-				waypoints[waypoints.length - 1].stop = true;
-				curPts = 0;
-				continue;
-			}
-
-			if (point.length !== 4) {
-				console.warn(`%cGot a point with ${point.length} values`, 'color: yellow');
-				continue;
-			}
-
-			const x = parseAndRound(point[0]);
-			const y = parseAndRound(point[1]);
-			const psi = parseWpilibAngle(point[2]);
-			const th = parseWpilibAngle(point[3]);
-
-			waypoints.push({
-				x,
-				y,
-				th,
-				psi,
-				hidden: false
-			});
-		}
-		if (curPts > 0) {
-			waypoints[waypoints.length - 1].stop = true;
-			console.debug(`%cGot a subpath with ${curPts} points`, 'color: white');
-		}
-		points.push({
-			title,
-			paths: [{ waypoints, path: getDoNothingPath(), config: {}, hidden: false }],
-			config: {
-				maxVelocity: 4.5,
-				maxAcceleration: 3.5,
-				reversed: false
-			}
-		});
-	}
-	return points;
-}
-
 export {
 	initTauriTrajectoryApi,
 	onTauri,
 	waypointsToPoses,
 	getPath,
+	getAutoPaths,
+	getPathIncremental,
+	simulatePath,
+	findPath,
+	getField,
+	getAprilTagLayout,
+	loadAprilTagLayout,
+	nearestLandmark,
+	openProject,
+	getRecentProjects,
+	loadProjectAuto,
+	saveProjectAuto,
+	importAuto,
+	importJava,
+	autoToJson,
+	getDeployManifest,
+	syncDeploy,
+	onAutosChanged,
+	generateCode,
+	saveCsv,
+	saveMotionProfiles,
+	getWpilogPoseEntries,
+	replayWpilog,
 	pathToString,
-	stringToPaths,
 	getDefaultRobotConfig,
+	getDefaultDifferentialDrivetrain,
+	getDefaultFeedforward,
+	getDefaultCamera,
+	getDefaultSimulationConfig,
 	getDefaultAuto,
 	getDoNothingPath,
 	type PathState,
 	type PathRequest,
 	type Path,
+	type PathTiming,
+	type AutoPaths,
+	type AutoEntry,
+	type ImportWarning,
+	type JavaImport,
+	type ProjectAuto,
+	type ExportFormat,
+	type DeployManifest,
+	type AutoChange,
+	type CodeLanguage,
+	type CodegenOptions,
+	type GeneratedCode,
+	type CsvOptions,
+	type MotionProfileFormat,
+	type MotionProfileOptions,
+	type PhoenixVersion,
+	type LogEntry,
+	type ReplayOptions,
+	type ReplayedState,
+	type Replay,
+	type SwerveModuleState,
+	type WheelStates,
+	type Collision,
+	type Camera,
+	type TagVisibility,
+	type Limit,
+	type TrajectoryReport,
+	type Field,
+	type Landmark,
+	type AprilTag,
+	type Controller,
+	type SimulationConfig,
+	type SimulatedState,
+	type SimulationResult,
 	type Pose,
 	type Waypoint,
 	type Auto,
 	type AutoConfig,
 	type RobotConfig,
+	type DrivetrainType,
+	type SimpleMotorFeedforward,
 	type PathConfig,
 	DetailType,
 	type Detail,
@@ -1,4 +1,4 @@
-import type { Auto, Path, Waypoint } from './Trajectory';
+import type { Auto, Collision, Path, Replay, SimulationResult, Waypoint } from './Trajectory';
 import { fieldLengthMeters, fieldWidthMeters } from '$lib/assets/field-data.json';
 import { degreesToRadians, radiansToDegrees, roundFloat } from '$lib/scripts/math';
 
@@ -34,7 +34,7 @@ class AutoCanvas {
 		this.ctx = canvas.getContext('2d') as CanvasRenderingContext2D;
 	}
 
-	public draw(auto: Auto, preview: Waypoint | null | undefined) {
+	public draw(auto: Auto, preview: Waypoint | null | undefined, replay: Replay | null = null) {
 		this.ctx.clearRect(0, 0, this.canvas.width, this.canvas.height);
 		for (const path of auto.paths) {
 			for (const waypoint of path.waypoints) {
@@ -42,7 +42,10 @@ class AutoCanvas {
 				this.drawWaypoint(waypoint);
 			}
 			this.drawPath(path.path);
+			if (path.simulated) this.drawSimulatedPath(path.simulated);
+			if (path.collision) this.drawCollision(path.collision);
 		}
+		if (replay) this.drawReplay(replay);
 
 		if (preview) {
 			this.drawWaypoint(preview);
@@ -168,6 +171,49 @@ class AutoCanvas {
 		this.ctx.stroke();
 	}
 
+	/** Draws where the simulated robot actually drove as a dashed line over the planned path */
+	private drawSimulatedPath(simulated: SimulationResult) {
+		if (!simulated.states.length) return;
+		this.ctx.beginPath();
+		this.ctx.lineWidth = 2;
+		this.ctx.strokeStyle = '#e879f9';
+		this.ctx.setLineDash([6, 6]);
+		for (const state of simulated.states) {
+			const { x, y } = this.fieldToCanvas(state.pose.translation.x, state.pose.translation.y);
+			this.ctx.lineTo(x, y);
+		}
+		this.ctx.stroke();
+		this.ctx.setLineDash([]);
+	}
+
+	/** Draws where the robot drove according to its log as a solid line over the planned path */
+	private drawReplay(replay: Replay) {
+		if (!replay.states.length) return;
+		this.ctx.beginPath();
+		this.ctx.lineWidth = 2;
+		this.ctx.strokeStyle = '#facc15';
+		for (const state of replay.states) {
+			const { x, y } = this.fieldToCanvas(state.pose.translation.x, state.pose.translation.y);
+			this.ctx.lineTo(x, y);
+		}
+		this.ctx.stroke();
+	}
+
+	/** Outlines the robot where it first hits a field element */
+	private drawCollision(collision: Collision) {
+		this.ctx.beginPath();
+		this.ctx.lineWidth = 3;
+		this.ctx.strokeStyle = '#ef4444';
+		for (const corner of collision.footprint) {
+			const { x, y } = this.fieldToCanvas(corner.x, corner.y);
+			this.ctx.lineTo(x, y);
+		}
+		this.ctx.closePath();
+		this.ctx.fillStyle = 'rgba(239, 68, 68, 0.25)';
+		this.ctx.fill();
+		this.ctx.stroke();
+	}
+
 	private drawArrow(xPx: number, yPx: number, thetaRads: number) {
 		this.ctx.translate(xPx, yPx);
 		this.ctx.rotate(thetaRads);